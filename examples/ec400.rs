@@ -12,7 +12,7 @@ use ethercrab::{
     ds402::{Ds402, Ds402Sm},
     error::Error,
     std::tx_rx_task,
-    Client, ClientConfig, PduStorage, SlaveGroupState, Timeouts,
+    Client, ClientConfig, Pdo, PdoAssignment, PdoEntry, PduStorage, SlaveGroupState, Timeouts,
 };
 use std::{
     sync::{
@@ -73,33 +73,34 @@ async fn main() -> Result<(), Error> {
         .await
         .expect("Init");
 
-    for slave in group.iter(&client) {
+    for mut slave in group.iter(&client) {
         if slave.name() == "ELP-EC400S" {
             // CSV described a bit better in section 7.6.2.2 Related Objects of the manual
-            slave.sdo_write(0x1600, 0, 0u8).await?;
-            // Control word, u16
-            // NOTE: The lower word specifies the field length
-            slave.sdo_write(0x1600, 1, 0x6040_0010u32).await?;
-            // Target velocity, i32
-            slave.sdo_write(0x1600, 2, 0x60ff_0020u32).await?;
-            slave.sdo_write(0x1600, 0, 2u8).await?;
-
-            slave.sdo_write(0x1a00, 0, 0u8).await?;
-            // Status word, u16
-            slave.sdo_write(0x1a00, 1, 0x6041_0010u32).await?;
-            // Actual position, i32
-            slave.sdo_write(0x1a00, 2, 0x6064_0020u32).await?;
-            // Actual velocity, i32
-            slave.sdo_write(0x1a00, 3, 0x606c_0020u32).await?;
-            slave.sdo_write(0x1a00, 0, 0x03u8).await?;
-
-            slave.sdo_write(0x1c12, 0, 0u8).await?;
-            slave.sdo_write(0x1c12, 1, 0x1600).await?;
-            slave.sdo_write(0x1c12, 0, 1u8).await?;
-
-            slave.sdo_write(0x1c13, 0, 0u8).await?;
-            slave.sdo_write(0x1c13, 1, 0x1a00).await?;
-            slave.sdo_write(0x1c13, 0, 1u8).await?;
+            slave
+                .configure_pdos(
+                    &PdoAssignment::new()
+                        .outputs(&[Pdo::new(
+                            0x1600,
+                            &[
+                                // Control word, u16
+                                PdoEntry::new(0x6040, 0, 16),
+                                // Target velocity, i32
+                                PdoEntry::new(0x60ff, 0, 32),
+                            ],
+                        )])
+                        .inputs(&[Pdo::new(
+                            0x1a00,
+                            &[
+                                // Status word, u16
+                                PdoEntry::new(0x6041, 0, 16),
+                                // Actual position, i32
+                                PdoEntry::new(0x6064, 0, 32),
+                                // Actual velocity, i32
+                                PdoEntry::new(0x606c, 0, 32),
+                            ],
+                        )]),
+                )
+                .await?;
 
             // Opmode - Cyclic Synchronous Position
             // slave.write_sdo(0x6060, 0, 0x08).await?;
@@ -230,5 +231,12 @@ async fn main() -> Result<(), Error> {
 
     log::info!("Drive is shut down");
 
+    // Release the borrow of the servo's slave device so the group can be moved back down
+    drop(servo);
+
+    group.into_init(&client).await?;
+
+    log::info!("Group is in INIT");
+
     Ok(())
 }
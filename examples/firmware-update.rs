@@ -0,0 +1,103 @@
+//! Update the firmware of a given sub device over FoE.
+//!
+//! The file name and password sent to the device are device specific. By default, the file name
+//! of the given firmware file and a password of `0` are used.
+
+use env_logger::Env;
+use ethercrab::{
+    error::Error, std::tx_rx_task, Client, ClientConfig, PduStorage, SlaveGroupState, Timeouts,
+};
+use std::path::PathBuf;
+
+/// Maximum number of slaves that can be stored. This must be a power of 2 greater than 1.
+const MAX_SLAVES: usize = 16;
+/// Maximum PDU data payload size - set this to the max PDI size or higher.
+const MAX_PDU_DATA: usize = 1100;
+/// Maximum number of EtherCAT frames that can be in flight at any one time.
+const MAX_FRAMES: usize = 16;
+/// Maximum total PDI length.
+const PDI_LEN: usize = 64;
+
+static PDU_STORAGE: PduStorage<MAX_FRAMES, MAX_PDU_DATA> = PduStorage::new();
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+
+    let interface = std::env::args()
+        .nth(1)
+        .expect("Provide network interface as first argument.");
+
+    let index: usize = std::env::args()
+        .nth(2)
+        .expect("Provide device index (starting from zero) as second argument.")
+        .parse()
+        .expect("Invalid index: must be a number");
+
+    let path = PathBuf::from(
+        std::env::args()
+            .nth(3)
+            .expect("Provide firmware file path as third argument."),
+    );
+
+    let password = std::env::args()
+        .nth(4)
+        .map(|password| {
+            u32::from_str_radix(password.trim_start_matches("0x"), 16)
+                .expect("Invalid password: must be a hex number")
+        })
+        .unwrap_or(0);
+
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .expect("Invalid firmware file name")
+        .to_string();
+
+    let firmware = std::fs::read(&path).expect("Read firmware file");
+
+    log::info!(
+        "Starting firmware update, interface {}, device index {}, file {} ({} bytes)",
+        interface,
+        index,
+        file_name,
+        firmware.len()
+    );
+
+    let (tx, rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+
+    let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+
+    tokio::spawn(tx_rx_task(&interface, tx, rx).expect("spawn TX/RX task"));
+
+    let group = client
+        .init_single_group::<MAX_SLAVES, PDI_LEN>()
+        .await
+        .expect("Init");
+
+    {
+        let mut slave = group
+            .slave(&client, index)
+            .expect("Could not find device for given index");
+
+        log::info!(
+            "Updating device {:#06x} {} {}...",
+            slave.configured_address(),
+            slave.name(),
+            slave.identity()
+        );
+
+        slave
+            .update_firmware(&file_name, password, &firmware)
+            .await?;
+
+        log::info!("--> Update complete, identity {}", slave.identity());
+    }
+
+    // The updated device is left in INIT, so the group must be configured again.
+    let group = group.into_init(&client).await?.into_pre_op(&client).await?;
+
+    log::info!("Group back in PRE-OP with {} slaves", group.len());
+
+    Ok(())
+}
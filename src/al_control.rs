@@ -27,6 +27,15 @@ impl AlControl {
         }
     }
 
+    /// Acknowledge an error flagged in the AL status register while remaining in the given state.
+    pub fn acknowledge(state: SlaveState) -> Self {
+        Self {
+            state,
+            error: true,
+            ..Default::default()
+        }
+    }
+
     pub fn reset() -> Self {
         Self {
             state: SlaveState::Init,
@@ -55,6 +64,14 @@ mod tests {
         assert_eq!(packed, [0x04 | 0x10, 0x00]);
     }
 
+    #[test]
+    fn acknowledge() {
+        assert_eq!(
+            AlControl::acknowledge(SlaveState::PreOp).pack(),
+            [0x02 | 0x10, 0x00]
+        );
+    }
+
     #[test]
     fn unpack() {
         let value = AlControl {
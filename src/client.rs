@@ -1,14 +1,16 @@
 use crate::{
     al_control::AlControl,
-    al_status_code::AlStatusCode,
     command::Command,
     dc,
-    error::{Error, Item},
+    driver::SlaveDriver,
+    eeprom::cache::EepromCache,
+    error::{Error, Item, SlaveStateError, StateErrorStore, StateErrors, StateTransitionError},
+    expected::{self, ExpectedSlave, TopologyMismatch},
     fmt,
     pdi::PdiOffset,
     pdu_loop::PduLoop,
     register::RegisterAddress,
-    slave::Slave,
+    slave::{Slave, SlaveRef},
     slave_group::{self, SlaveGroupHandle},
     slave_state::SlaveState,
     timer_factory::timeout,
@@ -20,6 +22,15 @@ use core::{
 };
 use heapless::FnvIndexMap;
 
+#[cfg(feature = "alloc")]
+use crate::SlaveHook;
+
+/// An expected network and the list to write its deviations from the discovered network to.
+type ExpectedTopology<'a, const MAX_SLAVES: usize> = (
+    &'a [ExpectedSlave],
+    &'a mut heapless::Vec<TopologyMismatch, MAX_SLAVES>,
+);
+
 /// The main EtherCAT master instance.
 ///
 /// The client is passed to [`SlaveGroup`]s to drive their TX/RX methods. It also provides direct
@@ -29,12 +40,25 @@ pub struct Client<'sto> {
     pub(crate) pdu_loop: PduLoop<'sto>,
     /// The total number of discovered slaves.
     ///
-    /// Using an `AtomicU16` here only to satisfy `Sync` requirements, but it's only ever written to
-    /// once so its safety is largely unused.
+    /// Using an `AtomicU16` here to satisfy `Sync` requirements. It is written during init, and
+    /// when hot connect segments are connected or disconnected.
     num_slaves: AtomicU16,
     pub(crate) timeouts: Timeouts,
 
     pub(crate) config: ClientConfig,
+
+    /// Optional cache of slave device EEPROM images.
+    pub(crate) eeprom_cache: Option<&'sto dyn EepromCache>,
+
+    /// Drivers for specific slave devices, matched in order by identity.
+    pub(crate) drivers: &'sto [&'sto dyn SlaveDriver],
+
+    /// Optional storage for slave devices that fail to transition to a requested state.
+    state_errors: Option<&'sto dyn StateErrorStore>,
+
+    /// Optional configuration hook called for every slave device during state transitions.
+    #[cfg(feature = "alloc")]
+    pub(crate) slave_hook: Option<&'static dyn SlaveHook>,
 }
 
 unsafe impl<'sto> Sync for Client<'sto> {}
@@ -47,9 +71,69 @@ impl<'sto> Client<'sto> {
             num_slaves: AtomicU16::new(0),
             timeouts,
             config,
+            eeprom_cache: None,
+            drivers: &[],
+            state_errors: None,
+            #[cfg(feature = "alloc")]
+            slave_hook: None,
+        }
+    }
+
+    /// Cache slave device EEPROM contents in the given store.
+    ///
+    /// During [`init`](Client::init), each slave device's EEPROM is read in bulk and stored in
+    /// `cache` if the cache does not already contain an image for the device. All further EEPROM
+    /// reads for that device are then served from the cache.
+    pub const fn with_eeprom_cache(self, cache: &'sto dyn EepromCache) -> Self {
+        Self {
+            eeprom_cache: Some(cache),
+            ..self
         }
     }
 
+    /// Configure slave devices using the first matching driver from the given list.
+    ///
+    /// See [`SlaveDriver`] for details.
+    pub const fn with_drivers(self, drivers: &'sto [&'sto dyn SlaveDriver]) -> Self {
+        Self { drivers, ..self }
+    }
+
+    /// Store every slave device that fails to transition to a requested state in `errors`.
+    ///
+    /// See [`StateErrors`] for details.
+    pub const fn with_state_errors<const N: usize>(self, errors: &'sto StateErrors<N>) -> Self {
+        Self {
+            state_errors: Some(errors),
+            ..self
+        }
+    }
+
+    /// Call the given hook for every slave device as it transitions from INIT to PRE-OP and from
+    /// PRE-OP to SAFE-OP.
+    ///
+    /// See [`SlaveHook`] for details.
+    #[cfg(feature = "alloc")]
+    pub const fn with_slave_hook(self, hook: &'static dyn SlaveHook) -> Self {
+        Self {
+            slave_hook: Some(hook),
+            ..self
+        }
+    }
+
+    /// Log a slave device that failed to transition to a requested state, and store it in the
+    /// client's [`StateErrors`] if one is set.
+    pub(crate) fn record_state_error(&self, failure: SlaveStateError) -> StateTransitionError {
+        fmt::error!("{}", failure);
+
+        if let Some(errors) = self.state_errors {
+            if !errors.push(failure) {
+                fmt::warn!("State error storage is full, dropping failure");
+            }
+        }
+
+        failure.into()
+    }
+
     /// Write zeroes to every slave's memory in chunks.
     async fn blank_memory(&self, start: impl Into<u16>, len: u16) -> Result<(), Error> {
         let step = self.pdu_loop.max_frame_data();
@@ -69,6 +153,8 @@ impl<'sto> Client<'sto> {
 
     // FIXME: When adding a powered on slave to the network, something breaks. Maybe need to reset
     // the configured address? But this broke other stuff so idk...
+    //
+    // Slaves that are power cycled or reconnected after init are handled by `SlaveGroup::recover`.
     async fn reset_slaves(&self) -> Result<(), Error> {
         fmt::debug!("Beginning reset");
 
@@ -191,7 +277,76 @@ impl<'sto> Client<'sto> {
     /// ```
     pub async fn init<const MAX_SLAVES: usize, G>(
         &self,
+        group_filter: impl for<'g> FnMut(&'g G, &Slave) -> Result<&'g dyn SlaveGroupHandle, Error>,
+    ) -> Result<G, Error>
+    where
+        G: Default,
+    {
+        self.init_inner::<MAX_SLAVES, G>(None, group_filter).await
+    }
+
+    /// Discover all slaves on the network, check them against an expected network description,
+    /// then group and configure them as [`Client::init`] does.
+    ///
+    /// Each item in `expected` describes the slave at the same position in the network. The check
+    /// is performed after all slaves are discovered but before any are assigned to a group or
+    /// configured. If the network does not match, every deviating position is logged and written
+    /// to `mismatches`, and [`Error::TopologyMismatch`] is returned. Deviations that don't fit in
+    /// `mismatches` are only logged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use ethercrab::{
+    ///     error::Error, Client, ClientConfig, ExpectedSlave, PduStorage, SlaveGroup, Timeouts,
+    /// };
+    ///
+    /// static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    ///
+    /// /// An EK1100 coupler with an EL2004 output module connected to port 3.
+    /// const EXPECTED: [ExpectedSlave; 2] = [
+    ///     ExpectedSlave::new(0x2, 0x044c2c52),
+    ///     ExpectedSlave::new(0x2, 0x07d43052)
+    ///         .with_revision(0x00100000)
+    ///         .with_parent(0, 3),
+    /// ];
+    ///
+    /// let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    ///
+    /// let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    ///
+    /// # async {
+    /// let mut mismatches = heapless::Vec::new();
+    ///
+    /// let result = client
+    ///     .init_expected::<2, SlaveGroup<2, 8>>(&EXPECTED, &mut mismatches, |group, _slave| {
+    ///         Ok(group)
+    ///     })
+    ///     .await;
+    ///
+    /// if let Err(Error::TopologyMismatch { .. }) = result {
+    ///     for mismatch in mismatches {
+    ///         eprintln!("Network does not match at {}", mismatch);
+    ///     }
+    /// }
+    /// # };
+    /// ```
+    pub async fn init_expected<const MAX_SLAVES: usize, G>(
+        &self,
+        expected: &[ExpectedSlave],
+        mismatches: &mut heapless::Vec<TopologyMismatch, MAX_SLAVES>,
+        group_filter: impl for<'g> FnMut(&'g G, &Slave) -> Result<&'g dyn SlaveGroupHandle, Error>,
+    ) -> Result<G, Error>
+    where
+        G: Default,
+    {
+        self.init_inner::<MAX_SLAVES, G>(Some((expected, mismatches)), group_filter)
+            .await
+    }
 
+    async fn init_inner<const MAX_SLAVES: usize, G>(
+        &self,
+        mut expected: Option<ExpectedTopology<'_, MAX_SLAVES>>,
         mut group_filter: impl for<'g> FnMut(&'g G, &Slave) -> Result<&'g dyn SlaveGroupHandle, Error>,
     ) -> Result<G, Error>
     where
@@ -207,20 +362,28 @@ impl<'sto> Client<'sto> {
         if num_slaves == 0 {
             fmt::warn!("No slaves were discovered. Check NIC device, connections and PDU response timeouts");
 
+            Self::check_topology(expected.as_mut(), &[])?;
+
             return Ok(groups);
         }
 
         self.reset_slaves().await?;
 
-        // This is the only place we store the number of slave devices, so the ordering can be
-        // pretty much anything.
+        // Only otherwise changed by hot connect segments after init, so the ordering can be pretty
+        // much anything.
         self.num_slaves.store(num_slaves, Ordering::Relaxed);
 
         let mut slaves = heapless::Deque::<Slave, MAX_SLAVES>::new();
 
         // Set configured address for all discovered slaves
         for slave_idx in 0..num_slaves {
-            let configured_address = BASE_SLAVE_ADDR.wrapping_add(slave_idx);
+            let configured_address = self.initial_address(slave_idx).await?;
+
+            check_address_free(
+                slaves.iter().map(|slave| slave.configured_address),
+                slave_idx,
+                configured_address,
+            )?;
 
             Command::apwr(slave_idx, RegisterAddress::ConfiguredStationAddress.into())
                 .wrap(self)
@@ -236,15 +399,35 @@ impl<'sto> Client<'sto> {
 
         fmt::debug!("Configuring topology/distributed clocks");
 
+        // Parent/child relationships are needed to check the whole topology, and are found
+        // without writing any configuration to the slaves.
+        dc::assign_topology(self, slaves.as_mut_slices().0).await?;
+
+        // Check the network before any slave is configured.
+        Self::check_topology(expected.as_mut(), slaves.as_slices().0)?;
+
         // Configure distributed clock offsets/propagation delays, perform static drift
         // compensation. We need the slaves in a single list so we can read the topology.
-        let dc_master = dc::configure_dc(self, slaves.as_mut_slices().0).await?;
+        let dc_master = dc::configure_dc(self, slaves.as_slices().0)
+            .await?
+            .map(|dc_master| dc_master.index);
 
         // If there are slave devices that support distributed clocks, run static drift compensation
-        if let Some(dc_master) = dc_master {
+        if let Some(dc_master) =
+            dc_master.and_then(|index| slaves.iter().find(|slave| slave.index == index))
+        {
             dc::run_dc_static_sync(self, dc_master, self.config.dc_static_sync_iterations).await?;
         }
 
+        // Hot connect segments that weren't found are given addresses after all discovered
+        // slaves.
+        let mut next_address = slaves
+            .iter()
+            .map(|slave| slave.configured_address)
+            .max()
+            .unwrap_or(BASE_SLAVE_ADDR)
+            .wrapping_add(1);
+
         // This block is to reduce the lifetime of the groups map references
         {
             // A unique list of groups so we can iterate over them and assign consecutive PDIs to each
@@ -256,17 +439,34 @@ impl<'sto> Client<'sto> {
 
                 // SAFETY: This mutates the internal slave list, so a reference to `group` may not be
                 // held over this line.
-                unsafe { group.push(slave)? };
+                let followers = unsafe { group.push(slave)? };
+
+                // The rest of a hot connect segment belongs to the same group as its first slave.
+                for _ in 0..followers {
+                    let slave = slaves.pop_front().ok_or_else(|| {
+                        fmt::error!("Hot connect segment has fewer slaves than declared");
+
+                        Error::Topology
+                    })?;
+
+                    // SAFETY: As above.
+                    unsafe { group.push(slave)? };
+                }
 
                 group_map
-                    .insert(usize::from(group.id()), group.as_ref())
+                    .insert(usize::from(group.id()), group)
                     .map_err(|_| Error::Capacity(Item::Group))?;
             }
 
+            for group in group_map.values() {
+                // SAFETY: No references to group internals are held at this point.
+                unsafe { group.reserve_hot_connect(&mut next_address)? };
+            }
+
             let mut offset = PdiOffset::default();
 
-            for (id, group) in group_map.iter_mut() {
-                offset = group.into_pre_op(offset, self).await?;
+            for (id, group) in group_map.iter() {
+                offset = group.as_ref().into_pre_op(offset, self).await?;
 
                 fmt::debug!("After group ID {} offset: {:?}", id, offset);
             }
@@ -280,6 +480,36 @@ impl<'sto> Client<'sto> {
         Ok(groups)
     }
 
+    /// Compare discovered slaves against an optional expected network.
+    fn check_topology<const MAX_SLAVES: usize>(
+        expected: Option<&mut ExpectedTopology<'_, MAX_SLAVES>>,
+        slaves: &[Slave],
+    ) -> Result<(), Error> {
+        let Some((expected, mismatches)) = expected else {
+            return Ok(());
+        };
+
+        mismatches.clear();
+
+        let mut first = None;
+        let mut count = 0;
+
+        for mismatch in expected::mismatches(expected, slaves) {
+            fmt::error!("Topology mismatch at {}", mismatch);
+
+            first.get_or_insert(mismatch.position);
+            count += 1;
+
+            // The full list is logged above, so overflowing entries can be dropped.
+            let _ = mismatches.push(mismatch);
+        }
+
+        match first {
+            Some(position) => Err(Error::TopologyMismatch { position, count }),
+            None => Ok(()),
+        }
+    }
+
     /// A convenience method to allow the quicker creation of a single group containing all
     /// discovered slave devices.
     ///
@@ -288,7 +518,9 @@ impl<'sto> Client<'sto> {
     /// To transition groups into different states, see [`SlaveGroup::into_safe_op`] or
     /// [`SlaveGroup::into_op`].
     ///
-    /// For multiple groups, see [`Client::init`].
+    /// For multiple groups, see [`Client::init`]. The returned group has no signal table, so
+    /// [`SlaveRef::signal`](crate::SlaveRef::signal) and friends will not find any PDO entries. Use
+    /// [`Client::init`] with a [`SlaveGroup`] that sets `MAX_SIGNALS` to record them.
     ///
     /// # Examples
     ///
@@ -368,7 +600,7 @@ impl<'sto> Client<'sto> {
     }
 
     /// Count the number of slaves on the network.
-    async fn count_slaves(&self) -> Result<u16, Error> {
+    pub(crate) async fn count_slaves(&self) -> Result<u16, Error> {
         Command::brd(RegisterAddress::Type.into())
             .wrap(self)
             .receive_wkc::<u8>()
@@ -383,50 +615,145 @@ impl<'sto> Client<'sto> {
         usize::from(self.num_slaves.load(Ordering::Relaxed))
     }
 
+    /// Record slave devices added to the network by connecting a hot connect segment.
+    pub(crate) fn add_slaves(&self, count: usize) {
+        self.num_slaves.fetch_add(count as u16, Ordering::Relaxed);
+    }
+
+    /// Record slave devices removed from the network by disconnecting a hot connect segment.
+    pub(crate) fn remove_slaves(&self, count: usize) {
+        self.num_slaves.fetch_sub(count as u16, Ordering::Relaxed);
+    }
+
+    /// Choose the configured address given to the slave device at the given position during init.
+    ///
+    /// This is the slave's station alias if [`ClientConfig::alias_addressing`] is enabled and the
+    /// slave has an alias, otherwise an address based on the slave's position.
+    async fn initial_address(&self, position: u16) -> Result<u16, Error> {
+        let alias = if self.config.alias_addressing {
+            Command::aprd(position, RegisterAddress::ConfiguredStationAlias.into())
+                .wrap(self)
+                .receive::<u16>()
+                .await?
+        } else {
+            0
+        };
+
+        Ok(address_for(alias, position))
+    }
+
+    /// Read the configured address of the slave device at the given position in the network.
+    ///
+    /// Slave addresses are not always contiguous, e.g. when using
+    /// [`ClientConfig::alias_addressing`], so they are read back from each device. Devices that
+    /// have not been given an address, e.g. in a hot connect segment that has not been connected
+    /// yet, return `None`.
+    async fn address_at(&self, position: u16) -> Result<Option<u16>, Error> {
+        Command::aprd(position, RegisterAddress::ConfiguredStationAddress.into())
+            .wrap(self)
+            .receive::<u16>()
+            .await
+            .map(|address| Some(address).filter(|address| *address != 0))
+    }
+
     /// Wait for all slaves on the network to reach a given state.
     pub async fn wait_for_state(&self, desired_state: SlaveState) -> Result<(), Error> {
         let num_slaves = self.num_slaves.load(Ordering::Relaxed);
 
-        timeout(self.timeouts.state_transition, async {
-            loop {
-                let status = Command::brd(RegisterAddress::AlStatus.into())
-                    .wrap(self)
-                    .with_wkc(num_slaves)
-                    .receive::<AlControl>()
-                    .await?;
-
-                fmt::trace!("Global AL status {:?}", status);
-
-                if status.error {
-                    fmt::error!(
-                        "Error occurred transitioning all slaves to {:?}",
-                        desired_state,
-                    );
-
-                    for slave_addr in BASE_SLAVE_ADDR..(BASE_SLAVE_ADDR + self.num_slaves() as u16)
-                    {
-                        let slave_status =
-                            Command::fprd(slave_addr, RegisterAddress::AlStatusCode.into())
-                                .wrap(self)
-                                .ignore_wkc()
-                                .receive::<AlStatusCode>()
+        timeout(
+            self.timeouts.state_transitions.for_state(desired_state),
+            async {
+                loop {
+                    let status = Command::brd(RegisterAddress::AlStatus.into())
+                        .wrap(self)
+                        .with_wkc(num_slaves)
+                        .receive::<AlControl>()
+                        .await?;
+
+                    fmt::trace!("Global AL status {:?}", status);
+
+                    if status.error {
+                        fmt::error!(
+                            "Error occurred transitioning all slaves to {:?}",
+                            desired_state,
+                        );
+
+                        let mut failures = None;
+
+                        for position in 0..self.count_slaves().await? {
+                            let Some(slave_addr) = self.address_at(position).await? else {
+                                continue;
+                            };
+
+                            match SlaveRef::new(self, slave_addr, ())
+                                .checked_state(desired_state)
                                 .await
-                                .unwrap_or(AlStatusCode::UnspecifiedError);
+                            {
+                                Ok(_) => (),
+                                Err(Error::StateTransition(e)) => {
+                                    StateTransitionError::merge(&mut failures, e)
+                                }
+                                Err(e) => return Err(e),
+                            }
+                        }
+
+                        if let Some(failures) = failures {
+                            return Err(Error::StateTransition(failures));
+                        }
+                    }
 
-                        fmt::error!("--> Slave {:#06x} status code {}", slave_addr, slave_status);
+                    if status.state == desired_state {
+                        break Ok(());
                     }
 
-                    return Err(Error::StateTransition);
+                    self.timeouts.loop_tick().await;
                 }
+            },
+        )
+        .await
+    }
 
-                if status.state == desired_state {
-                    break Ok(());
-                }
+    /// Move every slave device on the network down to INIT, e.g. before the application exits.
+    ///
+    /// Slaves in OP are first moved to SAFE-OP, where they put their outputs into a safe state.
+    /// Slaves in SAFE-OP are then moved to PRE-OP, before all slaves are moved to INIT.
+    ///
+    /// Any [`SlaveGroup`]s should be dropped before calling this method, as their typestates will
+    /// no longer reflect the state of their slave devices. To move a single group down, use e.g.
+    /// [`SlaveGroup::into_init`](crate::SlaveGroup::into_init) instead, which also zeroes the
+    /// group's outputs.
+    pub async fn shutdown(&self) -> Result<(), Error> {
+        fmt::debug!("Shutting down {} slave devices", self.num_slaves());
+
+        for (from, to) in [
+            (SlaveState::Op, SlaveState::SafeOp),
+            (SlaveState::SafeOp, SlaveState::PreOp),
+        ] {
+            for position in 0..self.count_slaves().await? {
+                let Some(configured_address) = self.address_at(position).await? else {
+                    continue;
+                };
+
+                let slave = SlaveRef::new(self, configured_address, ());
+
+                if slave.state().await? == from {
+                    slave.request_slave_state_nowait(to).await?;
 
-                self.timeouts.loop_tick().await;
+                    slave
+                        .wait_for_state_with(to, &self.timeouts.state_transitions)
+                        .await?;
+                }
             }
-        })
-        .await
+        }
+
+        // Any state can transition directly to INIT
+        Command::bwr(RegisterAddress::AlControl.into())
+            .wrap(self)
+            .ignore_wkc()
+            .send(AlControl::new(SlaveState::Init))
+            .await?;
+
+        self.wait_for_state(SlaveState::Init).await
     }
 
     pub(crate) fn max_frame_data(&self) -> usize {
@@ -457,9 +784,108 @@ fn blank_mem_iter(
     })
 }
 
+/// The configured address of the slave device at the given position, which is its station alias
+/// if it has one, otherwise an address based on its position.
+fn address_for(alias: u16, position: u16) -> u16 {
+    if alias != 0 {
+        alias
+    } else {
+        BASE_SLAVE_ADDR.wrapping_add(position)
+    }
+}
+
+/// Check that a slave device's configured address is not already used by another slave.
+fn check_address_free(
+    mut used: impl Iterator<Item = u16>,
+    position: u16,
+    configured_address: u16,
+) -> Result<(), Error> {
+    if used.any(|address| address == configured_address) {
+        fmt::error!(
+            "Slave at position {} has address {:#06x} which is already in use",
+            position,
+            configured_address
+        );
+
+        return Err(Error::AddressConflict { configured_address });
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use crate::PduStorage;
+
+    /// A client that is not connected to a network, for testing code that doesn't send any PDUs.
+    pub(crate) fn offline_client() -> Client<'static> {
+        // Leaked so every test gets its own storage.
+        let storage = Box::leak(Box::new(PduStorage::<1, 32>::new()));
+
+        let (_tx, _rx, pdu_loop) = storage.try_split().unwrap();
+
+        Client::new(pdu_loop, Timeouts::default(), ClientConfig::default())
+    }
+
+    /// A client connected to a fake network, and the future that runs the network.
+    ///
+    /// Each PDU sent by the client is answered by `respond`, which is given the PDU's command and
+    /// data to fill in, and returns the working counter.
+    pub(crate) fn fake_network<'a>(
+        mut respond: impl FnMut(Command, &mut [u8]) -> u16 + 'a,
+    ) -> (
+        &'static Client<'static>,
+        impl core::future::Future<Output = ()> + 'a,
+    ) {
+        let storage = Box::leak(Box::new(PduStorage::<8, 64>::new()));
+
+        let (mut tx, mut rx, pdu_loop) = storage.try_split().unwrap();
+
+        let client = Box::leak(Box::new(Client::new(
+            pdu_loop,
+            Timeouts::default(),
+            ClientConfig::default(),
+        )));
+
+        let network = async move {
+            let mut packet = [0u8; 1536];
+
+            loop {
+                while let Some(frame) = tx.next_sendable_frame() {
+                    let mut reply = Vec::new();
+
+                    frame
+                        .send_blocking(&mut packet, |bytes| {
+                            reply.extend_from_slice(bytes);
+
+                            Ok(bytes.len())
+                        })
+                        .unwrap();
+
+                    // Skip the Ethernet and EtherCAT headers
+                    let pdu = &mut reply[16..];
+
+                    let command =
+                        Command::parse_code_data(pdu[0], pdu[2..6].try_into().unwrap()).unwrap();
+                    let len = usize::from(u16::from_le_bytes([pdu[6], pdu[7]]) & 0x07ff);
+
+                    let (data, wkc) = pdu[10..].split_at_mut(len);
+
+                    wkc[0..2].copy_from_slice(&respond(command, data).to_le_bytes());
+
+                    // The first slave sets the U/L bit of the source MAC address
+                    reply[6] |= 0x02;
+
+                    rx.receive_frame(&reply).unwrap();
+                }
+
+                futures_lite::future::yield_now().await;
+            }
+        };
+
+        (client, network)
+    }
 
     #[test]
     #[cfg_attr(miri, ignore)]
@@ -493,4 +919,34 @@ mod tests {
             Ok(())
         });
     }
+
+    #[test]
+    fn alias_addresses() {
+        assert_eq!(address_for(0, 0), BASE_SLAVE_ADDR);
+        assert_eq!(address_for(0, 3), BASE_SLAVE_ADDR + 3);
+        assert_eq!(address_for(0x10, 3), 0x10);
+    }
+
+    #[test]
+    fn duplicate_alias() {
+        let used = [address_for(0, 0), address_for(0x10, 1)];
+
+        assert_eq!(
+            check_address_free(used.into_iter(), 2, address_for(0x20, 2)),
+            Ok(())
+        );
+        assert_eq!(
+            check_address_free(used.into_iter(), 2, address_for(0x10, 2)),
+            Err(Error::AddressConflict {
+                configured_address: 0x10
+            })
+        );
+        // An alias can also collide with a position based address.
+        assert_eq!(
+            check_address_free(used.into_iter(), 2, address_for(BASE_SLAVE_ADDR, 2)),
+            Err(Error::AddressConflict {
+                configured_address: BASE_SLAVE_ADDR
+            })
+        );
+    }
 }
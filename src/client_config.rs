@@ -13,6 +13,20 @@ pub struct ClientConfig {
 
     /// EtherCAT packet (PDU) network retry behaviour.
     pub retry_behaviour: RetryBehaviour,
+
+    /// Use each slave device's configured station alias as its configured address.
+    ///
+    /// By default, slaves are given addresses based on their position in the network, so
+    /// inserting a slave changes the address of every slave after it. When this option is
+    /// enabled, slaves with a station alias are given the alias as their address during
+    /// [`Client::init`](crate::Client::init) so their address does not depend on the network
+    /// layout. Slaves without an alias are still addressed by position.
+    ///
+    /// Init fails with [`Error::AddressConflict`](crate::error::Error::AddressConflict) if two
+    /// slaves end up with the same address, e.g. because of duplicate aliases.
+    ///
+    /// Defaults to `false`.
+    pub alias_addressing: bool,
 }
 
 impl Default for ClientConfig {
@@ -20,6 +34,7 @@ impl Default for ClientConfig {
         Self {
             dc_static_sync_iterations: 10_000,
             retry_behaviour: RetryBehaviour::default(),
+            alias_addressing: false,
         }
     }
 }
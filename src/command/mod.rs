@@ -225,6 +225,11 @@ impl Command {
         Reads::Frmw { address, register }
     }
 
+    /// Logical Read (LRD).
+    pub fn lrd(address: u32) -> Reads {
+        Reads::Lrd { address }
+    }
+
     /// Logical Read Write (LRD), used mainly for sending and receiving PDI.
     pub fn lrw(address: u32) -> Writes {
         Writes::Lrw { address }
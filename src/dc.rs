@@ -2,6 +2,7 @@
 
 use crate::{
     command::Command,
+    eeprom::types::DcOpMode,
     error::Error,
     fmt,
     register::RegisterAddress,
@@ -9,6 +10,126 @@ use crate::{
     Client, SlaveRef,
 };
 
+/// Startup delay before the first SYNC0 pulse is generated, in nanoseconds.
+const SYNC_START_DELAY: u32 = 100_000_000;
+
+/// Distributed Clock SYNC0/SYNC1 configuration for a single slave device.
+///
+/// Defaults are read from the DC category of the slave's EEPROM, if present. SYNC signals are only
+/// configured when both [`assign_activate`](DcSync::assign_activate) and
+/// [`sync0_cycle_time`](DcSync::sync0_cycle_time) are non-zero.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DcSync {
+    /// Value written to the DC activation registers `0x0980:0x0981`.
+    ///
+    /// For example, `0x0300` enables cyclic operation and the SYNC0 signal.
+    pub assign_activate: u16,
+
+    /// SYNC0 cycle time in nanoseconds.
+    pub sync0_cycle_time: u32,
+
+    /// SYNC0 shift time in nanoseconds.
+    pub sync0_shift_time: u32,
+
+    /// SYNC1 cycle time in nanoseconds.
+    pub sync1_cycle_time: u32,
+}
+
+impl DcSync {
+    /// Whether this configuration enables any SYNC signals.
+    pub fn is_enabled(&self) -> bool {
+        self.assign_activate != 0 && self.sync0_cycle_time != 0
+    }
+}
+
+impl From<DcOpMode> for DcSync {
+    fn from(op_mode: DcOpMode) -> Self {
+        let sync1_cycle_time = u32::try_from(op_mode.sync1_cycle_factor)
+            .map(|factor| op_mode.cycle_time_0.saturating_mul(factor))
+            .unwrap_or(0);
+
+        Self {
+            assign_activate: op_mode.assign_activate,
+            sync0_cycle_time: op_mode.cycle_time_0,
+            sync0_shift_time: op_mode.shift_time_0,
+            sync1_cycle_time,
+        }
+    }
+}
+
+/// Configure SYNC0/SYNC1 signal generation for a slave device.
+///
+/// Based on SOEM's `ecx_dcsync0`. The first SYNC0 pulse is aligned to the cycle time, starting
+/// [`SYNC_START_DELAY`] ns after the slave's current system time.
+pub(crate) async fn configure_dc_sync(client: &Client<'_>, slave: &Slave) -> Result<(), Error> {
+    let sync = slave.config.dc_sync;
+
+    if !slave.flags.dc_supported || !sync.is_enabled() {
+        return Ok(());
+    }
+
+    let sl = SlaveRef::new(client, slave.configured_address, ());
+
+    fmt::debug!(
+        "Slave {:#06x} configuring DC sync {:?}",
+        slave.configured_address,
+        sync
+    );
+
+    // Disable sync signals while configuring
+    sl.write(RegisterAddress::DcSyncActive).send(0u8).await?;
+
+    // Slaves with 32 bit DC only hold the lower half of the system time, so the start time is
+    // computed with the same width to wrap the way the slave's own clock does.
+    if slave.flags.has_64bit_dc {
+        let local_time = sl
+            .read(RegisterAddress::DcSystemTime)
+            .receive::<u64>()
+            .await?;
+
+        let cycle_time = u64::from(sync.sync0_cycle_time);
+
+        let start_time = ((local_time + u64::from(SYNC_START_DELAY)) / cycle_time) * cycle_time
+            + cycle_time
+            + u64::from(sync.sync0_shift_time);
+
+        sl.write(RegisterAddress::DcSyncStartTime)
+            .send(start_time)
+            .await?;
+    } else {
+        let local_time = sl
+            .read(RegisterAddress::DcSystemTime)
+            .receive::<u32>()
+            .await?;
+
+        let cycle_time = sync.sync0_cycle_time;
+
+        let start_time = (local_time.wrapping_add(SYNC_START_DELAY) / cycle_time)
+            .wrapping_mul(cycle_time)
+            .wrapping_add(cycle_time)
+            .wrapping_add(sync.sync0_shift_time);
+
+        sl.write(RegisterAddress::DcSyncStartTime)
+            .send(start_time)
+            .await?;
+    }
+
+    sl.write(RegisterAddress::DcSync0CycleTime)
+        .send(sync.sync0_cycle_time)
+        .await?;
+    sl.write(RegisterAddress::DcSync1CycleTime)
+        .send(sync.sync1_cycle_time)
+        .await?;
+
+    // Writes both 0x0980 and 0x0981, enabling the configured SYNC signals
+    sl.write(RegisterAddress::DcCyclicUnitControl)
+        .send(sync.assign_activate)
+        .await?;
+
+    Ok(())
+}
+
 /// Send a broadcast to all slaves to latch in DC receive time, then store it on the slave structs.
 async fn latch_dc_times(client: &Client<'_>, slaves: &mut [Slave]) -> Result<(), Error> {
     let num_slaves_with_dc: usize = slaves
@@ -372,16 +493,27 @@ fn assign_parent_relationships(slaves: &mut [Slave]) -> Result<(), Error> {
     Ok(())
 }
 
+/// Latch DC receive times and work out the network topology from them.
+///
+/// This assigns parent/child relationships and propagation delays to every slave, but does not
+/// write any DC configuration, so the topology can be checked before slaves are configured.
+pub(crate) async fn assign_topology(
+    client: &Client<'_>,
+    slaves: &mut [Slave],
+) -> Result<(), Error> {
+    latch_dc_times(client, slaves).await?;
+
+    assign_parent_relationships(slaves)
+}
+
 /// Configure distributed clocks.
 ///
 /// This method walks through the discovered list of devices and sets the system time offset and
-/// transmission delay of each device.
+/// transmission delay of each device. [`assign_topology`] must be called first.
 pub(crate) async fn configure_dc<'slaves>(
     client: &Client<'_>,
-    slaves: &'slaves mut [Slave],
+    slaves: &'slaves [Slave],
 ) -> Result<Option<&'slaves Slave>, Error> {
-    latch_dc_times(client, slaves).await?;
-
     // let ethercat_offset = Utc.ymd(2000, 01, 01).and_hms(0, 0, 0);
 
     // TODO: Allow passing in of an initial value
@@ -389,8 +521,6 @@ pub(crate) async fn configure_dc<'slaves>(
     //     chrono::Utc::now().timestamp_nanos() - dbg!(ethercat_offset.timestamp_nanos());
     let now_nanos = 0;
 
-    assign_parent_relationships(slaves)?;
-
     for slave in slaves.iter() {
         write_dc_parameters(client, slave, slave.dc_receive_time, now_nanos).await?;
     }
@@ -441,6 +571,43 @@ mod tests {
         slave::ports::{tests::make_ports, Port, Ports},
     };
 
+    #[test]
+    fn dc_sync_from_op_mode() {
+        let op_mode = DcOpMode {
+            cycle_time_0: 1_000_000,
+            shift_time_0: 250_000,
+            shift_time_1: 0,
+            sync1_cycle_factor: 2,
+            assign_activate: 0x0700,
+            sync0_cycle_factor: 1,
+            name_string_idx: 0,
+            desc_string_idx: 0,
+        };
+
+        let sync = DcSync::from(op_mode);
+
+        assert_eq!(
+            sync,
+            DcSync {
+                assign_activate: 0x0700,
+                sync0_cycle_time: 1_000_000,
+                sync0_shift_time: 250_000,
+                sync1_cycle_time: 2_000_000,
+            }
+        );
+        assert!(sync.is_enabled());
+
+        // Cycle time is defined by the master
+        let sync = DcSync::from(DcOpMode {
+            cycle_time_0: 0,
+            sync1_cycle_factor: -1,
+            ..op_mode
+        });
+
+        assert_eq!(sync.sync1_cycle_time, 0);
+        assert!(!sync.is_enabled());
+    }
+
     // A slave device in the middle of the chain
     fn ports_passthrough() -> Ports {
         make_ports(true, true, false, false)
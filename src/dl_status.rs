@@ -48,6 +48,39 @@ pub struct DlStatus {
     pub signal_port3: bool,
 }
 
+impl DlStatus {
+    /// Get the link status of the port with the given EtherCAT port number.
+    pub(crate) fn port(&self, number: usize) -> PortLinkStatus {
+        let (link, loop_closed, communication) = match number {
+            0 => (self.link_port0, self.loopback_port0, self.signal_port0),
+            1 => (self.link_port1, self.loopback_port1, self.signal_port1),
+            2 => (self.link_port2, self.loopback_port2, self.signal_port2),
+            3 => (self.link_port3, self.loopback_port3, self.signal_port3),
+            _ => (false, true, false),
+        };
+
+        PortLinkStatus {
+            link,
+            loop_closed,
+            communication,
+        }
+    }
+}
+
+/// The physical link status of a single port of a slave device, read from its DL status register.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PortLinkStatus {
+    /// A physical link is present on the port.
+    pub link: bool,
+    /// The port's loop is closed, so frames are sent back out of the same port instead of being
+    /// forwarded to the next port. Ports without a link are closed automatically.
+    pub loop_closed: bool,
+    /// A stable communication signal is being received on the port.
+    pub communication: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,370 @@
+//! Reusable drivers for specific slave devices, matched by identity during initialisation.
+
+use crate::{
+    error::Error, fmt, slave::SignalStore, Client, EtherCrabWireReadSized, EtherCrabWireWriteSized,
+    PdoAssignment, Slave, SlaveIdentity, SlavePdi, SlaveRef, Transition,
+};
+use core::ops::Deref;
+
+/// A value written to a slave device with an expedited SDO download.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SdoValue {
+    /// A single byte.
+    U8(u8),
+    /// A 16 bit value.
+    U16(u16),
+    /// A 32 bit value.
+    U32(u32),
+}
+
+impl SdoValue {
+    /// Write this value to the given object of a slave device.
+    pub(crate) async fn write<S>(
+        self,
+        slave: &SlaveRef<'_, S>,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<(), Error>
+    where
+        S: Deref<Target = Slave>,
+    {
+        match self {
+            SdoValue::U8(value) => slave.sdo_write(index, sub_index, value).await,
+            SdoValue::U16(value) => slave.sdo_write(index, sub_index, value).await,
+            SdoValue::U32(value) => slave.sdo_write(index, sub_index, value).await,
+        }
+    }
+}
+
+impl From<u8> for SdoValue {
+    fn from(value: u8) -> Self {
+        Self::U8(value)
+    }
+}
+
+impl From<u16> for SdoValue {
+    fn from(value: u16) -> Self {
+        Self::U16(value)
+    }
+}
+
+impl From<u32> for SdoValue {
+    fn from(value: u32) -> Self {
+        Self::U32(value)
+    }
+}
+
+/// An SDO written by a [`SlaveDriver`] during a state transition.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StartupSdo {
+    /// The transition during which this SDO is written.
+    pub transition: Transition,
+    /// Object index.
+    pub index: u16,
+    /// Object sub-index.
+    pub sub_index: u8,
+    /// The value to write.
+    pub value: SdoValue,
+}
+
+impl StartupSdo {
+    /// Write `value` to the given object during the given transition.
+    pub const fn new(transition: Transition, index: u16, sub_index: u8, value: SdoValue) -> Self {
+        Self {
+            transition,
+            index,
+            sub_index,
+            value,
+        }
+    }
+}
+
+/// A driver for one or more slave device types.
+///
+/// Drivers are registered with [`Client::with_drivers`]. During initialisation, the first driver
+/// that [`matches`](SlaveDriver::matches) a slave's identity is used to configure it:
+///
+/// 1. Once the slave reaches PRE-OP, all [`startup_sdos`](SlaveDriver::startup_sdos) for
+///    [`Transition::INIT_TO_PRE_OP`] are written.
+/// 2. When the slave's group is moved into SAFE-OP, all startup SDOs for
+///    [`Transition::PRE_OP_TO_SAFE_OP`] are written, followed by the
+///    [`pdo_assignment`](SlaveDriver::pdo_assignment), if any.
+///
+/// Once the group has a PDI, [`SlaveGroup::run_drivers`](crate::SlaveGroup::run_drivers) calls the
+/// optional [`cycle`](SlaveDriver::cycle) logic for every slave with a driver.
+///
+/// Drivers are shared between all matching slaves, so any per-slave state must be kept by the
+/// application.
+///
+/// # Examples
+///
+/// ```rust
+/// use ethercrab::{
+///     Pdo, PdoAssignment, SdoValue, SlaveDriver, SlaveIdentity, StartupSdo, Transition,
+/// };
+///
+/// /// Beckhoff EL3004 4 channel analog input terminal.
+/// struct El3004;
+///
+/// const INPUTS: [Pdo; 4] = [
+///     Pdo::fixed(0x1a00),
+///     Pdo::fixed(0x1a02),
+///     Pdo::fixed(0x1a04),
+///     Pdo::fixed(0x1a06),
+/// ];
+///
+/// const STARTUP: [StartupSdo; 1] = [
+///     // Disable the filter on the first channel
+///     StartupSdo::new(Transition::PRE_OP_TO_SAFE_OP, 0x8000, 0x06, SdoValue::U8(0)),
+/// ];
+///
+/// impl SlaveDriver for El3004 {
+///     fn name(&self) -> &str {
+///         "EL3004"
+///     }
+///
+///     fn matches(&self, identity: &SlaveIdentity) -> bool {
+///         identity.vendor_id == 0x2 && identity.product_id == 0x0bbc3052
+///     }
+///
+///     fn startup_sdos(&self) -> &[StartupSdo] {
+///         &STARTUP
+///     }
+///
+///     fn pdo_assignment(&self) -> Option<PdoAssignment<'_>> {
+///         Some(PdoAssignment::new().outputs(&[]).inputs(&INPUTS))
+///     }
+/// }
+/// ```
+pub trait SlaveDriver: Sync {
+    /// A human readable name for this driver, used in log messages.
+    fn name(&self) -> &str;
+
+    /// Whether this driver can handle a slave device with the given identity.
+    fn matches(&self, identity: &SlaveIdentity) -> bool;
+
+    /// SDOs to write during startup.
+    fn startup_sdos(&self) -> &[StartupSdo] {
+        &[]
+    }
+
+    /// The PDO assignment to write before the slave moves into SAFE-OP. If this is `None`, the
+    /// mapping stored in the slave device is used.
+    fn pdo_assignment(&self) -> Option<PdoAssignment<'_>> {
+        None
+    }
+
+    /// Cyclic logic called for each slave by
+    /// [`SlaveGroup::run_drivers`](crate::SlaveGroup::run_drivers), e.g. to compute outputs from
+    /// the last received inputs.
+    fn cycle(&self, slave: &mut SlaveRef<'_, SlavePdi<'_>>) -> Result<(), Error> {
+        let _ = slave;
+
+        Ok(())
+    }
+}
+
+impl<'a> core::fmt::Debug for dyn SlaveDriver + 'a {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("SlaveDriver").field(&self.name()).finish()
+    }
+}
+
+/// Typed process data for the slave devices handled by a [`SlaveDriver`].
+///
+/// The types must match the driver's PDO assignment, and are read and written with
+/// [`SlaveRef::inputs`] and [`SlaveRef::set_outputs`].
+pub trait SlaveDriverIo: SlaveDriver {
+    /// Process data inputs (TxPDOs) read from the slave device.
+    type Inputs: EtherCrabWireReadSized;
+
+    /// Process data outputs (RxPDOs) written to the slave device.
+    type Outputs: EtherCrabWireWriteSized;
+}
+
+/// Find the driver for the given slave device, if any.
+pub(crate) fn find<'sto>(
+    client: &Client<'sto>,
+    identity: &SlaveIdentity,
+) -> Option<&'sto dyn SlaveDriver> {
+    client
+        .drivers
+        .iter()
+        .copied()
+        .find(|driver| driver.matches(identity))
+}
+
+/// A driver's startup SDOs for the given transition, in the order they are defined.
+fn startup_sdos(
+    driver: &dyn SlaveDriver,
+    transition: Transition,
+) -> impl Iterator<Item = &StartupSdo> {
+    driver
+        .startup_sdos()
+        .iter()
+        .filter(move |sdo| sdo.transition == transition)
+}
+
+/// Apply a slave's driver configuration for the given transition.
+pub(crate) async fn configure(
+    client: &Client<'_>,
+    transition: Transition,
+    slave: &mut Slave,
+    signals: Option<&dyn SignalStore>,
+) -> Result<(), Error> {
+    let Some(driver) = find(client, &slave.identity) else {
+        return Ok(());
+    };
+
+    fmt::debug!(
+        "Slave {:#06x} using driver {} for {}",
+        slave.configured_address,
+        driver.name(),
+        transition
+    );
+
+    let mut slave = SlaveRef::new(client, slave.configured_address, slave).with_signals(signals);
+
+    for sdo in startup_sdos(driver, transition) {
+        sdo.value.write(&slave, sdo.index, sdo.sub_index).await?;
+    }
+
+    if transition == Transition::PRE_OP_TO_SAFE_OP {
+        if let Some(assignment) = driver.pdo_assignment() {
+            slave.configure_pdos(&assignment).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::tests::offline_client, slave_group::Op, SlaveGroup, SlaveGroupHandle};
+    use std::sync::Mutex;
+
+    struct Driver(&'static str, u32);
+
+    impl SlaveDriver for Driver {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn matches(&self, identity: &SlaveIdentity) -> bool {
+            identity.product_id == self.1
+        }
+    }
+
+    #[test]
+    fn first_matching_driver() {
+        let drivers: [&dyn SlaveDriver; 3] = [
+            &Driver("first", 1),
+            &Driver("second", 2),
+            &Driver("third", 2),
+        ];
+
+        let client = offline_client().with_drivers(&drivers);
+
+        let identity = |product_id| SlaveIdentity {
+            vendor_id: 0x2,
+            product_id,
+            revision: 0,
+            serial: 0,
+        };
+
+        assert_eq!(
+            find(&client, &identity(2)).map(|driver| driver.name()),
+            Some("second")
+        );
+        assert!(find(&client, &identity(3)).is_none());
+    }
+
+    #[test]
+    fn startup_sdo_order() {
+        struct Startup;
+
+        const SDOS: [StartupSdo; 4] = [
+            StartupSdo::new(Transition::PRE_OP_TO_SAFE_OP, 0x8000, 1, SdoValue::U8(1)),
+            StartupSdo::new(Transition::INIT_TO_PRE_OP, 0x8000, 2, SdoValue::U16(2)),
+            StartupSdo::new(Transition::PRE_OP_TO_SAFE_OP, 0x8010, 1, SdoValue::U32(3)),
+            StartupSdo::new(Transition::PRE_OP_TO_SAFE_OP, 0x8000, 3, SdoValue::U8(4)),
+        ];
+
+        impl SlaveDriver for Startup {
+            fn name(&self) -> &str {
+                "startup"
+            }
+
+            fn matches(&self, _identity: &SlaveIdentity) -> bool {
+                true
+            }
+
+            fn startup_sdos(&self) -> &[StartupSdo] {
+                &SDOS
+            }
+        }
+
+        let sdos = |transition| {
+            startup_sdos(&Startup, transition)
+                .map(|sdo| (sdo.index, sdo.sub_index))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(sdos(Transition::INIT_TO_PRE_OP), [(0x8000, 2)]);
+        assert_eq!(
+            sdos(Transition::PRE_OP_TO_SAFE_OP),
+            [(0x8000, 1), (0x8010, 1), (0x8000, 3)]
+        );
+        assert_eq!(sdos(Transition::SAFE_OP_TO_OP), []);
+    }
+
+    #[test]
+    fn run_drivers() {
+        struct Cycle(Mutex<Vec<u16>>);
+
+        impl SlaveDriver for Cycle {
+            fn name(&self) -> &str {
+                "cycle"
+            }
+
+            fn matches(&self, identity: &SlaveIdentity) -> bool {
+                identity.product_id == 1
+            }
+
+            fn cycle(&self, slave: &mut SlaveRef<'_, SlavePdi<'_>>) -> Result<(), Error> {
+                self.0.lock().unwrap().push(slave.configured_address());
+
+                Ok(())
+            }
+        }
+
+        let driver = Cycle(Mutex::new(Vec::new()));
+        let drivers: [&dyn SlaveDriver; 1] = [&driver];
+
+        let client = offline_client().with_drivers(&drivers);
+
+        let group = SlaveGroup::<3, 8, Op>::default();
+
+        for (configured_address, product_id) in [(0x1000, 1), (0x1001, 2), (0x1002, 1)] {
+            let slave = Slave {
+                configured_address,
+                identity: SlaveIdentity {
+                    product_id,
+                    ..SlaveIdentity::default()
+                },
+                ..Slave::default()
+            };
+
+            // SAFETY: No references to the group's slaves are held.
+            unsafe { group.push(slave).unwrap() };
+        }
+
+        assert_eq!(group.run_drivers(&client), Ok(()));
+
+        // Slaves are visited in group order, skipping the slave without a driver.
+        assert_eq!(*driver.0.lock().unwrap(), [0x1000, 0x1002]);
+    }
+}
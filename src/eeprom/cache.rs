@@ -0,0 +1,43 @@
+//! Caching of slave device EEPROM contents.
+
+use crate::SlaveIdentity;
+
+/// A store of slave device EEPROM images, keyed by the device's [`SlaveIdentity`].
+///
+/// Reading SII data from a device is slow, so passing a cache to
+/// [`Client::with_eeprom_cache`](crate::Client::with_eeprom_cache) reads each device's EEPROM once
+/// in bulk, after which all EEPROM reads are served from the cache. A cache may also be populated
+/// ahead of time, e.g. from a previous run, to skip EEPROM reads on startup entirely.
+///
+/// Devices with the same vendor ID, product ID, revision and serial number are assumed to have
+/// identical EEPROM contents.
+pub trait EepromCache: Sync {
+    /// Whether an image for the given device is present in the cache.
+    fn contains(&self, identity: &SlaveIdentity) -> bool;
+
+    /// Copy cached data for the given device into `buf`, starting at the given word address.
+    ///
+    /// Returns the number of bytes copied, which may be less than the length of `buf` at the end of
+    /// the image. `None` must be returned if the device has no cached image, or if `start_word` is
+    /// past the end of the image.
+    fn read(&self, identity: &SlaveIdentity, start_word: u16, buf: &mut [u8]) -> Option<usize>;
+
+    /// Store a chunk of a device's EEPROM data, starting at the given word address.
+    ///
+    /// When a device's EEPROM is cached, chunks are written in ascending address order starting
+    /// from word `0`, then [`commit`](EepromCache::commit) is called once the whole image has been
+    /// read. Written data must not be returned by [`contains`](EepromCache::contains) or
+    /// [`read`](EepromCache::read) before it is committed. If reading the EEPROM fails part way
+    /// through, `commit` is not called, and a later attempt writes the image again from word `0`.
+    fn write(&self, identity: &SlaveIdentity, start_word: u16, data: &[u8]);
+
+    /// Make the complete image stored with [`write`](EepromCache::write) for the given device
+    /// available, replacing any image already cached for it.
+    fn commit(&self, identity: &SlaveIdentity);
+}
+
+impl<'a> core::fmt::Debug for dyn EepromCache + 'a {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("EepromCache")
+    }
+}
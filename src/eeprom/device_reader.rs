@@ -6,7 +6,7 @@ use crate::{
     error::{EepromError, Error},
     fmt,
     register::RegisterAddress,
-    Client, Command,
+    Client, Command, SlaveIdentity,
 };
 
 /// The address of the first proper category, positioned after the fixed fields defined in ETG2010
@@ -20,6 +20,12 @@ pub(crate) const SII_FIRST_CATEGORY_START: u16 = 0x0040u16;
 pub struct DeviceEeprom<'slave> {
     client: &'slave Client<'slave>,
     configured_address: u16,
+
+    /// Device identity used to look up cached EEPROM data, if the client has a cache.
+    cache_key: Option<SlaveIdentity>,
+
+    /// Buffer to return chunks in.
+    buf: [u8; 8],
 }
 
 impl<'slave> DeviceEeprom<'slave> {
@@ -28,6 +34,17 @@ impl<'slave> DeviceEeprom<'slave> {
         Self {
             client,
             configured_address,
+            cache_key: None,
+            buf: [0u8; 8],
+        }
+    }
+
+    /// Serve reads from the client's EEPROM cache, if one is configured and holds an image for the
+    /// given device.
+    pub(crate) fn with_cache_key(self, identity: SlaveIdentity) -> Self {
+        Self {
+            cache_key: Some(identity),
+            ..self
         }
     }
 }
@@ -37,6 +54,14 @@ impl<'slave> EepromDataProvider for DeviceEeprom<'slave> {
         &mut self,
         start_word: u16,
     ) -> Result<impl core::ops::Deref<Target = [u8]>, Error> {
+        if let (Some(cache), Some(identity)) = (self.client.eeprom_cache, self.cache_key.as_ref()) {
+            if let Some(len) = cache.read(identity, start_word, &mut self.buf) {
+                fmt::trace!("Read addr {:#06x} from cache", start_word);
+
+                return Ok(&self.buf[0..len]);
+            }
+        }
+
         Command::fpwr(self.configured_address, RegisterAddress::SiiControl.into())
             .wrap(self.client)
             .send_receive(SiiRequest::read(start_word))
@@ -59,18 +84,21 @@ impl<'slave> EepromDataProvider for DeviceEeprom<'slave> {
         })
         .await?;
 
-        Command::fprd(self.configured_address, RegisterAddress::SiiData.into())
+        let data = Command::fprd(self.configured_address, RegisterAddress::SiiData.into())
             .wrap(self.client)
             .receive_slice(status.read_size.chunk_len())
-            .await
-            .map(|data| {
-                #[cfg(not(feature = "defmt"))]
-                fmt::trace!("Read addr {:#06x}: {:02x?}", start_word, data);
-                #[cfg(feature = "defmt")]
-                fmt::trace!("Read addr {:#06x}: {=[u8]}", start_word, data);
-
-                data
-            })
+            .await?;
+
+        #[cfg(not(feature = "defmt"))]
+        fmt::trace!("Read addr {:#06x}: {:02x?}", start_word, data);
+        #[cfg(feature = "defmt")]
+        fmt::trace!("Read addr {:#06x}: {=[u8]}", start_word, data);
+
+        let buf = &mut self.buf[0..data.len()];
+
+        buf.copy_from_slice(&data);
+
+        Ok(buf)
     }
 
     async fn clear_errors(&self) -> Result<(), Error> {
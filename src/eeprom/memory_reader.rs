@@ -0,0 +1,171 @@
+//! An EEPROM reader backed by an in-memory EEPROM image.
+//!
+//! Unlike the file-backed `EepromFile`, this reader does not require
+//! `std` so can be used to provide a fixed SII image on `no_std` targets, as well as in unit tests
+//! where no network is available.
+
+use crate::{
+    eeprom::EepromDataProvider,
+    error::{EepromError, Error},
+    fmt,
+};
+
+/// Backing store for an [`EepromMemory`].
+#[derive(Debug, Copy, Clone)]
+enum Image<'a> {
+    /// Raw bytes, as they would be read from the device.
+    Bytes(&'a [u8]),
+    /// EEPROM words. Each word is serialised as little endian bytes when read.
+    Words(&'a [u16]),
+}
+
+impl<'a> Image<'a> {
+    /// Length of the image in bytes.
+    fn len_bytes(&self) -> usize {
+        match self {
+            Image::Bytes(bytes) => bytes.len(),
+            Image::Words(words) => words.len() * 2,
+        }
+    }
+
+    /// Get the byte at the given byte address.
+    fn byte(&self, addr: usize) -> u8 {
+        match self {
+            Image::Bytes(bytes) => bytes[addr],
+            Image::Words(words) => words[addr / 2].to_le_bytes()[addr % 2],
+        }
+    }
+}
+
+/// An EEPROM data provider that reads from a fixed image in memory.
+///
+/// Chunks of either 4 or 8 bytes are returned, mirroring the two read sizes supported by real
+/// devices.
+#[derive(Debug, Copy, Clone)]
+pub struct EepromMemory<'a, const CHUNK: usize = 8> {
+    image: Image<'a>,
+    buf: [u8; CHUNK],
+}
+
+impl<'a> EepromMemory<'a, 8> {
+    /// Create a reader over a raw EEPROM byte image that returns chunks of 8 bytes.
+    pub const fn new(image: &'a [u8]) -> Self {
+        Self {
+            image: Image::Bytes(image),
+            buf: [0u8; 8],
+        }
+    }
+
+    /// Create a reader over a list of EEPROM words that returns chunks of 8 bytes.
+    pub const fn from_words(image: &'a [u16]) -> Self {
+        Self {
+            image: Image::Words(image),
+            buf: [0u8; 8],
+        }
+    }
+}
+
+impl<'a> EepromMemory<'a, 4> {
+    /// Create a reader over a raw EEPROM byte image that returns chunks of 4 bytes.
+    pub const fn new_short(image: &'a [u8]) -> Self {
+        Self {
+            image: Image::Bytes(image),
+            buf: [0u8; 4],
+        }
+    }
+
+    /// Create a reader over a list of EEPROM words that returns chunks of 4 bytes.
+    pub const fn from_words_short(image: &'a [u16]) -> Self {
+        Self {
+            image: Image::Words(image),
+            buf: [0u8; 4],
+        }
+    }
+}
+
+impl<'a, const CHUNK: usize> EepromDataProvider for EepromMemory<'a, CHUNK> {
+    async fn read_chunk(
+        &mut self,
+        start_word: u16,
+    ) -> Result<impl core::ops::Deref<Target = [u8]>, Error> {
+        let start = usize::from(start_word) * 2;
+        let image_len = self.image.len_bytes();
+
+        if start >= image_len {
+            fmt::error!(
+                "EEPROM read at word {:#06x} is past end of {} byte image",
+                start_word,
+                image_len
+            );
+
+            return Err(Error::Eeprom(EepromError::SectionOverrun));
+        }
+
+        // A read near the end of the image may return fewer bytes than the chunk length.
+        let buf_len = CHUNK.min(image_len - start);
+
+        let buf = &mut self.buf[0..buf_len];
+
+        for (i, byte) in buf.iter_mut().enumerate() {
+            *byte = self.image.byte(start + i);
+        }
+
+        Ok(buf)
+    }
+
+    async fn clear_errors(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eeprom::ChunkReader;
+    use embedded_io_async::Read;
+
+    #[tokio::test]
+    async fn words_are_little_endian() {
+        let words = [0x0201u16, 0x0403, 0x0605, 0x0807, 0x0a09];
+
+        let mut e = EepromMemory::from_words(&words);
+
+        assert_eq!(
+            e.read_chunk(0).await.as_deref(),
+            Ok([0x01u8, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08].as_slice())
+        );
+
+        // Short read at end of image
+        assert_eq!(
+            e.read_chunk(4).await.as_deref(),
+            Ok([0x09u8, 0x0a].as_slice())
+        );
+
+        assert_eq!(
+            e.read_chunk(5).await.as_deref(),
+            Err(&Error::Eeprom(EepromError::SectionOverrun))
+        );
+    }
+
+    #[tokio::test]
+    async fn bytes_and_words_match() {
+        let bytes = [0x11u8, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa];
+        let words = [0x2211u16, 0x4433, 0x6655, 0x8877, 0xaa99];
+
+        let mut from_bytes = [0u8; 7];
+        let mut from_words = [0u8; 7];
+
+        ChunkReader::new(EepromMemory::new_short(&bytes), 1, 4)
+            .read_exact(&mut from_bytes)
+            .await
+            .expect("bytes");
+
+        ChunkReader::new(EepromMemory::from_words(&words), 1, 4)
+            .read_exact(&mut from_words)
+            .await
+            .expect("words");
+
+        assert_eq!(from_bytes, from_words);
+        assert_eq!(from_bytes, [0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99]);
+    }
+}
@@ -6,7 +6,11 @@ use crate::{
 };
 use embedded_io_async::{ErrorType, Read, ReadExactError};
 
+pub mod cache;
 pub mod device_reader;
+// Only used in tests and by consumers of `ethercrab::internals`.
+#[cfg_attr(not(feature = "__internals"), allow(unused))]
+pub mod memory_reader;
 pub mod types;
 
 #[cfg(feature = "std")]
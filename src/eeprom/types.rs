@@ -284,6 +284,40 @@ pub struct FmmuEx {
     pub sync_manager: u8,
 }
 
+/// A Distributed Clock operation mode, read from the SII DC category.
+///
+/// Defined in ETG2010 Table 16 "Structure Category DC". Each DC category contains one or more of
+/// these 24 byte elements, mirroring the `Dc/OpMode` elements of a device's ESI file.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 24)]
+pub struct DcOpMode {
+    /// SYNC0 cycle time in ns. A value of `0` means the cycle time is defined by the master.
+    #[wire(bytes = 4)]
+    pub cycle_time_0: u32,
+    /// SYNC0 shift time in ns.
+    #[wire(bytes = 4)]
+    pub shift_time_0: u32,
+    /// SYNC1 shift time in ns.
+    #[wire(bytes = 4)]
+    pub shift_time_1: u32,
+    /// SYNC1 cycle time as a multiple of the SYNC0 cycle time.
+    #[wire(bytes = 2)]
+    pub sync1_cycle_factor: i16,
+    /// Value written to the DC activation registers `0x0980:0x0981`.
+    #[wire(bytes = 2)]
+    pub assign_activate: u16,
+    /// SYNC0 cycle time as a multiple of the bus cycle time.
+    #[wire(bytes = 2)]
+    pub sync0_cycle_factor: i16,
+    /// Operation mode name string index.
+    #[wire(bytes = 1)]
+    pub name_string_idx: u8,
+    /// Operation mode description string index.
+    #[wire(bytes = 1, post_skip_bytes = 4)]
+    pub desc_string_idx: u8,
+}
+
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct PortStatuses(pub [PortStatus; 4]);
@@ -766,6 +800,33 @@ impl core::fmt::Debug for DefaultMailbox {
     }
 }
 
+/// Bootstrap mailbox configuration, used instead of the standard mailbox in the BOOT state.
+///
+/// Defined in ETG2010 Table 2, words `0x0014` to `0x0017`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 8)]
+pub struct BootstrapMailbox {
+    /// Master to slave receive mailbox address offset.
+    #[wire(bytes = 2)]
+    pub slave_receive_offset: u16,
+    /// Master to slave receive mailbox size.
+    #[wire(bytes = 2)]
+    pub slave_receive_size: u16,
+    /// Slave to master send mailbox address offset.
+    #[wire(bytes = 2)]
+    pub slave_send_offset: u16,
+    /// Slave to master send mailbox size.
+    #[wire(bytes = 2)]
+    pub slave_send_size: u16,
+}
+
+impl BootstrapMailbox {
+    pub fn has_mailbox(&self) -> bool {
+        self.slave_receive_size > 0 && self.slave_send_size > 0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,9 +1,11 @@
 //! EtherCrab error types.
 
-use crate::{command::Command, fmt, SlaveState};
+use crate::{command::Command, fmt, AlStatusCode, SlaveState};
+use atomic_refcell::AtomicRefCell;
 use core::{cell::BorrowError, num::TryFromIntError, str::Utf8Error};
 
 pub use crate::coe::abort_code::CoeAbortCode;
+pub use crate::foe::error_code::FoeErrorCode;
 
 /// An EtherCrab error.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -61,6 +63,48 @@ pub enum Error {
         /// Actual PDI length.
         desired_length: usize,
     },
+    /// A slave device's process data length does not match the length of the type used to read or
+    /// write it. This usually means the slave's PDO mapping does not match the type.
+    ProcessDataLength {
+        /// Slave address.
+        configured_address: u16,
+
+        /// Length of the slave's process data in bytes.
+        pdi_length: usize,
+
+        /// Packed length of the type in bytes.
+        type_length: usize,
+    },
+    /// A slave device reconfigured by [`SlaveGroup::recover`](crate::SlaveGroup::recover) maps
+    /// different process data than when its group was configured, so it cannot be returned to the
+    /// group's PDI.
+    ProcessDataChanged {
+        /// Slave address.
+        configured_address: u16,
+    },
+    /// The working counter of a group's process data cycle did not match the working counter
+    /// expected when every slave device in the group is in the group's state.
+    ///
+    /// Unlike [`Error::WorkingCounter`], process data was still exchanged with the slaves that
+    /// responded.
+    GroupWorkingCounter {
+        /// The expected working counter value.
+        expected: u16,
+        /// The actual value received.
+        received: u16,
+        /// The number of consecutive cycles with an unexpected working counter, including this
+        /// one.
+        bad_cycles: u32,
+    },
+    /// A mapped PDO entry is a slave input so cannot be written.
+    ReadOnlySignal {
+        /// Slave address.
+        configured_address: u16,
+        /// Object index.
+        index: u16,
+        /// Object sub-index.
+        sub_index: u8,
+    },
     /// An item in a list could not be found.
     NotFound {
         /// Item kind.
@@ -73,8 +117,32 @@ pub enum Error {
     Internal,
     /// There is a problem with the discovered EtherCAT slave topology.
     Topology,
-    /// An error was read back from one or more slaves when attempting to transition to a new state.
-    StateTransition,
+    /// More than one slave device would be given the same configured address, e.g. because of
+    /// duplicate station aliases when using
+    /// [`ClientConfig::alias_addressing`](crate::ClientConfig::alias_addressing).
+    AddressConflict {
+        /// The conflicting address.
+        configured_address: u16,
+    },
+    /// The discovered network does not match the expected network passed to
+    /// [`Client::init_expected`](crate::Client::init_expected).
+    ///
+    /// Every deviation is written to the list passed to `init_expected`.
+    ///
+    /// This is also reported by [`SlaveGroup::recover`](crate::SlaveGroup::recover) when a device
+    /// found in place of a lost slave has a different identity.
+    TopologyMismatch {
+        /// Position of the first deviating slave device in the network.
+        position: usize,
+        /// The number of deviating positions.
+        count: usize,
+    },
+    /// One or more slaves reported an error or did not reach the requested state when attempting to
+    /// transition to a new state.
+    ///
+    /// The AL status error flag of every failed slave has been acknowledged, so the transition can
+    /// be requested again. Each failed slave is stored in the client's [`StateErrors`], if set.
+    StateTransition(StateTransitionError),
     /// An unknown slave device was encountered during device discovery/initialisation.
     UnknownSlave,
     /// An invalid state was encountered.
@@ -130,12 +198,54 @@ impl core::fmt::Display for Error {
                 "Process Data Image is too long ({} bytes), max length is {}",
                 desired_length, max_length
             ),
+            Error::ProcessDataLength {
+                configured_address,
+                pdi_length,
+                type_length,
+            } => write!(
+                f,
+                "slave {:#06x} process data is {} bytes but type is {} bytes",
+                configured_address, pdi_length, type_length
+            ),
+            Error::ProcessDataChanged { configured_address } => write!(
+                f,
+                "slave {:#06x} process data mapping changed",
+                configured_address
+            ),
+            Error::GroupWorkingCounter {
+                expected,
+                received,
+                bad_cycles,
+            } => write!(
+                f,
+                "group working counter expected {}, got {} for {} cycle(s)",
+                expected, received, bad_cycles
+            ),
+            Error::ReadOnlySignal {
+                configured_address,
+                index,
+                sub_index,
+            } => write!(
+                f,
+                "slave {:#06x} PDO entry {:#06x}:{} is an input and cannot be written",
+                configured_address, index, sub_index
+            ),
             Error::NotFound { item, index } => {
                 write!(f, "item kind {:?} not found (index: {:?})", item, index)
             }
             Error::Internal => f.write_str("internal error"),
             Error::Topology => f.write_str("topology"),
-            Error::StateTransition => f.write_str("a slave failed to transition to a new state"),
+            Error::AddressConflict { configured_address } => write!(
+                f,
+                "address {:#06x} used by more than one slave",
+                configured_address
+            ),
+            Error::TopologyMismatch { position, count } => write!(
+                f,
+                "topology mismatch at position {} ({} deviations)",
+                position, count
+            ),
+            Error::StateTransition(e) => write!(f, "state transition: {}", e),
             Error::UnknownSlave => f.write_str("unknown slave device"),
             Error::InvalidState {
                 expected,
@@ -157,6 +267,231 @@ impl From<BorrowError> for Error {
     }
 }
 
+/// A slave device that failed to transition to a requested state.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SlaveStateError {
+    /// Slave address.
+    pub configured_address: u16,
+    /// The state the slave remained in.
+    pub current_state: SlaveState,
+    /// The state that was requested.
+    pub requested_state: SlaveState,
+    /// The reason for the failure, read from the slave's AL status code register.
+    ///
+    /// This is [`AlStatusCode::NoError`] if the slave did not report an error but did not reach
+    /// the requested state before the transition timed out.
+    pub status_code: AlStatusCode,
+}
+
+impl core::fmt::Display for SlaveStateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "slave {:#06x} failed to transition from {} to {}: {}",
+            self.configured_address, self.current_state, self.requested_state, self.status_code
+        )
+    }
+}
+
+/// One or more slave devices failed to transition to a requested state.
+///
+/// Every failed slave is logged, and stored in the client's [`StateErrors`] if one is set with
+/// [`Client::with_state_errors`](crate::Client::with_state_errors).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StateTransitionError {
+    requested_state: SlaveState,
+    total: u16,
+}
+
+impl StateTransitionError {
+    /// Add the failures in `other` to the failures in `errors`.
+    pub(crate) fn merge(errors: &mut Option<Self>, other: Self) {
+        match errors {
+            Some(errors) => errors.total = errors.total.saturating_add(other.total),
+            None => *errors = Some(other),
+        }
+    }
+
+    /// The state that was requested.
+    pub fn requested_state(&self) -> SlaveState {
+        self.requested_state
+    }
+
+    /// The total number of slaves that failed to transition.
+    pub fn total(&self) -> usize {
+        usize::from(self.total)
+    }
+}
+
+impl From<SlaveStateError> for StateTransitionError {
+    fn from(failure: SlaveStateError) -> Self {
+        Self {
+            requested_state: failure.requested_state,
+            total: 1,
+        }
+    }
+}
+
+impl From<SlaveStateError> for Error {
+    fn from(failure: SlaveStateError) -> Self {
+        Self::StateTransition(failure.into())
+    }
+}
+
+impl core::fmt::Display for StateTransitionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} slave(s) failed to reach {}",
+            self.total, self.requested_state
+        )
+    }
+}
+
+/// Storage for every slave device that failed to transition to a requested state.
+///
+/// Set with [`Client::with_state_errors`](crate::Client::with_state_errors). When a state
+/// transition fails, each failed slave is added here and [`Error::StateTransition`] holds the
+/// number of failed slaves. Up to `N` failures are kept until they are removed with
+/// [`take`](StateErrors::take). Failures that don't fit are only logged.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ethercrab::{
+///     error::{Error, StateErrors},
+///     Client, ClientConfig, PduStorage, SlaveGroup, Timeouts,
+/// };
+///
+/// static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+/// static STATE_ERRORS: StateErrors<16> = StateErrors::new();
+///
+/// let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+///
+/// let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default())
+///     .with_state_errors(&STATE_ERRORS);
+///
+/// # async {
+/// let group = client
+///     .init_single_group::<16, 64>()
+///     .await
+///     .expect("Init");
+///
+/// if let Err(Error::StateTransition(_)) = group.into_op(&client).await {
+///     for failure in STATE_ERRORS.take() {
+///         eprintln!("{}", failure);
+///     }
+/// }
+/// # };
+/// ```
+#[derive(Debug, Default)]
+pub struct StateErrors<const N: usize> {
+    failures: AtomicRefCell<heapless::Vec<SlaveStateError, N>>,
+}
+
+impl<const N: usize> StateErrors<N> {
+    /// Create empty storage.
+    pub const fn new() -> Self {
+        Self {
+            failures: AtomicRefCell::new(heapless::Vec::new()),
+        }
+    }
+
+    /// Remove and return every stored failure, oldest first.
+    pub fn take(&self) -> heapless::Vec<SlaveStateError, N> {
+        core::mem::take(&mut *self.failures.borrow_mut())
+    }
+}
+
+/// Type erased [`StateErrors`], so the client doesn't need to know its capacity.
+pub(crate) trait StateErrorStore: Sync {
+    /// Store a failure, returning `false` if there is no space left for it.
+    fn push(&self, failure: SlaveStateError) -> bool;
+}
+
+impl<'a> core::fmt::Debug for dyn StateErrorStore + 'a {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("StateErrors")
+    }
+}
+
+impl<const N: usize> StateErrorStore for StateErrors<N> {
+    fn push(&self, failure: SlaveStateError) -> bool {
+        self.failures.borrow_mut().push(failure).is_ok()
+    }
+}
+
+/// An error encountered when loading an EtherCAT XML file, e.g. an ESI or ENI file.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlError {
+    /// The file could not be read.
+    Io(std::io::ErrorKind),
+    /// The file is not valid XML.
+    Parse(String),
+    /// The document root is not the expected element.
+    InvalidRoot {
+        /// Expected root element name.
+        expected: &'static str,
+        /// Actual root element name.
+        actual: String,
+    },
+    /// A required element is missing.
+    Missing {
+        /// The element that should contain the missing element.
+        parent: String,
+        /// The name of the missing element.
+        name: &'static str,
+    },
+    /// A value could not be parsed.
+    InvalidValue {
+        /// The name of the element or attribute.
+        name: &'static str,
+        /// The raw value.
+        value: String,
+    },
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for XmlError {}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for XmlError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            XmlError::Io(kind) => write!(f, "failed to read file: {}", kind),
+            XmlError::Parse(e) => write!(f, "invalid XML: {}", e),
+            XmlError::InvalidRoot { expected, actual } => {
+                write!(f, "expected root element {}, got {}", expected, actual)
+            }
+            XmlError::Missing { parent, name } => {
+                write!(f, "element {} is missing from {}", name, parent)
+            }
+            XmlError::InvalidValue { name, value } => {
+                write!(f, "invalid value for {}: {:?}", name, value)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for XmlError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value.kind())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<roxmltree::Error> for XmlError {
+    fn from(value: roxmltree::Error) -> Self {
+        Self::Parse(value.to_string())
+    }
+}
+
 /// The kind of item being looked for.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -176,6 +511,10 @@ pub enum Item {
     FmmuEx,
     /// A user-defined slave group.
     Group,
+    /// Distributed Clock operation mode from a slave's EEPROM or ESI file.
+    DcOpMode,
+    /// A hot connect segment declared on a slave group.
+    HotConnectSegment,
 }
 
 /// Low-level PDU (Process Data Unit) error.
@@ -222,7 +561,7 @@ impl core::fmt::Display for PduError {
     }
 }
 
-/// CoE mailbox error.
+/// Mailbox error.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
@@ -252,6 +591,26 @@ pub enum MailboxError {
         /// The subindex used in the operation.
         sub_index: u8,
     },
+    /// An FoE transfer was aborted by the slave.
+    FoeAborted {
+        /// Error code.
+        code: FoeErrorCode,
+        /// The packet number being transferred when the error occurred.
+        packet_number: u32,
+    },
+    /// The response to an FoE request is invalid.
+    FoeResponseInvalid {
+        /// The packet number being transferred.
+        packet_number: u32,
+    },
+    /// The SDO access is not supported by EtherCrab, e.g. a complete access write or a write of
+    /// more than 4 bytes.
+    Unsupported {
+        /// The address used in the operation.
+        address: u16,
+        /// The subindex used in the operation.
+        sub_index: u8,
+    },
 }
 
 impl core::fmt::Display for MailboxError {
@@ -273,6 +632,18 @@ impl core::fmt::Display for MailboxError {
                 "{:#06x}:{} invalid response from device",
                 address, sub_index
             ),
+            MailboxError::FoeAborted {
+                code,
+                packet_number,
+            } => write!(f, "FoE packet {} aborted: {}", packet_number, code),
+            MailboxError::FoeResponseInvalid { packet_number } => write!(
+                f,
+                "FoE packet {} invalid response from device",
+                packet_number
+            ),
+            MailboxError::Unsupported { address, sub_index } => {
+                write!(f, "{:#06x}:{} access is not supported", address, sub_index)
+            }
         }
     }
 }
@@ -413,3 +784,56 @@ impl From<ethercrab_wire::WireError> for Error {
         Self::Wire(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_transition_failures() {
+        let failure = |configured_address| SlaveStateError {
+            configured_address,
+            current_state: SlaveState::PreOp,
+            requested_state: SlaveState::SafeOp,
+            status_code: AlStatusCode::InvalidOutputConfiguration,
+        };
+
+        let mut e = None;
+
+        for i in 0..10 {
+            StateTransitionError::merge(&mut e, failure(0x1000 + i).into());
+        }
+
+        let e = e.unwrap();
+
+        assert_eq!(e.total(), 10);
+        assert_eq!(e.requested_state(), SlaveState::SafeOp);
+
+        let single = StateTransitionError::from(failure(0x1001));
+
+        assert_eq!(single.total(), 1);
+        assert_eq!(Error::from(failure(0x1001)), Error::StateTransition(single));
+    }
+
+    #[test]
+    fn state_errors() {
+        let failure = |configured_address| SlaveStateError {
+            configured_address,
+            current_state: SlaveState::PreOp,
+            requested_state: SlaveState::SafeOp,
+            status_code: AlStatusCode::NoError,
+        };
+
+        let errors = StateErrors::<2>::new();
+
+        assert!(errors.push(failure(0x1000)));
+        assert!(errors.push(failure(0x1001)));
+        assert!(!errors.push(failure(0x1002)));
+
+        assert_eq!(
+            errors.take().as_slice(),
+            &[failure(0x1000), failure(0x1001)]
+        );
+        assert!(errors.take().is_empty());
+    }
+}
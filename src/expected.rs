@@ -0,0 +1,378 @@
+//! Expected network topology, checked during [`Client::init_expected`](crate::Client::init_expected).
+
+use crate::{Slave, SlaveIdentity};
+
+/// A slave device expected at a given position in the network.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ExpectedSlave {
+    /// Vendor ID.
+    pub vendor_id: u32,
+    /// Product ID.
+    pub product_id: u32,
+    /// Product revision. If this is `None`, any revision is accepted.
+    pub revision: Option<u32>,
+    /// The port of a previous slave this slave should be connected to. If this is `None`, the
+    /// connection is not checked.
+    pub parent: Option<ExpectedPort>,
+}
+
+impl ExpectedSlave {
+    /// Expect a slave with the given vendor and product ID, of any revision.
+    pub const fn new(vendor_id: u32, product_id: u32) -> Self {
+        Self {
+            vendor_id,
+            product_id,
+            revision: None,
+            parent: None,
+        }
+    }
+
+    /// Require an exact revision.
+    pub const fn with_revision(self, revision: u32) -> Self {
+        Self {
+            revision: Some(revision),
+            ..self
+        }
+    }
+
+    /// Require that this slave is connected to the given port of the slave at position `index`.
+    ///
+    /// Port numbers are EtherCAT port numbers, e.g. `1` for port B.
+    pub const fn with_parent(self, index: usize, port: usize) -> Self {
+        Self {
+            parent: Some(ExpectedPort { index, port }),
+            ..self
+        }
+    }
+
+    /// Compare a discovered identity against this slave's vendor ID, product ID and revision.
+    pub(crate) fn identity_mismatch(&self, found: SlaveIdentity) -> Option<TopologyMismatchKind> {
+        if self.vendor_id != found.vendor_id || self.product_id != found.product_id {
+            Some(TopologyMismatchKind::Identity {
+                expected: *self,
+                found,
+            })
+        } else {
+            self.revision
+                .filter(|revision| *revision != found.revision)
+                .map(|revision| TopologyMismatchKind::Revision {
+                    expected: revision,
+                    found,
+                })
+        }
+    }
+}
+
+impl From<SlaveIdentity> for ExpectedSlave {
+    fn from(value: SlaveIdentity) -> Self {
+        Self::new(value.vendor_id, value.product_id).with_revision(value.revision)
+    }
+}
+
+/// A connection to a port of another slave device.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ExpectedPort {
+    /// Position of the previous slave in the network.
+    pub index: usize,
+    /// EtherCAT port number on the previous slave, from `0` (port A) to `3` (port D).
+    pub port: usize,
+}
+
+impl core::fmt::Display for ExpectedPort {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "port {} of slave {}", self.port, self.index)
+    }
+}
+
+/// A difference between the expected and discovered network.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TopologyMismatch {
+    /// Position of the slave in the network, starting at `0`.
+    pub position: usize,
+    /// The kind of mismatch.
+    pub kind: TopologyMismatchKind,
+}
+
+/// The kind of a [`TopologyMismatch`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TopologyMismatchKind {
+    /// A slave was expected at this position but none was discovered.
+    Missing {
+        /// The expected slave.
+        expected: ExpectedSlave,
+    },
+    /// A slave was discovered at a position beyond the end of the expected network.
+    Extra {
+        /// The discovered slave identity.
+        found: SlaveIdentity,
+    },
+    /// The discovered slave has a different vendor or product ID.
+    Identity {
+        /// The expected slave.
+        expected: ExpectedSlave,
+        /// The discovered slave identity.
+        found: SlaveIdentity,
+    },
+    /// The discovered slave is the expected product but has the wrong revision.
+    Revision {
+        /// The expected revision.
+        expected: u32,
+        /// The discovered slave identity.
+        found: SlaveIdentity,
+    },
+    /// The discovered slave is connected to a different port or slave than expected.
+    Connection {
+        /// The expected connection.
+        expected: ExpectedPort,
+        /// The discovered connection. This is `None` for the first slave in the network.
+        found: Option<ExpectedPort>,
+    },
+}
+
+impl core::fmt::Display for TopologyMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "position {}: ", self.position)?;
+
+        match self.kind {
+            TopologyMismatchKind::Missing { expected } => write!(
+                f,
+                "missing slave (vendor {:#010x}, product {:#010x})",
+                expected.vendor_id, expected.product_id
+            ),
+            TopologyMismatchKind::Extra { found } => write!(f, "extra slave ({})", found),
+            TopologyMismatchKind::Identity { expected, found } => write!(
+                f,
+                "expected vendor {:#010x}, product {:#010x}, found ({})",
+                expected.vendor_id, expected.product_id, found
+            ),
+            TopologyMismatchKind::Revision { expected, found } => write!(
+                f,
+                "expected revision {}, found revision {}",
+                expected, found.revision
+            ),
+            TopologyMismatchKind::Connection { expected, found } => match found {
+                Some(found) => write!(f, "expected connection to {}, found {}", expected, found),
+                None => write!(f, "expected connection to {}, found master", expected),
+            },
+        }
+    }
+}
+
+/// Find the port on a parent slave that the given slave is connected to.
+fn connection(slave: &Slave, discovered: &[Slave]) -> Option<ExpectedPort> {
+    let parent = discovered
+        .iter()
+        .find(|parent| Some(parent.index) == slave.parent_index)?;
+
+    parent
+        .ports
+        .port_assigned_to(slave)
+        .map(|port| ExpectedPort {
+            index: parent.index,
+            port: port.number,
+        })
+}
+
+/// Compare discovered slaves against the expected network, position by position.
+///
+/// Connections can only be checked once parent relationships have been assigned during DC
+/// configuration.
+pub(crate) fn mismatches<'a>(
+    expected: &'a [ExpectedSlave],
+    discovered: &'a [Slave],
+) -> impl Iterator<Item = TopologyMismatch> + 'a {
+    let len = expected.len().max(discovered.len());
+
+    (0..len).filter_map(move |position| {
+        let kind = match (expected.get(position), discovered.get(position)) {
+            (Some(expected), Some(slave)) => {
+                expected.identity_mismatch(slave.identity).or_else(|| {
+                    expected.parent.and_then(|parent| {
+                        let found = connection(slave, discovered);
+
+                        (found != Some(parent)).then_some(TopologyMismatchKind::Connection {
+                            expected: parent,
+                            found,
+                        })
+                    })
+                })
+            }
+            (Some(expected), None) => Some(TopologyMismatchKind::Missing {
+                expected: *expected,
+            }),
+            (None, Some(slave)) => Some(TopologyMismatchKind::Extra {
+                found: slave.identity,
+            }),
+            (None, None) => None,
+        };
+
+        kind.map(|kind| TopologyMismatch { position, kind })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slave::ports::tests::make_ports;
+
+    const EK1100: SlaveIdentity = SlaveIdentity {
+        vendor_id: 0x2,
+        product_id: 0x044c2c52,
+        revision: 0x00110000,
+        serial: 0,
+    };
+
+    const EL2004: SlaveIdentity = SlaveIdentity {
+        vendor_id: 0x2,
+        product_id: 0x07d43052,
+        revision: 0x00100000,
+        serial: 0,
+    };
+
+    /// An EK1100 with two EL2004 modules.
+    fn discovered() -> [Slave; 3] {
+        let mut coupler = Slave {
+            index: 0,
+            identity: EK1100,
+            ports: make_ports(true, true, false, false),
+            ..Slave::default()
+        };
+
+        let mut first = Slave {
+            index: 1,
+            identity: EL2004,
+            parent_index: Some(0),
+            ports: make_ports(true, true, false, false),
+            ..Slave::default()
+        };
+
+        let second = Slave {
+            index: 2,
+            identity: EL2004,
+            parent_index: Some(1),
+            ports: make_ports(true, false, false, false),
+            ..Slave::default()
+        };
+
+        coupler.ports.assign_next_downstream_port(1);
+        first.ports.assign_next_downstream_port(2);
+
+        [coupler, first, second]
+    }
+
+    #[test]
+    fn matching() {
+        let expected = [
+            ExpectedSlave::from(EK1100),
+            ExpectedSlave::new(0x2, 0x07d43052).with_parent(0, 3),
+            ExpectedSlave::new(0x2, 0x07d43052).with_parent(1, 3),
+        ];
+
+        assert_eq!(mismatches(&expected, &discovered()).next(), None);
+    }
+
+    #[test]
+    fn missing_and_extra() {
+        let discovered = discovered();
+
+        let expected = [ExpectedSlave::from(EK1100), ExpectedSlave::from(EL2004)];
+
+        assert_eq!(
+            mismatches(&expected, &discovered).collect::<Vec<_>>(),
+            [TopologyMismatch {
+                position: 2,
+                kind: TopologyMismatchKind::Extra { found: EL2004 }
+            }]
+        );
+
+        let expected = [
+            ExpectedSlave::from(EK1100),
+            ExpectedSlave::from(EL2004),
+            ExpectedSlave::from(EL2004),
+            ExpectedSlave::from(EL2004),
+        ];
+
+        assert_eq!(
+            mismatches(&expected, &discovered).collect::<Vec<_>>(),
+            [TopologyMismatch {
+                position: 3,
+                kind: TopologyMismatchKind::Missing {
+                    expected: ExpectedSlave::from(EL2004)
+                }
+            }]
+        );
+    }
+
+    #[test]
+    fn identity_and_revision() {
+        let expected = [
+            ExpectedSlave::from(EL2004),
+            ExpectedSlave::from(EL2004).with_revision(0x00120000),
+            ExpectedSlave::from(EL2004),
+        ];
+
+        let mismatches = mismatches(&expected, &discovered()).collect::<Vec<_>>();
+
+        assert_eq!(
+            mismatches,
+            [
+                TopologyMismatch {
+                    position: 0,
+                    kind: TopologyMismatchKind::Identity {
+                        expected: ExpectedSlave::from(EL2004),
+                        found: EK1100
+                    }
+                },
+                TopologyMismatch {
+                    position: 1,
+                    kind: TopologyMismatchKind::Revision {
+                        expected: 0x00120000,
+                        found: EL2004
+                    }
+                }
+            ]
+        );
+
+        assert_eq!(
+            mismatches[1].to_string(),
+            "position 1: expected revision 1179648, found revision 1048576"
+        );
+    }
+
+    #[test]
+    fn connection() {
+        let expected = [
+            ExpectedSlave::from(EK1100).with_parent(0, 1),
+            ExpectedSlave::from(EL2004).with_parent(0, 3),
+            ExpectedSlave::from(EL2004).with_parent(0, 1),
+        ];
+
+        assert_eq!(
+            mismatches(&expected, &discovered()).collect::<Vec<_>>(),
+            [
+                TopologyMismatch {
+                    position: 0,
+                    kind: TopologyMismatchKind::Connection {
+                        expected: ExpectedPort { index: 0, port: 1 },
+                        found: None
+                    }
+                },
+                TopologyMismatch {
+                    position: 2,
+                    kind: TopologyMismatchKind::Connection {
+                        expected: ExpectedPort { index: 0, port: 1 },
+                        found: Some(ExpectedPort { index: 1, port: 3 })
+                    }
+                }
+            ]
+        );
+    }
+}
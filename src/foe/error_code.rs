@@ -0,0 +1,60 @@
+/// FoE error codes, as defined in ETG1000.6.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u32)]
+pub enum FoeErrorCode {
+    /// Not defined
+    NotDefined = 0x8000,
+    /// Not found
+    NotFound = 0x8001,
+    /// Access denied
+    AccessDenied = 0x8002,
+    /// Disk full
+    DiskFull = 0x8003,
+    /// Illegal
+    Illegal = 0x8004,
+    /// Packet number wrong
+    PacketNumberWrong = 0x8005,
+    /// Already exists
+    AlreadyExists = 0x8006,
+    /// No user
+    NoUser = 0x8007,
+    /// Bootstrap only
+    BootstrapOnly = 0x8008,
+    /// Not bootstrap
+    NotBootstrap = 0x8009,
+    /// No rights
+    NoRights = 0x800A,
+    /// Program error
+    ProgramError = 0x800B,
+
+    /// Unknown error code.
+    #[wire(catch_all)]
+    Unknown(u32),
+}
+
+impl core::fmt::Display for FoeErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let num = u32::from(*self);
+
+        f.write_fmt(format_args!("{:#06x}", num))?;
+        f.write_str(": ")?;
+
+        match self {
+            Self::NotDefined => f.write_str("Not defined"),
+            Self::NotFound => f.write_str("Not found"),
+            Self::AccessDenied => f.write_str("Access denied"),
+            Self::DiskFull => f.write_str("Disk full"),
+            Self::Illegal => f.write_str("Illegal"),
+            Self::PacketNumberWrong => f.write_str("Packet number wrong"),
+            Self::AlreadyExists => f.write_str("Already exists"),
+            Self::NoUser => f.write_str("No user"),
+            Self::BootstrapOnly => f.write_str("Bootstrap only"),
+            Self::NotBootstrap => f.write_str("Not bootstrap"),
+            Self::NoRights => f.write_str("No rights"),
+            Self::ProgramError => f.write_str("Program error"),
+            Self::Unknown(_) => f.write_str("Unknown code"),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! File access over EtherCAT (FoE).
+
+pub mod error_code;
+
+use crate::mailbox::{MailboxType, Priority};
+use ethercrab_wire::{EtherCrabWireSized, EtherCrabWireWrite, EtherCrabWireWriteSized};
+
+/// FoE operation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum FoeOpCode {
+    /// Read request.
+    Read = 0x01,
+    /// Write request.
+    Write = 0x02,
+    /// File data.
+    Data = 0x03,
+    /// Acknowledge.
+    Ack = 0x04,
+    /// Error.
+    Error = 0x05,
+    /// The receiver is busy and the last packet must be sent again.
+    Busy = 0x06,
+    /// Unknown operation.
+    #[wire(catch_all)]
+    Unknown(u8),
+}
+
+/// Mailbox header followed by the FoE header, which is the same for all FoE operations.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 12)]
+pub struct FoeHeader {
+    /// Mailbox data payload length.
+    #[wire(bytes = 2)]
+    pub length: u16,
+    #[wire(bytes = 2)]
+    pub address: u16,
+    #[wire(pre_skip = 6, bits = 2)]
+    pub priority: Priority,
+    #[wire(bits = 4)]
+    pub mailbox_type: MailboxType,
+    /// Mailbox counter from 1 to 7 inclusive.
+    #[wire(bits = 3, post_skip = 1)]
+    pub counter: u8,
+    #[wire(bytes = 1, post_skip_bytes = 1)]
+    pub op_code: FoeOpCode,
+    /// Password for read and write requests, packet number for data and acknowledge packets, or
+    /// error code for error packets.
+    #[wire(bytes = 4)]
+    pub value: u32,
+}
+
+/// An FoE request followed by its payload, e.g. a file name or a chunk of file data.
+#[derive(Debug, Copy, Clone)]
+pub struct FoeRequest<'a> {
+    pub header: FoeHeader,
+    pub payload: &'a [u8],
+}
+
+impl<'a> FoeRequest<'a> {
+    pub fn new(counter: u8, op_code: FoeOpCode, value: u32, payload: &'a [u8]) -> Self {
+        Self {
+            header: FoeHeader {
+                // Mailbox header is not included in the length
+                length: (FoeHeader::PACKED_LEN - 6 + payload.len()) as u16,
+                address: 0x0000,
+                priority: Priority::Lowest,
+                mailbox_type: MailboxType::Foe,
+                counter,
+                op_code,
+                value,
+            },
+            payload,
+        }
+    }
+}
+
+impl<'a> EtherCrabWireWrite for FoeRequest<'a> {
+    fn pack_to_slice_unchecked<'buf>(&self, buf: &'buf mut [u8]) -> &'buf [u8] {
+        let (header, rest) = buf.split_at_mut(FoeHeader::PACKED_LEN);
+
+        header.copy_from_slice(&self.header.pack());
+        rest[0..self.payload.len()].copy_from_slice(self.payload);
+
+        &buf[0..self.packed_len()]
+    }
+
+    fn packed_len(&self) -> usize {
+        FoeHeader::PACKED_LEN + self.payload.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::EtherCrabWireRead;
+
+    #[test]
+    fn write_request() {
+        let request = FoeRequest::new(2, FoeOpCode::Write, 0x1234_5678, b"app.bin");
+
+        let mut buf = [0u8; 32];
+
+        let packed = request.pack_to_slice(&mut buf).unwrap();
+
+        assert_eq!(
+            packed,
+            &[
+                // Mailbox header, length 13
+                0x0d, 0x00, 0x00, 0x00, 0x00, 0x24, //
+                // Op code, reserved
+                0x02, 0x00, //
+                // Password
+                0x78, 0x56, 0x34, 0x12, //
+                // File name
+                b'a', b'p', b'p', b'.', b'b', b'i', b'n',
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_ack() {
+        let raw = [
+            0x06, 0x00, 0x00, 0x00, 0x00, 0x34, 0x04, 0x00, 0x05, 0x00, 0x00, 0x00,
+        ];
+
+        assert_eq!(
+            FoeHeader::unpack_from_slice(&raw),
+            Ok(FoeHeader {
+                length: 6,
+                address: 0x0000,
+                priority: Priority::Lowest,
+                mailbox_type: MailboxType::Foe,
+                counter: 3,
+                op_code: FoeOpCode::Ack,
+                value: 5,
+            })
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! Per-slave configuration hooks called during state transitions.
+
+use crate::{error::Error, fmt, slave::SignalStore, Client, Slave, SlaveRef, Transition};
+use alloc::boxed::Box;
+use core::{future::Future, pin::Pin};
+
+/// The future returned by a [`SlaveHook`].
+pub type HookFuture<'a> = Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'a>>;
+
+/// Slave-specific configuration run by EtherCrab as each slave device changes state.
+///
+/// A hook can be registered on the [`Client`] with [`Client::with_slave_hook`] to be called for
+/// every slave on the network, or on a single [`SlaveGroup`](crate::SlaveGroup) with
+/// [`SlaveGroup::with_hook`](crate::SlaveGroup::with_hook). If both are registered, the client hook
+/// is called first. Hooks are `'static`, so the same hook can be registered in either place.
+///
+/// Hooks are called with the following transitions:
+///
+/// - [`Transition::INIT_TO_PRE_OP`] once the slave's mailboxes are configured and it has reached
+///   PRE-OP, during [`Client::init`] and its variants.
+/// - [`Transition::PRE_OP_TO_SAFE_OP`] before the group's PDI is configured in
+///   [`SlaveGroup::into_safe_op`](crate::SlaveGroup::into_safe_op), so PDO mappings written by the
+///   hook are used to size the PDI. This is equivalent to SOEM's `PO2SOconfig`.
+///
+/// Because hooks are part of initialisation, they are also called again whenever the network is
+/// re-initialised, when a slave is configured again by
+/// [`SlaveGroup::recover`](crate::SlaveGroup::recover), and when a hot connect segment is connected.
+///
+/// This trait is implemented for functions with the same signature as [`SlaveHook::call`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ethercrab::{
+///     error::Error, Client, ClientConfig, HookFuture, PduStorage, Slave, SlaveRef, Timeouts,
+///     Transition,
+/// };
+///
+/// static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+///
+/// fn configure_slave<'a>(
+///     transition: Transition,
+///     slave: &'a mut SlaveRef<'_, &'_ mut Slave>,
+/// ) -> HookFuture<'a> {
+///     Box::pin(async move {
+///         if transition == Transition::PRE_OP_TO_SAFE_OP && slave.name() == "EL3004" {
+///             // Disable the filter on the first channel
+///             slave.sdo_write(0x8000, 0x06, 0u8).await?;
+///         }
+///
+///         Ok(())
+///     })
+/// }
+///
+/// let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+///
+/// let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default())
+///     .with_slave_hook(&configure_slave);
+/// ```
+pub trait SlaveHook: Sync {
+    /// Configure a single slave device during the given state transition.
+    fn call<'a>(
+        &'a self,
+        transition: Transition,
+        slave: &'a mut SlaveRef<'_, &'_ mut Slave>,
+    ) -> HookFuture<'a>;
+}
+
+impl<F> SlaveHook for F
+where
+    F: for<'a, 'b, 'c> Fn(Transition, &'a mut SlaveRef<'b, &'c mut Slave>) -> HookFuture<'a> + Sync,
+{
+    fn call<'a>(
+        &'a self,
+        transition: Transition,
+        slave: &'a mut SlaveRef<'_, &'_ mut Slave>,
+    ) -> HookFuture<'a> {
+        (self)(transition, slave)
+    }
+}
+
+impl<'a> core::fmt::Debug for dyn SlaveHook + 'a {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("SlaveHook")
+    }
+}
+
+/// Call the client hook then the group hook, if either are registered, for the given slave.
+pub(crate) async fn call_hooks(
+    client: &Client<'_>,
+    group_hook: Option<&dyn SlaveHook>,
+    transition: Transition,
+    slave: &mut Slave,
+    signals: Option<&dyn SignalStore>,
+) -> Result<(), Error> {
+    for hook in client.slave_hook.into_iter().chain(group_hook) {
+        fmt::debug!(
+            "Calling {} hook for slave {:#06x}",
+            transition,
+            slave.configured_address
+        );
+
+        let mut slave =
+            SlaveRef::new(client, slave.configured_address, &mut *slave).with_signals(signals);
+
+        hook.call(transition, &mut slave).await?;
+    }
+
+    Ok(())
+}
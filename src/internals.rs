@@ -3,6 +3,7 @@
 //! Anything exported by this module should be considered unstable and may change at any time.
 
 pub use crate::eeprom::device_reader::DeviceEeprom;
+pub use crate::eeprom::memory_reader::EepromMemory;
 pub use crate::eeprom::ChunkReader;
 pub use crate::eeprom::EepromDataProvider;
 pub use crate::pdu_loop::FramePreamble;
@@ -12,6 +12,8 @@
 //!
 //! - `std` (enabled by default) - exposes the [`std`] module, containing helpers to run the TX/RX
 //!   loop on desktop operating systems.
+//! - `alloc` - enable [`SlaveHook`], whose futures are boxed. This is enabled by default when the
+//!   `std` feature is enabled.
 //! - `defmt` - enable logging with the [`defmt`](https://docs.rs/defmt) crate.
 //! - `log` - enable logging with the [`log`](https://docs.rs/log) crate. This is enabled by default
 //!   when the `std` feature is enabled.
@@ -156,6 +158,9 @@
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(rustdoc::private_intra_doc_links)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 // MUST go first so everything else can see the macros inside
 pub(crate) mod fmt;
 
@@ -168,11 +173,16 @@ mod coe;
 mod command;
 mod dc;
 mod dl_status;
+mod driver;
 pub mod ds402;
 mod eeprom;
 pub mod error;
+mod expected;
 mod fmmu;
+mod foe;
 mod generate;
+#[cfg(feature = "alloc")]
+mod hook;
 mod mailbox;
 mod pdi;
 mod pdu_loop;
@@ -182,7 +192,9 @@ pub mod slave_group;
 mod slave_state;
 mod sync_manager_channel;
 mod timer_factory;
+mod topology;
 mod vendors;
+mod watchdog;
 
 #[cfg(feature = "__internals")]
 pub mod internals;
@@ -197,16 +209,32 @@ pub use client::Client;
 pub use client_config::{ClientConfig, RetryBehaviour};
 pub use coe::SubIndex;
 pub use command::{Command, Reads, WrappedRead, WrappedWrite, Writes};
+pub use dc::DcSync;
+pub use dl_status::PortLinkStatus;
+pub use driver::{SdoValue, SlaveDriver, SlaveDriverIo, StartupSdo};
+pub use eeprom::cache::EepromCache;
+pub use eeprom::memory_reader::EepromMemory;
 pub use ethercrab_wire::{
     EtherCrabWireRead, EtherCrabWireReadSized, EtherCrabWireReadWrite, EtherCrabWireSized,
     EtherCrabWireWrite, EtherCrabWireWriteSized,
 };
+pub use expected::{ExpectedPort, ExpectedSlave, TopologyMismatch, TopologyMismatchKind};
+#[cfg(feature = "alloc")]
+pub use hook::{HookFuture, SlaveHook};
 pub use pdu_loop::{PduLoop, PduRx, PduStorage, PduTx, SendableFrame};
 pub use register::RegisterAddress;
-pub use slave::{Slave, SlaveIdentity, SlavePdi, SlaveRef};
-pub use slave_group::{GroupId, GroupSlaveIterator, SlaveGroup, SlaveGroupHandle, SlaveGroupState};
-pub use slave_state::SlaveState;
-pub use timer_factory::Timeouts;
+pub use slave::{
+    ErrorCounters, Pdo, PdoAssignment, PdoEntry, PdoSignal, PortErrorCounters, SignalDirection,
+    Slave, SlaveIdentity, SlavePdi, SlaveRef,
+};
+pub use slave_group::{
+    GroupId, GroupSlaveIterator, HotConnect, RecoveryEvent, SlaveGroup, SlaveGroupHandle,
+    SlaveGroupState, SlaveHealth, MAX_HOT_CONNECT_SEGMENTS,
+};
+pub use slave_state::{SlaveState, Transition};
+pub use timer_factory::{StateTimeouts, Timeouts};
+pub use topology::{CableBreak, NetworkTopology, TopologyEdge, TopologyNode};
+pub use watchdog::{Watchdog, WatchdogStatus};
 
 const LEN_MASK: u16 = 0b0000_0111_1111_1111;
 const ETHERCAT_ETHERTYPE_RAW: u16 = 0x88a4;
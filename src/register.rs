@@ -40,6 +40,10 @@ pub enum RegisterAddress {
     /// Application Layer (AL) status code register.
     AlStatusCode = 0x0134,
 
+    /// RX error counters, 2 bytes per port, followed by the rest of the ESC error counters up to
+    /// 0x0313.
+    RxErrorCounter = 0x0300,
+
     /// Watchdog divider, `u16`.
     ///
     /// See ETG1000.4 section 6.3 Watchdogs.
@@ -172,6 +176,12 @@ pub enum RegisterAddress {
     /// DC system time difference, `u32`.
     DcSystemTimeDifference = 0x092C,
 
+    /// Cyclic unit control, `u8`.
+    ///
+    /// Written together with [`DcSyncActive`](RegisterAddress::DcSyncActive) as the `u16` "assign
+    /// activate" value from a slave's ESI file or EEPROM.
+    DcCyclicUnitControl = 0x0980,
+
     /// ETG1000.6 Table 27 – Distributed Clock sync parameter, `u8`.
     ///
     /// AKA ETG1000.4 Table 61 DC user P1.
@@ -186,6 +196,9 @@ pub enum RegisterAddress {
     ///
     /// AKA ETG1000.4 Table 61 DC user P5.
     DcSync0CycleTime = 0x09A0,
+
+    /// SYNC1 cycle time, `u32`.
+    DcSync1CycleTime = 0x09A4,
 }
 
 impl From<RegisterAddress> for u16 {
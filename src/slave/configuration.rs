@@ -1,11 +1,16 @@
-use super::{Slave, SlaveRef};
+use super::{
+    eeprom::SlaveEeprom,
+    pdo::{SignalEntry, SignalRecorder},
+    SignalDirection, Slave, SlaveRef,
+};
 use crate::{
     coe::SubIndex,
     eeprom::types::{
-        CoeDetails, FmmuUsage, MailboxProtocols, SiiOwner, SyncManager, SyncManagerEnable,
+        CoeDetails, FmmuUsage, MailboxProtocols, Pdo, SiiOwner, SyncManager, SyncManagerEnable,
         SyncManagerType,
     },
-    error::{Error, Item},
+    eeprom::EepromDataProvider,
+    error::{Error, Item, MailboxError},
     fmmu::Fmmu,
     fmt,
     pdi::PdiOffset,
@@ -16,7 +21,14 @@ use crate::{
     sync_manager_channel::SyncManagerChannel,
     sync_manager_channel::{self, SM_BASE_ADDRESS, SM_TYPE_ADDRESS},
 };
-use core::ops::DerefMut;
+use core::ops::{Deref, DerefMut};
+
+/// Start of the logical memory area the AL status of each slave device is mapped into, far above
+/// any process data.
+pub(crate) const AL_STATUS_LOGICAL_START: u32 = 0xffff_0000;
+
+/// Maximum length of a string in a slave's EEPROM, so long PDO names never fail configuration.
+const MAX_SII_STRING: usize = 255;
 
 /// Configuation from EEPROM methods.
 impl<'a, S> SlaveRef<'a, S>
@@ -46,7 +58,7 @@ where
             self.configured_address
         );
 
-        self.request_slave_state(SlaveState::PreOp).await?;
+        self.move_to_state(SlaveState::PreOp).await?;
 
         if self.state.config.mailbox.has_coe {
             // TODO: Abstract this no-complete-access check into a method call so we can reuse it.
@@ -171,6 +183,65 @@ where
         Ok(global_offset)
     }
 
+    /// Map the low byte of the AL status register into logical memory at
+    /// [`AL_STATUS_LOGICAL_START`] plus the slave's configured address, so the states of many
+    /// slave devices can be read with one `LRD`.
+    ///
+    /// The last FMMU is used if process data did not need it, so this must be called after
+    /// [`configure_fmmus`](SlaveRef::configure_fmmus). Slaves without a spare FMMU are left
+    /// unmapped.
+    pub(crate) async fn map_al_status(&mut self) -> Result<(), Error> {
+        let fmmu_count = self
+            .read(RegisterAddress::FmmuCount)
+            .receive::<u8>()
+            .await?;
+
+        let Some(fmmu_index) = fmmu_count.min(16).checked_sub(1) else {
+            return Ok(());
+        };
+
+        let existing = self
+            .read(RegisterAddress::fmmu(fmmu_index))
+            .receive::<Fmmu>()
+            .await?;
+
+        if existing.enable {
+            fmt::debug!(
+                "Slave {:#06x} has no spare FMMU to map AL status",
+                self.configured_address
+            );
+
+            return Ok(());
+        }
+
+        let fmmu_config = Fmmu {
+            logical_start_address: AL_STATUS_LOGICAL_START + u32::from(self.configured_address),
+            length_bytes: 1,
+            logical_start_bit: 0,
+            logical_end_bit: 7,
+            physical_start_address: RegisterAddress::AlStatus.into(),
+            physical_start_bit: 0,
+            read_enable: true,
+            write_enable: false,
+            enable: true,
+        };
+
+        self.write(RegisterAddress::fmmu(fmmu_index))
+            .send(fmmu_config)
+            .await?;
+
+        fmt::debug!(
+            "Slave {:#06x} AL status FMMU{}: {}",
+            self.configured_address,
+            fmmu_index,
+            fmmu_config
+        );
+
+        self.state.config.al_status_mapped = true;
+
+        Ok(())
+    }
+
     async fn write_sm_config(
         &self,
         sync_manager_index: u8,
@@ -283,9 +354,89 @@ where
         Ok(())
     }
 
+    /// Configure SM0 and SM1 with the bootstrap mailbox from the slave's EEPROM, ready for the slave
+    /// to be moved into BOOT.
+    ///
+    /// Mailboxes must be configured in INIT state, and only FoE is available in BOOT.
+    pub(crate) async fn configure_bootstrap_mailbox(&mut self) -> Result<(), Error> {
+        self.set_eeprom_mode(SiiOwner::Master).await?;
+
+        let mailbox_config = self.eeprom().bootstrap_mailbox_config().await?;
+
+        fmt::trace!(
+            "Slave {:#06x} bootstrap mailbox configuration: {:?}",
+            self.configured_address,
+            mailbox_config
+        );
+
+        let no_mailbox = || {
+            fmt::error!(
+                "Slave {:#06x} has no bootstrap mailbox",
+                self.configured_address
+            );
+
+            Error::Mailbox(MailboxError::NoMailbox)
+        };
+
+        if !mailbox_config.has_mailbox() {
+            return Err(no_mailbox());
+        }
+
+        let sync_managers = self.eeprom().sync_managers().await?;
+
+        let mut read_mailbox = None;
+        let mut write_mailbox = None;
+
+        for (sync_manager_index, sync_manager) in sync_managers.iter().enumerate() {
+            let sync_manager_index = sync_manager_index as u8;
+
+            let mailbox = match sync_manager.usage_type {
+                SyncManagerType::MailboxWrite => Mailbox {
+                    address: mailbox_config.slave_receive_offset,
+                    len: mailbox_config.slave_receive_size,
+                    sync_manager: sync_manager_index,
+                },
+                SyncManagerType::MailboxRead => Mailbox {
+                    address: mailbox_config.slave_send_offset,
+                    len: mailbox_config.slave_send_size,
+                    sync_manager: sync_manager_index,
+                },
+                _ => continue,
+            };
+
+            // Same SM settings as the standard mailbox, but at the bootstrap address
+            let sync_manager = SyncManager {
+                start_addr: mailbox.address,
+                ..*sync_manager
+            };
+
+            self.write_sm_config(sync_manager_index, &sync_manager, mailbox.len)
+                .await?;
+
+            if sync_manager.usage_type == SyncManagerType::MailboxWrite {
+                write_mailbox = Some(mailbox);
+            } else {
+                read_mailbox = Some(mailbox);
+            }
+        }
+
+        if read_mailbox.is_none() || write_mailbox.is_none() {
+            return Err(no_mailbox());
+        }
+
+        self.state.config.mailbox = MailboxConfig {
+            read: read_mailbox,
+            write: write_mailbox,
+            supported_protocols: MailboxProtocols::FOE,
+            ..MailboxConfig::default()
+        };
+
+        Ok(())
+    }
+
     /// Configure PDOs from CoE registers.
     async fn configure_pdos_coe(
-        &self,
+        &mut self,
         sync_managers: &[SyncManager],
         fmmu_usage: &[FmmuUsage],
         direction: PdoDirection,
@@ -309,17 +460,11 @@ where
         let start_offset = *gobal_offset;
         let mut total_bit_len = 0;
 
-        for (sync_manager_index, sm_type) in self
-            .state
-            .config
-            .mailbox
-            .coe_sync_manager_types
-            .iter()
-            .enumerate()
-        {
-            let sync_manager_index = sync_manager_index as u8;
+        // Copied so sync manager offsets can be recorded on the slave while iterating.
+        let sm_types = self.state.config.mailbox.coe_sync_manager_types.clone();
 
-            let sm_address = SM_BASE_ADDRESS + u16::from(sync_manager_index);
+        for (sync_manager_index, sm_type) in sm_types.iter().enumerate() {
+            let sync_manager_index = sync_manager_index as u8;
 
             let sync_manager =
                 sync_managers
@@ -333,46 +478,33 @@ where
                 continue;
             }
 
-            // Total number of PDO assignments for this sync manager
-            let num_sm_assignments = self.sdo_read::<u8>(sm_address, SubIndex::Index(0)).await?;
-
-            fmt::trace!(
-                "SDO sync manager {}  {:#06x} {:?}, sub indices: {}",
-                sync_manager_index,
-                sm_address,
-                sm_type,
-                num_sm_assignments
-            );
-
-            let mut sm_bit_len = 0u16;
-
-            for i in 1..=num_sm_assignments {
-                let pdo = self.sdo_read::<u16>(sm_address, SubIndex::Index(i)).await?;
-                let num_mappings = self.sdo_read::<u8>(pdo, SubIndex::Index(0)).await?;
-
-                fmt::trace!("--> #{} data: {:#06x} ({} mappings):", i, pdo, num_mappings);
-
-                for i in 1..=num_mappings {
-                    let mapping = self.sdo_read::<u32>(pdo, SubIndex::Index(i)).await?;
-
-                    // Yes, big-endian. Makes life easier when mapping from debug prints to actual
-                    // data fields.
-                    let parts = mapping.to_be_bytes();
-
-                    let index = u16::from_be_bytes(fmt::unwrap!(parts[0..=1].try_into()));
-                    let sub_index = parts[2];
-                    let mapping_bit_len = parts[3];
-
-                    fmt::trace!(
-                        "----> index {:#06x}, sub index {}, bit length {}",
-                        index,
-                        sub_index,
-                        mapping_bit_len,
-                    );
+            // Use the length of a PDO assignment written by EtherCrab if we have one, otherwise
+            // read the current assignment back from the device.
+            let known_bit_len = self
+                .state
+                .config
+                .sm_bit_lens
+                .get(usize::from(sync_manager_index))
+                .copied()
+                .flatten();
+
+            // Entries of an assignment written by EtherCrab are already in the signal table.
+            let sm_bit_len = match known_bit_len {
+                Some(bit_len) => bit_len,
+                None => {
+                    let mut signals = SignalRecorder::new(
+                        self.signals,
+                        self.configured_address,
+                        direction.signal_direction(),
+                        sync_manager_index,
+                    )?;
 
-                    sm_bit_len += u16::from(mapping_bit_len);
+                    self.read_sm_mapping(sync_manager_index, |index, sub_index, bit_len| {
+                        signals.push(index, sub_index, bit_len, None, None)
+                    })
+                    .await?
                 }
-            }
+            };
 
             fmt::trace!(
                 "----= total SM bit length {} ({} bytes)",
@@ -384,6 +516,12 @@ where
                 .write_sm_config(sync_manager_index, sync_manager, (sm_bit_len + 7) / 8)
                 .await?;
 
+            self.state.config.sm_start_bits[usize::from(sync_manager_index)] = if sm_bit_len > 0 {
+                Some(sm_start_bit(start_offset, *gobal_offset)?)
+            } else {
+                None
+            };
+
             if sm_bit_len > 0 {
                 let fmmu_index = fmmu_usage
                     .iter()
@@ -406,6 +544,8 @@ where
             total_bit_len += sm_bit_len;
         }
 
+        self.name_signals(direction).await?;
+
         Ok(PdiSegment {
             bit_len: total_bit_len.into(),
             bytes: start_offset.up_to(*gobal_offset),
@@ -466,82 +606,46 @@ where
 
     /// Configure PDOs from EEPROM
     async fn configure_pdos_eeprom(
-        &self,
+        &mut self,
         sync_managers: &[SyncManager],
         fmmu_usage: &[FmmuUsage],
         direction: PdoDirection,
         offset: &mut PdiOffset,
     ) -> Result<PdiSegment, Error> {
-        let pdos = match direction {
-            PdoDirection::MasterRead => {
-                let read_pdos = self.eeprom().master_read_pdos().await?;
+        let eeprom = self.eeprom();
 
-                fmt::trace!("Slave inputs PDOs {:#?}", read_pdos);
-
-                read_pdos
-            }
-            PdoDirection::MasterWrite => {
-                let write_pdos = self.eeprom().master_write_pdos().await?;
+        let pdos = direction.eeprom_pdos(&eeprom).await?;
 
-                fmt::trace!("Slave outputs PDOs {:#?}", write_pdos);
-
-                write_pdos
-            }
-        };
-
-        let fmmu_sm_mappings = self.eeprom().fmmu_mappings().await?;
+        let layout =
+            eeprom_pdo_layout(&eeprom, &pdos, sync_managers, fmmu_usage, direction).await?;
 
         let start_offset = *offset;
         let mut total_bit_len = 0;
 
-        let (sm_type, fmmu_type) = direction.filter_terms();
+        let (sm_type, _fmmu_type) = direction.filter_terms();
 
-        for (sync_manager_index, sync_manager) in sync_managers
-            .iter()
-            .enumerate()
-            .filter(|(_idx, sm)| sm.usage_type == sm_type)
-        {
-            let sync_manager_index = sync_manager_index as u8;
+        for sm_layout in layout {
+            let sync_manager = &sync_managers[usize::from(sm_layout.sync_manager_index)];
 
-            let bit_len = pdos
-                .iter()
-                .filter(|pdo| pdo.sync_manager == sync_manager_index)
-                .map(|pdo| pdo.bit_len())
-                .sum();
-
-            total_bit_len += bit_len;
-
-            // Look for FMMU index using FMMU_EX section in EEPROM. If it's empty, default
-            // to looking through FMMU usage list and picking out the appropriate kind
-            // (Inputs, Outputs)
-            let fmmu_index = fmmu_sm_mappings
-                .iter()
-                .find(|fmmu| fmmu.sync_manager == sync_manager_index)
-                .map(|fmmu| fmmu.sync_manager)
-                .or_else(|| {
-                    fmt::trace!("Could not find FMMU for PDO SM{}", sync_manager_index);
-
-                    fmmu_usage
-                        .iter()
-                        .position(|usage| *usage == fmmu_type)
-                        .map(|idx| {
-                            fmt::trace!("Using fallback FMMU FMMU{}", idx);
-
-                            idx as u8
-                        })
-                })
-                .ok_or(Error::NotFound {
-                    item: Item::Fmmu,
-                    index: None,
-                })?;
+            self.state.config.sm_start_bits[usize::from(sm_layout.sync_manager_index)] =
+                Some(sm_start_bit(start_offset, *offset)?);
+
+            self.record_eeprom_signals(direction, sm_layout.sync_manager_index, &pdos)
+                .await?;
+
+            total_bit_len += sm_layout.bit_len;
 
             let sm_config = self
-                .write_sm_config(sync_manager_index, sync_manager, (bit_len + 7) / 8)
+                .write_sm_config(
+                    sm_layout.sync_manager_index,
+                    sync_manager,
+                    (sm_layout.bit_len + 7) / 8,
+                )
                 .await?;
 
             self.write_fmmu_config(
-                bit_len,
-                usize::from(fmmu_index),
+                sm_layout.bit_len,
+                usize::from(sm_layout.fmmu_index),
                 offset,
                 sm_type,
                 &sm_config,
@@ -554,6 +658,262 @@ where
             bytes: start_offset.up_to(*offset),
         })
     }
+
+    /// Record the entries of the EEPROM PDOs assigned to a sync manager in the group's signal
+    /// table, along with their names.
+    async fn record_eeprom_signals(
+        &self,
+        direction: PdoDirection,
+        sync_manager_index: u8,
+        pdos: &[Pdo],
+    ) -> Result<(), Error> {
+        if self.signals.is_none() {
+            return Ok(());
+        }
+
+        let eeprom = self.eeprom();
+
+        let mut signals = SignalRecorder::new(
+            self.signals,
+            self.configured_address,
+            direction.signal_direction(),
+            sync_manager_index,
+        )?;
+
+        for pdo in pdos
+            .iter()
+            .filter(|pdo| pdo.sync_manager == sync_manager_index)
+        {
+            let pdo_name = eeprom
+                .find_string::<MAX_SII_STRING>(pdo.name_string_idx)
+                .await?;
+
+            for entry in pdo.entries.iter() {
+                let entry_name = eeprom
+                    .find_string::<MAX_SII_STRING>(entry.name_string_idx)
+                    .await?;
+
+                signals.push(
+                    entry.index,
+                    entry.sub_index,
+                    entry.data_length_bits,
+                    pdo_name.as_deref(),
+                    entry_name.as_deref(),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Name signals read back from the device over CoE from the PDOs in the slave's EEPROM.
+    ///
+    /// Signals recorded from a PDO assignment written by EtherCrab keep the names they were given.
+    async fn name_signals(&self, direction: PdoDirection) -> Result<(), Error> {
+        let Some(store) = self.signals else {
+            return Ok(());
+        };
+
+        let signal_direction = direction.signal_direction();
+
+        let is_unnamed = |entry: &SignalEntry| {
+            entry.configured_address == self.configured_address
+                && entry.signal.direction == signal_direction
+                && !entry.is_named()
+        };
+
+        if !store.entries()?.iter().any(is_unnamed) {
+            return Ok(());
+        }
+
+        let eeprom = self.eeprom();
+
+        for pdo in direction.eeprom_pdos(&eeprom).await? {
+            let pdo_name = eeprom
+                .find_string::<MAX_SII_STRING>(pdo.name_string_idx)
+                .await?;
+
+            for entry in pdo.entries.iter() {
+                let matches = |signal: &SignalEntry| {
+                    is_unnamed(signal)
+                        && signal.signal.index == entry.index
+                        && signal.signal.sub_index == entry.sub_index
+                };
+
+                if entry.index == 0 || !store.entries()?.iter().any(matches) {
+                    continue;
+                }
+
+                let Some(entry_name) = eeprom
+                    .find_string::<MAX_SII_STRING>(entry.name_string_idx)
+                    .await?
+                else {
+                    continue;
+                };
+
+                for signal in store
+                    .entries_mut()?
+                    .iter_mut()
+                    .filter(|signal| matches(signal))
+                {
+                    signal.set_name(pdo_name.as_deref(), &entry_name);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read the PDO assignment and mapping of a sync manager from the device and compute its total
+    /// length in bits.
+    ///
+    /// `entry` is called with the index, sub-index and bit length of each mapped entry, in mapping
+    /// order.
+    pub(crate) async fn read_sm_mapping(
+        &self,
+        sync_manager_index: u8,
+        mut entry: impl FnMut(u16, u8, u8) -> Result<(), Error>,
+    ) -> Result<u16, Error> {
+        let sm_address = SM_BASE_ADDRESS + u16::from(sync_manager_index);
+
+        // Total number of PDO assignments for this sync manager
+        let num_sm_assignments = self.sdo_read::<u8>(sm_address, SubIndex::Index(0)).await?;
+
+        fmt::trace!(
+            "SDO sync manager {} {:#06x}, sub indices: {}",
+            sync_manager_index,
+            sm_address,
+            num_sm_assignments
+        );
+
+        let mut sm_bit_len = 0u16;
+
+        for i in 1..=num_sm_assignments {
+            let pdo = self.sdo_read::<u16>(sm_address, SubIndex::Index(i)).await?;
+            let num_mappings = self.sdo_read::<u8>(pdo, SubIndex::Index(0)).await?;
+
+            fmt::trace!("--> #{} data: {:#06x} ({} mappings):", i, pdo, num_mappings);
+
+            for i in 1..=num_mappings {
+                let mapping = self.sdo_read::<u32>(pdo, SubIndex::Index(i)).await?;
+
+                // Yes, big-endian. Makes life easier when mapping from debug prints to actual
+                // data fields.
+                let parts = mapping.to_be_bytes();
+
+                let index = u16::from_be_bytes(fmt::unwrap!(parts[0..=1].try_into()));
+                let sub_index = parts[2];
+                let mapping_bit_len = parts[3];
+
+                fmt::trace!(
+                    "----> index {:#06x}, sub index {}, bit length {}",
+                    index,
+                    sub_index,
+                    mapping_bit_len,
+                );
+
+                sm_bit_len += u16::from(mapping_bit_len);
+
+                entry(index, sub_index, mapping_bit_len)?;
+            }
+        }
+
+        Ok(sm_bit_len)
+    }
+}
+
+/// The size and FMMU assignment of a single process data sync manager.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct SmPdoLayout {
+    /// Sync manager index.
+    pub sync_manager_index: u8,
+
+    /// The FMMU used to map this sync manager into the PDI.
+    pub fmmu_index: u8,
+
+    /// Total length of all PDOs assigned to this sync manager.
+    pub bit_len: u16,
+}
+
+/// Compute the sync manager sizes and FMMU assignments for the given direction from the PDOs
+/// described in a slave's EEPROM.
+///
+/// This method does not communicate with the slave device so can be used with any
+/// [`EepromDataProvider`].
+pub(crate) async fn eeprom_pdo_layout<P>(
+    eeprom: &SlaveEeprom<P>,
+    pdos: &[Pdo],
+    sync_managers: &[SyncManager],
+    fmmu_usage: &[FmmuUsage],
+    direction: PdoDirection,
+) -> Result<heapless::Vec<SmPdoLayout, 8>, Error>
+where
+    P: EepromDataProvider,
+{
+    let fmmu_sm_mappings = eeprom.fmmu_mappings().await?;
+
+    let (sm_type, fmmu_type) = direction.filter_terms();
+
+    let mut layout = heapless::Vec::new();
+
+    for (sync_manager_index, _sync_manager) in sync_managers
+        .iter()
+        .enumerate()
+        .filter(|(_idx, sm)| sm.usage_type == sm_type)
+    {
+        let sync_manager_index = sync_manager_index as u8;
+
+        let bit_len = pdos
+            .iter()
+            .filter(|pdo| pdo.sync_manager == sync_manager_index)
+            .map(|pdo| pdo.bit_len())
+            .sum();
+
+        // Look for FMMU index using FMMU_EX section in EEPROM. If it's empty, default
+        // to looking through FMMU usage list and picking out the appropriate kind
+        // (Inputs, Outputs)
+        let fmmu_index = fmmu_sm_mappings
+            .iter()
+            .find(|fmmu| fmmu.sync_manager == sync_manager_index)
+            .map(|fmmu| fmmu.sync_manager)
+            .or_else(|| {
+                fmt::trace!("Could not find FMMU for PDO SM{}", sync_manager_index);
+
+                fmmu_usage
+                    .iter()
+                    .position(|usage| *usage == fmmu_type)
+                    .map(|idx| {
+                        fmt::trace!("Using fallback FMMU FMMU{}", idx);
+
+                        idx as u8
+                    })
+            })
+            .ok_or(Error::NotFound {
+                item: Item::Fmmu,
+                index: None,
+            })?;
+
+        layout
+            .push(SmPdoLayout {
+                sync_manager_index,
+                fmmu_index,
+                bit_len,
+            })
+            .map_err(|_| Error::Capacity(Item::SyncManager))?;
+    }
+
+    Ok(layout)
+}
+
+/// Offset in bits of the next sync manager's data from the start of a slave's inputs or outputs.
+fn sm_start_bit(start: PdiOffset, current: PdiOffset) -> Result<u16, Error> {
+    u16::try_from((current.start_address - start.start_address) * 8)
+        .map_err(|_| Error::IntegerTypeConversion)
 }
 
 #[derive(Copy, Clone)]
@@ -563,6 +923,36 @@ pub enum PdoDirection {
 }
 
 impl PdoDirection {
+    /// Read the PDOs for this direction from a slave's EEPROM.
+    async fn eeprom_pdos<P>(self, eeprom: &SlaveEeprom<P>) -> Result<heapless::Vec<Pdo, 16>, Error>
+    where
+        P: EepromDataProvider,
+    {
+        match self {
+            PdoDirection::MasterRead => {
+                let read_pdos = eeprom.master_read_pdos().await?;
+
+                fmt::trace!("Slave inputs PDOs {:#?}", read_pdos);
+
+                Ok(read_pdos)
+            }
+            PdoDirection::MasterWrite => {
+                let write_pdos = eeprom.master_write_pdos().await?;
+
+                fmt::trace!("Slave outputs PDOs {:#?}", write_pdos);
+
+                Ok(write_pdos)
+            }
+        }
+    }
+
+    fn signal_direction(self) -> SignalDirection {
+        match self {
+            PdoDirection::MasterRead => SignalDirection::Input,
+            PdoDirection::MasterWrite => SignalDirection::Output,
+        }
+    }
+
     fn filter_terms(self) -> (SyncManagerType, FmmuUsage) {
         match self {
             PdoDirection::MasterRead => (SyncManagerType::ProcessDataRead, FmmuUsage::Inputs),
@@ -570,3 +960,161 @@ impl PdoDirection {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dc::DcSync, eeprom::memory_reader::EepromMemory};
+
+    #[tokio::test]
+    async fn slave_from_image() {
+        let e = SlaveEeprom::new(EepromMemory::new(include_bytes!(
+            "../../dumps/eeprom/el2828.hex"
+        )));
+
+        let slave = Slave::from_eeprom(&e, 3, 0x1003)
+            .await
+            .expect("From EEPROM");
+
+        assert_eq!(slave.name(), "EL2828");
+        assert_eq!(slave.identity(), e.identity().await.unwrap());
+        assert_eq!(slave.configured_address, 0x1003);
+        assert_eq!(slave.index, 3);
+        assert_eq!(slave.config.dc_sync, DcSync::default());
+    }
+
+    #[tokio::test]
+    async fn slave_dc_sync_defaults() {
+        let slave = Slave::from_eeprom_image(
+            EepromMemory::new(include_bytes!("../../dumps/eeprom/akd.hex")),
+            0x1000,
+        )
+        .await
+        .expect("From EEPROM");
+
+        // AKD leaves the cycle time up to the master, so SYNC0 is not enabled by default
+        assert_eq!(
+            slave.dc_sync(),
+            DcSync {
+                assign_activate: 0x0300,
+                ..DcSync::default()
+            }
+        );
+        assert!(!slave.dc_sync().is_enabled());
+    }
+
+    #[tokio::test]
+    async fn pdo_layout_outputs_only() {
+        let e = SlaveEeprom::new(EepromMemory::new(include_bytes!(
+            "../../dumps/eeprom/el2828.hex"
+        )));
+
+        let sms = e.sync_managers().await.unwrap();
+        let fmmus = e.fmmus().await.unwrap();
+
+        assert_eq!(
+            eeprom_pdo_layout(
+                &e,
+                &PdoDirection::MasterRead.eeprom_pdos(&e).await.unwrap(),
+                &sms,
+                &fmmus,
+                PdoDirection::MasterRead
+            )
+            .await,
+            Ok(heapless::Vec::new())
+        );
+        assert_eq!(
+            eeprom_pdo_layout(
+                &e,
+                &PdoDirection::MasterWrite.eeprom_pdos(&e).await.unwrap(),
+                &sms,
+                &fmmus,
+                PdoDirection::MasterWrite
+            )
+            .await,
+            Ok(heapless::Vec::from_slice(&[SmPdoLayout {
+                sync_manager_index: 0,
+                fmmu_index: 0,
+                bit_len: 8,
+            }])
+            .unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn pdo_layout_multiple_sms() {
+        let e = SlaveEeprom::new(EepromMemory::new_short(include_bytes!(
+            "../../dumps/eeprom/el2889.hex"
+        )));
+
+        let sms = e.sync_managers().await.unwrap();
+        let fmmus = e.fmmus().await.unwrap();
+
+        assert_eq!(
+            eeprom_pdo_layout(
+                &e,
+                &PdoDirection::MasterWrite.eeprom_pdos(&e).await.unwrap(),
+                &sms,
+                &fmmus,
+                PdoDirection::MasterWrite
+            )
+            .await,
+            Ok(heapless::Vec::from_slice(&[
+                SmPdoLayout {
+                    sync_manager_index: 0,
+                    fmmu_index: 0,
+                    bit_len: 8,
+                },
+                SmPdoLayout {
+                    sync_manager_index: 1,
+                    fmmu_index: 0,
+                    bit_len: 8,
+                }
+            ])
+            .unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn pdo_layout_inputs_and_outputs() {
+        let e = SlaveEeprom::new(EepromMemory::new(include_bytes!(
+            "../../dumps/eeprom/akd.hex"
+        )));
+
+        let sms = e.sync_managers().await.unwrap();
+        let fmmus = e.fmmus().await.unwrap();
+
+        assert_eq!(
+            eeprom_pdo_layout(
+                &e,
+                &PdoDirection::MasterRead.eeprom_pdos(&e).await.unwrap(),
+                &sms,
+                &fmmus,
+                PdoDirection::MasterRead
+            )
+            .await,
+            Ok(heapless::Vec::from_slice(&[SmPdoLayout {
+                sync_manager_index: 3,
+                fmmu_index: 1,
+                bit_len: 48,
+            }])
+            .unwrap())
+        );
+        assert_eq!(
+            eeprom_pdo_layout(
+                &e,
+                &PdoDirection::MasterWrite.eeprom_pdos(&e).await.unwrap(),
+                &sms,
+                &fmmus,
+                PdoDirection::MasterWrite
+            )
+            .await,
+            Ok(heapless::Vec::from_slice(&[SmPdoLayout {
+                sync_manager_index: 2,
+                fmmu_index: 0,
+                bit_len: 48,
+            }])
+            .unwrap())
+        );
+    }
+}
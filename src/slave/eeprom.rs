@@ -1,10 +1,12 @@
 use crate::{
     eeprom::types::{
-        CategoryType, DefaultMailbox, PdoEntry, SiiGeneral, RX_PDO_RANGE, TX_PDO_RANGE,
+        BootstrapMailbox, CategoryType, DefaultMailbox, PdoEntry, SiiGeneral, RX_PDO_RANGE,
+        TX_PDO_RANGE,
     },
     eeprom::{
+        cache::EepromCache,
         device_reader::SII_FIRST_CATEGORY_START,
-        types::{FmmuEx, FmmuUsage, Pdo, PdoType, SyncManager},
+        types::{DcOpMode, FmmuEx, FmmuUsage, Pdo, PdoType, SyncManager},
         ChunkReader, EepromDataProvider,
     },
     error::{EepromError, Error, Item},
@@ -37,22 +39,36 @@ where
         ))
     }
 
+    /// Read the type and length in words of the category whose header starts at the given word
+    /// address.
+    ///
+    /// The header is read through a [`ChunkReader`] as a provider may return fewer than 4 bytes,
+    /// e.g. at the end of an in-memory image.
+    async fn category_header(&self, word_addr: u16) -> Result<(CategoryType, u16), Error> {
+        let mut reader = self.start_at(word_addr, 4).await?;
+
+        let mut buf = [0u8; 4];
+
+        reader.read_exact(&mut buf).await?;
+
+        let [t0, t1, l0, l1] = buf;
+
+        Ok((
+            CategoryType::from(u16::from_le_bytes([t0, t1])),
+            u16::from_le_bytes([l0, l1]),
+        ))
+    }
+
     /// Search for a given category and return a reader over the bytes contained within the category
     /// if it is found.
     async fn category(&self, category: CategoryType) -> Result<Option<ChunkReader<P>>, Error> {
-        let mut reader = self.provider.clone();
-
         let mut word_addr = SII_FIRST_CATEGORY_START;
 
         loop {
-            let chunk = reader.read_chunk(word_addr).await?;
+            let (category_type, len_words) = self.category_header(word_addr).await?;
 
             word_addr += 2;
 
-            let category_type =
-                CategoryType::from(u16::from_le_bytes(fmt::unwrap!(chunk[0..2].try_into())));
-            let len_words = u16::from_le_bytes(fmt::unwrap!(chunk[2..4].try_into()));
-
             fmt::trace!(
                 "Found category {:?} at {:#06x} bytes, length {:#04x} ({}) words",
                 category_type,
@@ -78,6 +94,71 @@ where
         }
     }
 
+    /// Find the length of the EEPROM image in words, up to and including the `End` category header.
+    async fn image_len_words(&self) -> Result<u16, Error> {
+        let mut word_addr = SII_FIRST_CATEGORY_START;
+
+        loop {
+            let (category_type, len_words) = self.category_header(word_addr).await?;
+
+            word_addr += 2;
+
+            if category_type == CategoryType::End {
+                break Ok(word_addr);
+            }
+
+            word_addr = word_addr.checked_add(len_words).ok_or_else(|| {
+                fmt::error!(
+                    "EEPROM category at {:#06x} overflows address space",
+                    word_addr
+                );
+
+                Error::Eeprom(EepromError::SectionOverrun)
+            })?;
+        }
+    }
+
+    /// Read the entire EEPROM image and store it in the given cache.
+    pub(crate) async fn fill_cache(
+        &self,
+        cache: &dyn EepromCache,
+        identity: &SlaveIdentity,
+    ) -> Result<(), Error> {
+        let len_words = self.image_len_words().await?;
+
+        fmt::debug!(
+            "Caching {} byte EEPROM image for {}",
+            len_words * 2,
+            identity
+        );
+
+        let mut reader = self.provider.clone();
+
+        reader.clear_errors().await?;
+
+        let mut word_addr = 0;
+
+        while word_addr < len_words {
+            let chunk = reader.read_chunk(word_addr).await?;
+
+            // Don't store any data past the end of the image
+            let chunk_len = chunk.len().min(usize::from(len_words - word_addr) * 2);
+
+            if chunk_len < 2 {
+                break;
+            }
+
+            cache.write(identity, word_addr, &chunk[0..chunk_len]);
+
+            word_addr += (chunk_len / 2) as u16;
+        }
+
+        // Only a complete image may be used in place of the device's EEPROM
+        cache.commit(identity);
+
+        Ok(())
+    }
+
     /// Get the device name.
     ///
     /// Note that the string index is hard coded to `1` instead of reading the string index from the
@@ -115,6 +196,21 @@ where
         Ok(DefaultMailbox::unpack_from_slice(&buf)?)
     }
 
+    pub(crate) async fn bootstrap_mailbox_config(&self) -> Result<BootstrapMailbox, Error> {
+        // Raw start address defined in ETG2010 Table 2. Bootstrap mailbox config is 8 bytes long.
+        let mut reader = self
+            .start_at(0x0014, BootstrapMailbox::PACKED_LEN as u16)
+            .await?;
+
+        fmt::trace!("Get bootstrap mailbox config");
+
+        let mut buf = BootstrapMailbox::buffer();
+
+        reader.read_exact(&mut buf).await?;
+
+        Ok(BootstrapMailbox::unpack_from_slice(&buf)?)
+    }
+
     pub(crate) async fn general(&self) -> Result<SiiGeneral, Error> {
         let mut reader = self
             .category(CategoryType::General)
@@ -128,6 +224,19 @@ where
         Ok(SiiGeneral::unpack_from_slice(&buf)?)
     }
 
+    pub(crate) async fn station_alias(&self) -> Result<u16, Error> {
+        // Raw start address defined in ETG2010 Table 2
+        let mut reader = self.start_at(0x0004, 2).await?;
+
+        fmt::trace!("Get station alias");
+
+        let mut buf = [0u8; 2];
+
+        reader.read_exact(&mut buf).await?;
+
+        Ok(u16::from_le_bytes(buf))
+    }
+
     pub(crate) async fn identity(&self) -> Result<SlaveIdentity, Error> {
         let mut reader = self
             .start_at(0x0008, SlaveIdentity::PACKED_LEN as u16)
@@ -285,6 +394,31 @@ where
         self.pdos(PdoType::Rx, RX_PDO_RANGE).await
     }
 
+    /// Distributed Clock operation modes from the DC category.
+    ///
+    /// If the device has no DC category, an empty list is returned.
+    pub(crate) async fn dc_op_modes(&self) -> Result<heapless::Vec<DcOpMode, 8>, Error> {
+        let mut op_modes = heapless::Vec::<_, 8>::new();
+
+        fmt::trace!("Get DC operation modes");
+
+        if let Some(mut reader) = self.category(CategoryType::DistributedClock).await? {
+            let mut buf = DcOpMode::buffer();
+
+            while reader.read(&mut buf).await? == DcOpMode::PACKED_LEN {
+                let op_mode = DcOpMode::unpack_from_slice(&buf)?;
+
+                op_modes
+                    .push(op_mode)
+                    .map_err(|_| Error::Capacity(Item::DcOpMode))?;
+            }
+        }
+
+        fmt::debug!("Discovered DC operation modes:\n{:#?}", op_modes);
+
+        Ok(op_modes)
+    }
+
     /// Find a string in the device EEPROM.
     ///
     /// An index of 0 denotes an empty string and will always return `Ok(None)`.
@@ -372,11 +506,13 @@ mod tests {
         base_data_types::PrimitiveDataType,
         eeprom::{
             file_reader::EepromFile,
+            memory_reader::EepromMemory,
             types::{
                 CoeDetails, Flags, MailboxProtocols, PdoFlags, PortStatus, PortStatuses,
                 SyncManagerEnable, SyncManagerType,
             },
         },
+        std::EepromImageCache,
         sync_manager_channel::{Control, Direction, OperationMode},
     };
 
@@ -668,6 +804,21 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_bootstrap_mailbox_config() {
+        let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
+
+        assert_eq!(
+            e.bootstrap_mailbox_config().await,
+            Ok(BootstrapMailbox {
+                slave_receive_offset: 0x1800,
+                slave_receive_size: 0x0400,
+                slave_send_offset: 0x1c00,
+                slave_send_size: 0x0400,
+            })
+        );
+    }
+
     #[tokio::test]
     async fn default_mailbox_config_matches_sms() {
         let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
@@ -733,6 +884,21 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn station_alias() {
+        let mut image = [0u8; 16];
+
+        image[8..10].copy_from_slice(&0x1234u16.to_le_bytes());
+
+        let e = SlaveEeprom::new(EepromMemory::new(&image));
+
+        assert_eq!(e.station_alias().await, Ok(0x1234));
+
+        let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
+
+        assert_eq!(e.station_alias().await, Ok(0));
+    }
+
     #[tokio::test]
     async fn get_general_akd() {
         let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
@@ -831,4 +997,129 @@ mod tests {
         // None of the EEPROM dumps I have contain any FMMU_EX records :(
         assert_eq!(fmmu_ex, heapless::Vec::<FmmuEx, 16>::new());
     }
+
+    #[tokio::test]
+    async fn fill_cache() {
+        let image = include_bytes!("../../dumps/eeprom/akd.hex");
+
+        let e = SlaveEeprom::new(EepromMemory::new_short(image));
+
+        let identity = e.identity().await.expect("Identity");
+
+        let cache = EepromImageCache::new();
+
+        e.fill_cache(&cache, &identity).await.expect("Fill cache");
+
+        let cached = cache.image(&identity).expect("No cached image");
+
+        // Image should stop after the end category
+        assert!(cached.len() < image.len());
+        assert_eq!(cached[..], image[0..cached.len()]);
+
+        let c = SlaveEeprom::new(EepromMemory::new(&cached));
+
+        assert_eq!(c.general().await, e.general().await);
+        assert_eq!(c.sync_managers().await, e.sync_managers().await);
+        assert_eq!(c.master_read_pdos().await, e.master_read_pdos().await);
+        assert_eq!(c.master_write_pdos().await, e.master_write_pdos().await);
+        assert_eq!(c.fmmu_mappings().await, e.fmmu_mappings().await);
+
+        // An image cut off part way through the `End` category header
+        let c = SlaveEeprom::new(EepromMemory::new(&cached[0..cached.len() - 2]));
+
+        assert_eq!(
+            c.fmmu_mappings().await,
+            Err(Error::Eeprom(EepromError::SectionOverrun))
+        );
+    }
+
+    #[tokio::test]
+    async fn fill_cache_failure() {
+        /// Fails reads from the given word address onwards.
+        #[derive(Clone)]
+        struct FailFrom<'a> {
+            inner: EepromMemory<'a, 8>,
+            word: u16,
+        }
+
+        impl<'a> EepromDataProvider for FailFrom<'a> {
+            async fn read_chunk(
+                &mut self,
+                start_word: u16,
+            ) -> Result<impl core::ops::Deref<Target = [u8]>, Error> {
+                if (self.word..SII_FIRST_CATEGORY_START).contains(&start_word) {
+                    return Err(Error::Timeout);
+                }
+
+                self.inner.read_chunk(start_word).await
+            }
+
+            async fn clear_errors(&self) -> Result<(), Error> {
+                Ok(())
+            }
+        }
+
+        let image = include_bytes!("../../dumps/eeprom/akd.hex");
+
+        let identity = SlaveEeprom::new(EepromMemory::new(image))
+            .identity()
+            .await
+            .expect("Identity");
+
+        // Fail after the first chunk of the fixed header has been cached
+        let e = SlaveEeprom::new(FailFrom {
+            inner: EepromMemory::new(image),
+            word: 0x0004,
+        });
+
+        let cache = EepromImageCache::new();
+
+        assert_eq!(e.fill_cache(&cache, &identity).await, Err(Error::Timeout));
+        assert!(!cache.contains(&identity));
+        assert_eq!(cache.image(&identity), None);
+    }
+
+    #[tokio::test]
+    async fn akd_dc_op_modes() {
+        let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
+
+        let defaults = DcOpMode {
+            cycle_time_0: 0,
+            shift_time_0: 0,
+            shift_time_1: 0,
+            sync1_cycle_factor: 0,
+            assign_activate: 0,
+            sync0_cycle_factor: 1,
+            name_string_idx: 0,
+            desc_string_idx: 0,
+        };
+
+        assert_eq!(
+            e.dc_op_modes().await,
+            Ok(heapless::Vec::from_slice(&[
+                DcOpMode {
+                    assign_activate: 0x0300,
+                    name_string_idx: 6,
+                    ..defaults
+                },
+                DcOpMode {
+                    name_string_idx: 7,
+                    ..defaults
+                }
+            ])
+            .unwrap())
+        );
+
+        assert_eq!(
+            e.find_string::<64>(6).await,
+            Ok(Some("DcSync".try_into().unwrap()))
+        );
+    }
+
+    #[tokio::test]
+    async fn no_dc_op_modes() {
+        let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/el2828.hex"));
+
+        assert_eq!(e.dc_op_modes().await, Ok(heapless::Vec::new()));
+    }
 }
@@ -0,0 +1,164 @@
+//! ESC link and frame error counters.
+
+use super::SlaveRef;
+use crate::{error::Error, fmt, register::RegisterAddress};
+
+/// Length of the error counter registers from 0x0300 to 0x0313.
+const ERROR_COUNTERS_LEN: usize = 0x14;
+
+/// Error counters for a single port of a slave device.
+///
+/// Counters saturate at `255` and are only reset when cleared.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PortErrorCounters {
+    /// Frames received on this port with an invalid checksum or length.
+    pub invalid_frames: u8,
+    /// Physical layer RX errors on this port, e.g. invalid symbols.
+    pub rx_errors: u8,
+    /// Frames received on this port that were already marked as invalid by a previous slave.
+    pub forwarded_rx_errors: u8,
+    /// The number of times the link on this port was lost.
+    pub lost_links: u8,
+}
+
+impl PortErrorCounters {
+    /// The number of errors first detected on this port, i.e. excluding forwarded errors.
+    ///
+    /// Errors that were first detected by a previous slave are counted as forwarded errors by
+    /// every following slave, so the port with local errors closest to the master is usually
+    /// where the faulty cable or connector is.
+    pub fn local_errors(&self) -> u16 {
+        u16::from(self.invalid_frames) + u16::from(self.rx_errors)
+    }
+}
+
+/// Link and frame error counters of a slave device, read from registers 0x0300 to 0x0313.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ErrorCounters {
+    /// Slave address.
+    pub configured_address: u16,
+    /// Counters for each port, indexed by EtherCAT port number.
+    pub ports: [PortErrorCounters; 4],
+    /// Frames with errors found by the EtherCAT processing unit, e.g. an invalid datagram.
+    pub processing_unit_errors: u8,
+    /// Errors on the process data interface between the ESC and the slave's application
+    /// controller.
+    pub pdi_errors: u8,
+}
+
+impl ErrorCounters {
+    fn from_registers(configured_address: u16, registers: [u8; ERROR_COUNTERS_LEN]) -> Self {
+        let mut ports = [PortErrorCounters::default(); 4];
+
+        for (number, port) in ports.iter_mut().enumerate() {
+            *port = PortErrorCounters {
+                invalid_frames: registers[number * 2],
+                rx_errors: registers[number * 2 + 1],
+                forwarded_rx_errors: registers[0x08 + number],
+                lost_links: registers[0x10 + number],
+            };
+        }
+
+        Self {
+            configured_address,
+            ports,
+            processing_unit_errors: registers[0x0c],
+            pdi_errors: registers[0x0d],
+        }
+    }
+
+    /// Returns `true` if any counter is non-zero.
+    pub fn has_errors(&self) -> bool {
+        self.processing_unit_errors > 0
+            || self.pdi_errors > 0
+            || self
+                .ports
+                .iter()
+                .any(|port| *port != PortErrorCounters::default())
+    }
+}
+
+impl<'a, S> SlaveRef<'a, S> {
+    /// Read the link and frame error counters of this slave device.
+    pub async fn error_counters(&self) -> Result<ErrorCounters, Error> {
+        let registers = self
+            .read(RegisterAddress::RxErrorCounter)
+            .receive::<[u8; ERROR_COUNTERS_LEN]>()
+            .await?;
+
+        Ok(ErrorCounters::from_registers(
+            self.configured_address,
+            registers,
+        ))
+    }
+
+    /// Reset all link and frame error counters of this slave device to zero.
+    pub async fn clear_error_counters(&self) -> Result<(), Error> {
+        fmt::debug!(
+            "Clearing slave {:#06x} error counters",
+            self.configured_address
+        );
+
+        // Writing any value to one counter of each block clears the whole block. Writes to the
+        // read only registers in between are ignored.
+        self.write(RegisterAddress::RxErrorCounter)
+            .send(&[0u8; ERROR_COUNTERS_LEN][..])
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_counters() {
+        let registers = [
+            // Invalid frame and RX error counters, ports 0 - 3
+            1, 2, 3, 4, 0, 0, 0, 0, //
+            // Forwarded RX error counters
+            5, 6, 0, 0, //
+            // Processing unit, PDI
+            7, 8, //
+            // Reserved
+            0xff, 0xff, //
+            // Lost link counters
+            9, 10, 0, 0,
+        ];
+
+        let counters = ErrorCounters::from_registers(0x1001, registers);
+
+        assert_eq!(
+            counters,
+            ErrorCounters {
+                configured_address: 0x1001,
+                ports: [
+                    PortErrorCounters {
+                        invalid_frames: 1,
+                        rx_errors: 2,
+                        forwarded_rx_errors: 5,
+                        lost_links: 9
+                    },
+                    PortErrorCounters {
+                        invalid_frames: 3,
+                        rx_errors: 4,
+                        forwarded_rx_errors: 6,
+                        lost_links: 10
+                    },
+                    PortErrorCounters::default(),
+                    PortErrorCounters::default(),
+                ],
+                processing_unit_errors: 7,
+                pdi_errors: 8,
+            }
+        );
+
+        assert!(counters.has_errors());
+        assert_eq!(counters.ports[1].local_errors(), 7);
+        assert!(!ErrorCounters::from_registers(0x1001, [0; ERROR_COUNTERS_LEN]).has_errors());
+    }
+}
@@ -0,0 +1,223 @@
+//! File access over EtherCAT (FoE) and firmware updates.
+
+use super::{Slave, SlaveRef};
+use crate::{
+    error::{Error, FoeErrorCode, MailboxError},
+    fmt,
+    foe::{FoeHeader, FoeOpCode, FoeRequest},
+    slave_state::SlaveState,
+};
+use core::ops::{Deref, DerefMut};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized};
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Write a file to the slave device using File access over EtherCAT (FoE).
+    ///
+    /// The file name and password are device specific. Many devices only accept files, e.g.
+    /// firmware images, in BOOT. See [`update_firmware`](SlaveRef::update_firmware) to move a slave
+    /// into BOOT and write a firmware file.
+    pub async fn foe_write(
+        &self,
+        file_name: &str,
+        password: u32,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let write_mailbox = self
+            .state
+            .config
+            .mailbox
+            .write
+            .ok_or(Error::Mailbox(MailboxError::NoMailbox))?;
+
+        let max_packet_len = usize::from(write_mailbox.len).saturating_sub(FoeHeader::PACKED_LEN);
+
+        if max_packet_len == 0 || file_name.len() > max_packet_len {
+            return Err(Error::StringTooLong {
+                max_length: max_packet_len,
+                string_length: file_name.len(),
+            });
+        }
+
+        fmt::debug!(
+            "Slave {:#06x} FoE write {} ({} bytes)",
+            self.configured_address,
+            file_name,
+            data.len()
+        );
+
+        // The write request is acknowledged with packet number 0
+        self.foe_request(FoeOpCode::Write, password, file_name.as_bytes(), 0)
+            .await?;
+
+        let mut packet_number = 0;
+
+        for (number, chunk) in data_packets(data, max_packet_len) {
+            packet_number = number;
+
+            self.foe_request(FoeOpCode::Data, packet_number, chunk, packet_number)
+                .await?;
+        }
+
+        fmt::debug!(
+            "Slave {:#06x} FoE write complete in {} packets",
+            self.configured_address,
+            packet_number
+        );
+
+        Ok(())
+    }
+
+    /// Send an FoE request and wait for the slave to acknowledge the given packet number. The
+    /// request is sent again while the slave responds with busy, for up to the client's
+    /// [`foe_busy`](crate::Timeouts::foe_busy) timeout.
+    async fn foe_request(
+        &self,
+        op_code: FoeOpCode,
+        value: u32,
+        payload: &[u8],
+        packet_number: u32,
+    ) -> Result<(), Error> {
+        crate::timer_factory::timeout(self.client.timeouts.foe_busy, async {
+            loop {
+                let (read_mailbox, write_mailbox) = self.coe_mailboxes().await?;
+
+                let request = FoeRequest::new(self.mailbox_counter(), op_code, value, payload);
+
+                self.write(write_mailbox.address)
+                    .with_len(write_mailbox.len)
+                    .send(&request)
+                    .await?;
+
+                // Slaves may take some time to store data, e.g. when writing to flash
+                let response = self
+                    .mailbox_response(&read_mailbox, self.client.timeouts.mailbox_response)
+                    .await?;
+
+                let header = FoeHeader::unpack_from_slice(&response)?;
+
+                match header.op_code {
+                    FoeOpCode::Ack if header.value == packet_number => break Ok(()),
+                    FoeOpCode::Busy => {
+                        fmt::trace!(
+                            "Slave {:#06x} FoE busy, resending packet {}",
+                            self.configured_address,
+                            packet_number
+                        );
+
+                        self.client.timeouts.loop_tick().await;
+                    }
+                    FoeOpCode::Error => {
+                        let code = FoeErrorCode::from(header.value);
+
+                        fmt::error!(
+                            "Slave {:#06x} FoE packet {} error: {}",
+                            self.configured_address,
+                            packet_number,
+                            code
+                        );
+
+                        break Err(Error::Mailbox(MailboxError::FoeAborted {
+                            code,
+                            packet_number,
+                        }));
+                    }
+                    _ => {
+                        fmt::error!(
+                            "Invalid FoE response. Type: {:?}, op code {:?}, value {} (expected ack {})",
+                            header.mailbox_type,
+                            header.op_code,
+                            header.value,
+                            packet_number
+                        );
+
+                        break Err(Error::Mailbox(MailboxError::FoeResponseInvalid {
+                            packet_number,
+                        }));
+                    }
+                }
+            }
+        })
+        .await
+    }
+}
+
+/// File data packets with their packet numbers, starting at 1.
+///
+/// A packet shorter than the maximum packet length marks the end of the file, so an empty packet is
+/// added if the file length is a multiple of the packet length.
+fn data_packets(data: &[u8], max_packet_len: usize) -> impl Iterator<Item = (u32, &[u8])> {
+    let end = (data.len() % max_packet_len == 0).then_some(&[][..]);
+
+    (1..).zip(data.chunks(max_packet_len).chain(end))
+}
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: DerefMut<Target = Slave>,
+{
+    /// Update the firmware of this slave device.
+    ///
+    /// The slave is moved into INIT, then into BOOT with its mailbox configured using the bootstrap
+    /// mailbox from its EEPROM. `firmware` is written with [`foe_write`](SlaveRef::foe_write) using
+    /// the given file name and password, which are device specific. The slave is then returned to
+    /// INIT, and its identity is read again as it may have been changed by the new firmware.
+    ///
+    /// The slave is left in INIT, so it must be configured again before it can be used, e.g. by
+    /// moving its group into INIT with [`SlaveGroup::into_init`](crate::SlaveGroup::into_init) then
+    /// back into PRE-OP.
+    pub async fn update_firmware(
+        &mut self,
+        file_name: &str,
+        password: u32,
+        firmware: &[u8],
+    ) -> Result<(), Error> {
+        self.request_slave_state(SlaveState::Init).await?;
+
+        let mailbox = self.state.config.mailbox.clone();
+
+        self.configure_bootstrap_mailbox().await?;
+
+        let result = async {
+            self.request_slave_state(SlaveState::Bootstrap).await?;
+
+            self.foe_write(file_name, password, firmware).await
+        }
+        .await;
+
+        // Leave BOOT even if the update failed so the slave can be used again.
+        let init = self.request_slave_state(SlaveState::Init).await;
+
+        self.state.config.mailbox = mailbox;
+
+        result?;
+        init?;
+
+        self.reload_identity().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packets() {
+        let data = [1u8, 2, 3, 4, 5];
+
+        assert_eq!(
+            data_packets(&data, 2).collect::<Vec<_>>(),
+            [(1, &[1u8, 2][..]), (2, &[3, 4]), (3, &[5])]
+        );
+
+        // Terminating empty packet
+        assert_eq!(
+            data_packets(&data[0..4], 2).collect::<Vec<_>>(),
+            [(1, &[1u8, 2][..]), (2, &[3, 4]), (3, &[])]
+        );
+
+        assert_eq!(data_packets(&[], 2).collect::<Vec<_>>(), [(1, &[][..])]);
+    }
+}
@@ -1,7 +1,11 @@
 pub(crate) mod configuration;
 mod eeprom;
+mod error_counters;
+mod foe;
 pub mod pdi;
+mod pdo;
 pub mod ports;
+pub(crate) mod recovery;
 mod types;
 
 use crate::{
@@ -11,30 +15,40 @@ use crate::{
     coe::SubIndex,
     coe::{self, abort_code::CoeAbortCode, services::CoeServiceRequest, CoeCommand},
     command::Command,
-    dl_status::DlStatus,
-    eeprom::{device_reader::DeviceEeprom, types::SiiOwner},
-    error::{Error, MailboxError, PduError},
+    dc::DcSync,
+    dl_status::{DlStatus, PortLinkStatus},
+    eeprom::{
+        device_reader::DeviceEeprom, memory_reader::EepromMemory, types::SiiOwner,
+        EepromDataProvider,
+    },
+    error::{Error, MailboxError, PduError, SlaveStateError},
     fmt,
     mailbox::{MailboxHeader, MailboxType},
     pdu_loop::RxFrameDataBuf,
     register::RegisterAddress,
     register::SupportFlags,
     slave::{ports::Ports, types::SlaveConfig},
-    slave_state::SlaveState,
+    slave_state::{SlaveState, Transition},
+    timer_factory::StateTimeouts,
+    watchdog::Watchdog,
     WrappedRead, WrappedWrite,
 };
 use core::{
     any::type_name,
     fmt::{Debug, Write},
-    ops::Deref,
+    ops::{Deref, DerefMut},
     sync::atomic::{AtomicU8, Ordering},
+    time::Duration,
 };
 use ethercrab_wire::{
     EtherCrabWireRead, EtherCrabWireReadSized, EtherCrabWireReadWrite, EtherCrabWireSized,
     EtherCrabWireWrite,
 };
 
+pub use self::error_counters::{ErrorCounters, PortErrorCounters};
 pub use self::pdi::SlavePdi;
+pub use self::pdo::{Pdo, PdoAssignment, PdoEntry, PdoSignal, SignalDirection};
+pub(crate) use self::pdo::{SignalStore, SignalTable};
 pub use self::types::IoRanges;
 pub use self::types::SlaveIdentity;
 use self::{eeprom::SlaveEeprom, types::Mailbox};
@@ -48,6 +62,9 @@ pub struct Slave {
     /// Configured station address.
     pub(crate) configured_address: u16,
 
+    /// Configured station alias, loaded by the slave from its EEPROM on power up. `0` if unset.
+    pub(crate) alias: u16,
+
     pub(crate) config: SlaveConfig,
 
     pub(crate) identity: SlaveIdentity,
@@ -78,6 +95,18 @@ pub struct Slave {
 
     /// The 1-7 cyclic counter used when working with mailbox requests.
     pub(crate) mailbox_counter: AtomicU8,
+
+    /// Set when the slave stops responding at its configured address, and cleared once it is
+    /// recovered by [`SlaveGroup::recover`](crate::SlaveGroup::recover).
+    pub(crate) lost: bool,
+
+    /// The state last requested with [`SlaveRef::request_slave_state`], or [`SlaveState::None`]
+    /// since the slave's group last changed state.
+    pub(crate) requested_state: AtomicU8,
+
+    /// Whether each port has a physical link, indexed by EtherCAT port number, as last read during
+    /// init or by [`SlaveGroup::recover`](crate::SlaveGroup::recover).
+    pub(crate) links: [bool; 4],
 }
 
 // Only required for tests, also doesn't make much sense - consumers of EtherCrab should be
@@ -87,6 +116,7 @@ pub struct Slave {
 impl PartialEq for Slave {
     fn eq(&self, other: &Self) -> bool {
         self.configured_address == other.configured_address
+            && self.alias == other.alias
             && self.config == other.config
             && self.identity == other.identity
             && self.name == other.name
@@ -96,7 +126,9 @@ impl PartialEq for Slave {
             && self.index == other.index
             && self.parent_index == other.parent_index
             && self.propagation_delay == other.propagation_delay
-        // NOTE: No mailbox_counter
+            && self.lost == other.lost
+            && self.links == other.links
+        // NOTE: No mailbox_counter or requested_state
     }
 }
 
@@ -107,6 +139,7 @@ impl Clone for Slave {
     fn clone(&self) -> Self {
         Self {
             configured_address: self.configured_address,
+            alias: self.alias,
             config: self.config.clone(),
             identity: self.identity,
             name: self.name.clone(),
@@ -117,6 +150,9 @@ impl Clone for Slave {
             parent_index: self.parent_index,
             propagation_delay: self.propagation_delay,
             mailbox_counter: AtomicU8::new(self.mailbox_counter.load(Ordering::Acquire)),
+            lost: self.lost,
+            requested_state: AtomicU8::new(self.requested_state.load(Ordering::Acquire)),
+            links: self.links,
         }
     }
 }
@@ -139,71 +175,185 @@ impl Slave {
             SlaveState::Init
         );
 
-        slave_ref.wait_for_state(SlaveState::Init).await?;
+        slave_ref
+            .wait_for_state_with(SlaveState::Init, &client.timeouts.state_transitions)
+            .await?;
 
         // Make sure master has access to slave EEPROM
         slave_ref.set_eeprom_mode(SiiOwner::Master).await?;
 
-        let identity = slave_ref.eeprom().identity().await?;
+        let eeprom = SlaveEeprom::new(DeviceEeprom::new(client, configured_address));
 
-        let name = slave_ref.eeprom().device_name().await?.unwrap_or_else(|| {
-            let mut s = heapless::String::new();
+        let identity = eeprom.identity().await?;
 
-            fmt::unwrap!(write!(
-                s,
-                "manu. {:#010x}, device {:#010x}, serial {:#010x}",
-                identity.vendor_id, identity.product_id, identity.serial
-            )
-            .map_err(|_| ()));
+        if let Some(cache) = client.eeprom_cache {
+            if !cache.contains(&identity) {
+                eeprom.fill_cache(cache, &identity).await?;
+            }
+        }
 
-            s
-        });
+        let eeprom = SlaveEeprom::new(
+            DeviceEeprom::new(client, configured_address).with_cache_key(identity),
+        );
+
+        let slave = Self::from_eeprom(&eeprom, index, configured_address).await?;
 
         let flags = slave_ref
             .read(RegisterAddress::SupportFlags)
             .receive::<SupportFlags>()
             .await?;
 
-        let ports = slave_ref
+        // The ESC loads the alias from the EEPROM on power up, but it may also have been written
+        // since, so the register takes precedence.
+        let alias = match slave_ref
+            .read(RegisterAddress::ConfiguredStationAlias)
+            .receive::<u16>()
+            .await?
+        {
+            0 => slave.alias,
+            alias => alias,
+        };
+
+        let dl_status = slave_ref
             .read(RegisterAddress::DlStatus)
             .receive::<DlStatus>()
-            .await
-            .map(|dl_status| {
-                // NOTE: dc_receive_times are populated during DC initialisation
-                // Ports in EtherCAT order 0 -> 3 -> 1 -> 2
-                Ports::new(
-                    dl_status.link_port0,
-                    dl_status.link_port3,
-                    dl_status.link_port1,
-                    dl_status.link_port2,
-                )
-            })?;
+            .await?;
+
+        // NOTE: dc_receive_times are populated during DC initialisation
+        // Ports in EtherCAT order 0 -> 3 -> 1 -> 2
+        let ports = Ports::new(
+            dl_status.link_port0,
+            dl_status.link_port3,
+            dl_status.link_port1,
+            dl_status.link_port2,
+        );
+
+        let links = [0, 1, 2, 3].map(|number| dl_status.port(number).link);
 
         fmt::debug!(
             "Slave {:#06x} name {} {}, {}, {}",
             configured_address,
-            name,
-            identity,
+            slave.name,
+            slave.identity,
             flags,
             ports
         );
 
+        Ok(Self {
+            alias,
+            flags,
+            ports,
+            links,
+            ..slave
+        })
+    }
+
+    /// Create a slave device from an EEPROM (SII) image held in memory, without a network.
+    ///
+    /// The slave's identity, name, station alias and default DC sync configuration are read from
+    /// the image. This can be used to check the configuration EtherCrab derives from a known SII
+    /// image, e.g. one shipped with the firmware of an embedded master, or in unit tests.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ethercrab::{EepromMemory, Slave};
+    ///
+    /// # async fn example(image: &[u8]) -> Result<(), ethercrab::error::Error> {
+    /// let slave = Slave::from_eeprom_image(EepromMemory::new(image), 0x1000).await?;
+    ///
+    /// println!("{} {}", slave.name(), slave.identity());
+    /// # Ok(()) }
+    /// ```
+    pub async fn from_eeprom_image<const CHUNK: usize>(
+        image: EepromMemory<'_, CHUNK>,
+        configured_address: u16,
+    ) -> Result<Self, Error> {
+        Self::from_eeprom(&SlaveEeprom::new(image), 0, configured_address).await
+    }
+
+    /// Create a slave instance from the identity and name stored in the given EEPROM.
+    ///
+    /// No registers are read, so the returned slave has default support flags and ports.
+    pub(crate) async fn from_eeprom<P>(
+        eeprom: &SlaveEeprom<P>,
+        index: usize,
+        configured_address: u16,
+    ) -> Result<Self, Error>
+    where
+        P: EepromDataProvider,
+    {
+        let identity = eeprom.identity().await?;
+
+        let name = eeprom.device_name().await?.unwrap_or_else(|| {
+            let mut s = heapless::String::new();
+
+            fmt::unwrap!(write!(
+                s,
+                "manu. {:#010x}, device {:#010x}, serial {:#010x}",
+                identity.vendor_id, identity.product_id, identity.serial
+            )
+            .map_err(|_| ()));
+
+            s
+        });
+
+        let alias = eeprom.station_alias().await?;
+
+        // Use the first DC operation mode that enables SYNC signals as the default DC config.
+        let dc_sync = eeprom
+            .dc_op_modes()
+            .await?
+            .into_iter()
+            .find(|op_mode| op_mode.assign_activate != 0)
+            .map(DcSync::from)
+            .unwrap_or_default();
+
         Ok(Self {
             configured_address,
-            config: SlaveConfig::default(),
+            alias,
+            config: SlaveConfig {
+                dc_sync,
+                ..SlaveConfig::default()
+            },
             index,
             parent_index: None,
             propagation_delay: 0,
             dc_receive_time: 0,
             identity,
             name,
-            flags,
-            ports,
+            flags: SupportFlags::default(),
+            ports: Ports::default(),
             // 0 is a reserved value, so we initialise the cycle at 1. The cycle repeats 1 - 7.
             mailbox_counter: AtomicU8::new(1),
+            lost: false,
+            requested_state: AtomicU8::new(0),
+            links: [false; 4],
         })
     }
 
+    /// Create a slave that has not been found on the network, e.g. a member of a hot connect segment
+    /// that is absent during init, so its configured address can be reserved.
+    pub(crate) fn absent(configured_address: u16) -> Self {
+        Self {
+            configured_address,
+            alias: 0,
+            config: SlaveConfig::default(),
+            identity: SlaveIdentity::default(),
+            name: heapless::String::new(),
+            flags: SupportFlags::default(),
+            ports: Ports::default(),
+            dc_receive_time: 0,
+            index: 0,
+            parent_index: None,
+            propagation_delay: 0,
+            mailbox_counter: AtomicU8::new(1),
+            lost: true,
+            requested_state: AtomicU8::new(0),
+            links: [false; 4],
+        }
+    }
+
     /// Get the slave device's human readable name.
     pub fn name(&self) -> &str {
         self.name.as_str()
@@ -222,6 +372,25 @@ impl Slave {
         self.propagation_delay
     }
 
+    /// Get the position of the slave device in the network, starting at `0` for the device
+    /// closest to the master.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the configured station alias of the slave device, or `0` if it has none.
+    ///
+    /// Unlike its position, a slave's alias does not change when slaves are added to or removed
+    /// from the network, so it can be used to find a slave in a group filter.
+    pub fn alias(&self) -> u16 {
+        self.alias
+    }
+
+    /// Get the Distributed Clock SYNC0/SYNC1 configuration for this slave device.
+    pub fn dc_sync(&self) -> DcSync {
+        self.config.dc_sync
+    }
+
     pub(crate) fn io_segments(&self) -> &IoRanges {
         &self.config.io
     }
@@ -261,6 +430,8 @@ impl Slave {
 pub struct SlaveRef<'a, S> {
     pub(crate) client: &'a Client<'a>,
     pub(crate) configured_address: u16,
+    /// The signal table of the slave's group, if it has one.
+    signals: Option<&'a dyn SignalStore>,
     state: S,
 }
 
@@ -269,6 +440,7 @@ impl<'a> Clone for SlaveRef<'a, ()> {
         Self {
             client: self.client,
             configured_address: self.configured_address,
+            signals: self.signals,
             state: (),
         }
     }
@@ -296,6 +468,108 @@ where
         self.state.propagation_delay
     }
 
+    /// Get the position of the slave device in the network, starting at `0` for the device
+    /// closest to the master.
+    pub fn index(&self) -> usize {
+        self.state.index
+    }
+
+    /// Get the configured station alias of the slave device, or `0` if it has none.
+    pub fn alias(&self) -> u16 {
+        self.state.alias
+    }
+
+    /// Get the Distributed Clock SYNC0/SYNC1 configuration for this slave device.
+    ///
+    /// Defaults are read from the DC category in the slave's EEPROM, if present.
+    pub fn dc_sync(&self) -> DcSync {
+        self.state.config.dc_sync
+    }
+
+    /// State transition timeouts for this slave device.
+    ///
+    /// These are the client's [`Timeouts::state_transitions`](crate::Timeouts::state_transitions)
+    /// unless overridden with [`set_state_timeouts`](SlaveRef::set_state_timeouts).
+    pub fn state_timeouts(&self) -> StateTimeouts {
+        self.state
+            .config
+            .state_timeouts
+            .unwrap_or(self.client.timeouts.state_transitions)
+    }
+
+    /// Watchdog configuration for this slave device, if overridden with
+    /// [`set_watchdog`](SlaveRef::set_watchdog).
+    pub fn watchdog(&self) -> Option<Watchdog> {
+        self.state.config.watchdog
+    }
+
+    /// Wait for this slave device to reach the given state, using the
+    /// [`state_timeouts`](SlaveRef::state_timeouts) for the transition from its current state.
+    ///
+    /// If the slave flags an error, it is acknowledged and [`Error::StateTransition`] is returned.
+    /// The slave's AL status code is stored in the client's
+    /// [`StateErrors`](crate::error::StateErrors), if set. The state can then be requested again.
+    pub async fn wait_for_state(&self, desired_state: SlaveState) -> Result<(), Error> {
+        self.wait_for_state_with(desired_state, &self.state_timeouts())
+            .await
+    }
+
+    /// Request that this slave device moves into the given state, then wait for it to get there.
+    ///
+    /// This can be used to move a single slave out of its group's state, e.g. to put a drive into
+    /// PRE-OP to change a parameter, or into BOOT to update its firmware, while the rest of the
+    /// group stays in OP. See [`SlaveGroup::tx_rx`](crate::SlaveGroup::tx_rx) for how this affects
+    /// the group's working counter. The slave must be returned to the group's state before the
+    /// group itself changes state.
+    ///
+    /// Any error left unacknowledged by a previous failed transition is acknowledged first, as
+    /// slaves will not change state until it is. If the transition fails, the error is
+    /// acknowledged and returned as [`Error::StateTransition`].
+    ///
+    /// [`SlaveGroup::recover`](crate::SlaveGroup::recover) leaves a slave moved out of its group's
+    /// state with this method alone until it is moved back, or the group changes state.
+    pub async fn request_slave_state(&self, desired_state: SlaveState) -> Result<(), Error> {
+        self.state
+            .requested_state
+            .store(u8::from(desired_state), Ordering::Relaxed);
+
+        self.move_to_state(desired_state).await
+    }
+
+    /// Request that this slave device moves into the given state, then wait for it to get there,
+    /// without recording the request as made by the application.
+    pub(crate) async fn move_to_state(&self, desired_state: SlaveState) -> Result<(), Error> {
+        let status = self
+            .read(RegisterAddress::AlStatus)
+            .receive::<AlControl>()
+            .await?;
+
+        if status.error {
+            fmt::warn!(
+                "Slave {:#06x} acknowledging previous error in state {}",
+                self.configured_address,
+                status.state
+            );
+
+            self.write(RegisterAddress::AlControl)
+                .send(AlControl::acknowledge(status.state))
+                .await?;
+        }
+
+        self.request_slave_state_nowait(desired_state).await?;
+
+        self.wait_for_state(desired_state).await
+    }
+
+    /// Get an EEPROM reader for this slave device, served from the client's EEPROM cache if one is
+    /// configured.
+    fn eeprom(&self) -> SlaveEeprom<DeviceEeprom> {
+        SlaveEeprom::new(
+            DeviceEeprom::new(self.client, self.configured_address)
+                .with_cache_key(self.state.identity),
+        )
+    }
+
     /// Return the current cyclic mailbox counter value, from 0-7.
     ///
     /// Calling this method internally increments the counter, so subequent calls will produce a new
@@ -392,12 +666,16 @@ where
         Ok((read_mailbox, write_mailbox))
     }
 
-    /// Wait for a mailbox response
-    async fn coe_response(&self, read_mailbox: &Mailbox) -> Result<RxFrameDataBuf<'_>, Error> {
+    /// Wait up to `timeout` for a mailbox response
+    async fn mailbox_response(
+        &self,
+        read_mailbox: &Mailbox,
+        timeout: Duration,
+    ) -> Result<RxFrameDataBuf<'_>, Error> {
         let mailbox_read_sm = RegisterAddress::sync_manager_status(read_mailbox.sync_manager);
 
         // Wait for slave OUT mailbox to be ready
-        crate::timer_factory::timeout(self.client.timeouts.mailbox_echo, async {
+        crate::timer_factory::timeout(timeout, async {
             loop {
                 let sm_status = self
                     .read(mailbox_read_sm)
@@ -449,7 +727,9 @@ where
             .send(&request.pack().as_ref())
             .await?;
 
-        let mut response = self.coe_response(&read_mailbox).await?;
+        let mut response = self
+            .mailbox_response(&read_mailbox, self.client.timeouts.mailbox_echo)
+            .await?;
 
         /// A super generalised version of the various header shapes for responses, extracting only
         /// what we need in this method.
@@ -652,16 +932,77 @@ where
     }
 }
 
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: DerefMut<Target = Slave>,
+{
+    /// Set the Distributed Clock SYNC0/SYNC1 configuration for this slave device.
+    ///
+    /// The configuration is written to the slave when its group transitions from PRE-OP to
+    /// SAFE-OP.
+    pub fn set_dc_sync(&mut self, dc_sync: DcSync) {
+        self.state.config.dc_sync = dc_sync;
+    }
+
+    /// Override the client's state transition timeouts for this slave device, e.g. for a drive
+    /// that is slow to reach SAFE-OP.
+    pub fn set_state_timeouts(&mut self, timeouts: StateTimeouts) {
+        self.state.config.state_timeouts = Some(timeouts);
+    }
+
+    /// Override the group's sync manager and PDI watchdog configuration for this slave device.
+    ///
+    /// The configuration is written to the slave when its group transitions from PRE-OP to
+    /// SAFE-OP.
+    pub fn set_watchdog(&mut self, watchdog: Watchdog) {
+        self.state.config.watchdog = Some(watchdog);
+    }
+
+    /// Read this slave's identity from its EEPROM again, bypassing the client's EEPROM cache.
+    pub(crate) async fn reload_identity(&mut self) -> Result<(), Error> {
+        self.set_eeprom_mode(SiiOwner::Master).await?;
+
+        let eeprom = SlaveEeprom::new(DeviceEeprom::new(self.client, self.configured_address));
+
+        let identity = eeprom.identity().await?;
+
+        if identity != self.state.identity {
+            fmt::info!(
+                "Slave {:#06x} identity changed from {} to {}",
+                self.configured_address,
+                self.state.identity,
+                identity
+            );
+
+            if let Some(cache) = self.client.eeprom_cache {
+                if !cache.contains(&identity) {
+                    eeprom.fill_cache(cache, &identity).await?;
+                }
+            }
+        }
+
+        self.state.identity = identity;
+
+        Ok(())
+    }
+}
+
 // General impl with no bounds
 impl<'a, S> SlaveRef<'a, S> {
     pub(crate) fn new(client: &'a Client<'a>, configured_address: u16, state: S) -> Self {
         Self {
             client,
             configured_address,
+            signals: None,
             state,
         }
     }
 
+    /// Record and look up PDO signals in the given group signal table.
+    pub(crate) fn with_signals(self, signals: Option<&'a dyn SignalStore>) -> Self {
+        Self { signals, ..self }
+    }
+
     /// Get the configured station address of the slave device.
     pub fn configured_address(&self) -> u16 {
         self.configured_address
@@ -675,6 +1016,17 @@ impl<'a, S> SlaveRef<'a, S> {
             .map(|ctl| ctl.state)
     }
 
+    /// Read the physical link status of every port of this slave device, indexed by EtherCAT port
+    /// number.
+    pub async fn link_status(&self) -> Result<[PortLinkStatus; 4], Error> {
+        let status = self
+            .read(RegisterAddress::DlStatus)
+            .receive::<DlStatus>()
+            .await?;
+
+        Ok([0, 1, 2, 3].map(|number| status.port(number)))
+    }
+
     /// Get the EtherCAT state machine state of the sub device.
     pub async fn status(&self) -> Result<(SlaveState, AlStatusCode), Error> {
         let code = self
@@ -689,10 +1041,6 @@ impl<'a, S> SlaveRef<'a, S> {
         Ok((status, code))
     }
 
-    fn eeprom(&self) -> SlaveEeprom<DeviceEeprom> {
-        SlaveEeprom::new(DeviceEeprom::new(self.client, self.configured_address))
-    }
-
     /// Read a register.
     ///
     /// Note that while this method is marked safe, raw alterations to slave config or behaviour can
@@ -715,16 +1063,24 @@ impl<'a, S> SlaveRef<'a, S> {
         self.write(register.into()).send_receive(value).await
     }
 
-    pub(crate) async fn wait_for_state(&self, desired_state: SlaveState) -> Result<(), Error> {
-        crate::timer_factory::timeout(self.client.timeouts.state_transition, async {
-            loop {
-                let status = self
-                    .read(RegisterAddress::AlStatus)
-                    .ignore_wkc()
-                    .receive::<AlControl>()
-                    .await?;
+    /// Wait for the slave to reach the given state, using the timeout for the transition from its
+    /// current state.
+    pub(crate) async fn wait_for_state_with(
+        &self,
+        desired_state: SlaveState,
+        timeouts: &StateTimeouts,
+    ) -> Result<(), Error> {
+        let current_state = self.checked_state(desired_state).await?;
+
+        if current_state == desired_state {
+            return Ok(());
+        }
 
-                if status.state == desired_state {
+        let timeout = timeouts.transition(Transition::new(current_state, desired_state));
+
+        crate::timer_factory::timeout(timeout, async {
+            loop {
+                if self.checked_state(desired_state).await? == desired_state {
                     break Ok(());
                 }
 
@@ -734,6 +1090,61 @@ impl<'a, S> SlaveRef<'a, S> {
         .await
     }
 
+    /// Read the slave's current state.
+    ///
+    /// If the error flag in the slave's AL status register is set, the error is acknowledged,
+    /// recorded with the reason the slave failed to reach `requested_state`, and returned as
+    /// [`Error::StateTransition`].
+    pub(crate) async fn checked_state(
+        &self,
+        requested_state: SlaveState,
+    ) -> Result<SlaveState, Error> {
+        let status = self
+            .read(RegisterAddress::AlStatus)
+            .ignore_wkc()
+            .receive::<AlControl>()
+            .await?;
+
+        if status.error {
+            let failure = self
+                .acknowledge_error(status.state, requested_state)
+                .await?;
+
+            return Err(Error::StateTransition(
+                self.client.record_state_error(failure),
+            ));
+        }
+
+        Ok(status.state)
+    }
+
+    /// Read the slave's AL status code then acknowledge the error flag so the slave will accept
+    /// another state request.
+    async fn acknowledge_error(
+        &self,
+        current_state: SlaveState,
+        requested_state: SlaveState,
+    ) -> Result<SlaveStateError, Error> {
+        let status_code = self
+            .read(RegisterAddress::AlStatusCode)
+            .ignore_wkc()
+            .receive::<AlStatusCode>()
+            .await?;
+
+        let failure = SlaveStateError {
+            configured_address: self.configured_address,
+            current_state,
+            requested_state,
+            status_code,
+        };
+
+        self.write(RegisterAddress::AlControl)
+            .send(AlControl::acknowledge(current_state))
+            .await?;
+
+        Ok(failure)
+    }
+
     pub(crate) fn write(&self, register: impl Into<u16>) -> WrappedWrite {
         Command::fpwr(self.configured_address, register.into()).wrap(self.client)
     }
@@ -752,35 +1163,10 @@ impl<'a, S> SlaveRef<'a, S> {
             self.configured_address
         );
 
-        // Send state request
-        let response = self
-            .write(RegisterAddress::AlControl)
-            .send_receive::<AlControl>(AlControl::new(desired_state))
-            .await?;
-
-        if response.error {
-            let error = self
-                .read(RegisterAddress::AlStatus)
-                .receive::<AlStatusCode>()
-                .await?;
-
-            fmt::error!(
-                "Error occurred transitioning slave {:#06x} to {:?}: {}",
-                self.configured_address,
-                desired_state,
-                error,
-            );
-
-            return Err(Error::StateTransition);
-        }
-
-        Ok(())
-    }
-
-    pub(crate) async fn request_slave_state(&self, desired_state: SlaveState) -> Result<(), Error> {
-        self.request_slave_state_nowait(desired_state).await?;
-
-        self.wait_for_state(desired_state).await
+        // Send state request. Any error is reported when waiting for the new state.
+        self.write(RegisterAddress::AlControl)
+            .send(AlControl::new(desired_state))
+            .await
     }
 
     pub(crate) async fn set_eeprom_mode(&self, mode: SiiOwner) -> Result<(), Error> {
@@ -1,6 +1,8 @@
 use super::{Slave, SlaveRef};
+use crate::{error::Error, fmt};
 use atomic_refcell::AtomicRefMut;
 use core::ops::Deref;
+use ethercrab_wire::{EtherCrabWireReadSized, EtherCrabWireSized, EtherCrabWireWriteSized};
 
 /// Process Data Image (PDI) segments for a given slave device.
 ///
@@ -138,4 +140,193 @@ impl<'a, 'group> SlaveRef<'a, SlavePdi<'group>> {
     pub fn outputs_raw_mut(&mut self) -> &mut [u8] {
         self.state.outputs
     }
+
+    /// Decode this slave's inputs into the given type.
+    ///
+    /// The packed length of `T` must be equal to the length of the slave's input data, otherwise
+    /// [`Error::ProcessDataLength`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use ethercrab::{error::Error, Client, ClientConfig, PduStorage, SlaveGroupState, Timeouts};
+    ///
+    /// /// Inputs mapped by an EL3004 analog input terminal with its default PDOs.
+    /// #[derive(ethercrab_wire::EtherCrabWireReadWrite)]
+    /// #[wire(bytes = 16)]
+    /// struct El3004Inputs {
+    ///     #[wire(bytes = 2)]
+    ///     status_1: u16,
+    ///     #[wire(bytes = 2)]
+    ///     value_1: i16,
+    ///     #[wire(bytes = 2)]
+    ///     status_2: u16,
+    ///     #[wire(bytes = 2)]
+    ///     value_2: i16,
+    ///     #[wire(bytes = 2)]
+    ///     status_3: u16,
+    ///     #[wire(bytes = 2)]
+    ///     value_3: i16,
+    ///     #[wire(bytes = 2)]
+    ///     status_4: u16,
+    ///     #[wire(bytes = 2)]
+    ///     value_4: i16,
+    /// }
+    ///
+    /// # static PDU_STORAGE: PduStorage<8, 8> = PduStorage::new();
+    /// # async fn case() -> Result<(), Error> {
+    /// # let (tx, rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// let group = client.init_single_group::<8, 64>().await?;
+    /// let group = group.into_op(&client).await?;
+    ///
+    /// group.tx_rx(&client).await?;
+    ///
+    /// let inputs = group.slave(&client, 0)?.inputs::<El3004Inputs>()?;
+    ///
+    /// println!("Channel 1: {}", inputs.value_1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn inputs<T>(&self) -> Result<T, Error>
+    where
+        T: EtherCrabWireReadSized,
+    {
+        self.check_length::<T>(self.state.inputs)?;
+
+        T::unpack_from_slice(self.state.inputs).map_err(Error::from)
+    }
+
+    /// Decode this slave's current outputs into the given type.
+    ///
+    /// The packed length of `T` must be equal to the length of the slave's output data, otherwise
+    /// [`Error::ProcessDataLength`] is returned.
+    pub fn outputs<T>(&self) -> Result<T, Error>
+    where
+        T: EtherCrabWireReadSized,
+    {
+        self.check_length::<T>(self.state.outputs)?;
+
+        T::unpack_from_slice(self.state.outputs).map_err(Error::from)
+    }
+
+    /// Encode the given value into this slave's outputs.
+    ///
+    /// The packed length of `T` must be equal to the length of the slave's output data, otherwise
+    /// [`Error::ProcessDataLength`] is returned.
+    pub fn set_outputs<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: EtherCrabWireWriteSized,
+    {
+        self.check_length::<T>(self.state.outputs)?;
+
+        self.state.outputs.copy_from_slice(value.pack().as_ref());
+
+        Ok(())
+    }
+
+    fn check_length<T>(&self, data: &[u8]) -> Result<(), Error>
+    where
+        T: EtherCrabWireSized,
+    {
+        if data.len() != T::PACKED_LEN {
+            fmt::error!(
+                "Slave {:#06x} process data is {} bytes, type is {} bytes. Check PDO mapping.",
+                self.configured_address,
+                data.len(),
+                T::PACKED_LEN
+            );
+
+            return Err(Error::ProcessDataLength {
+                configured_address: self.configured_address,
+                pdi_length: data.len(),
+                type_length: T::PACKED_LEN,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::client::tests::offline_client;
+    use atomic_refcell::AtomicRefCell;
+
+    /// Run `f` with a reference to `slave` and the given process data, as if borrowed from a
+    /// group with a PDI.
+    pub(crate) fn with_slave_pdi<R>(
+        slave: Slave,
+        inputs: &[u8],
+        outputs: &mut [u8],
+        f: impl FnOnce(SlaveRef<'_, SlavePdi<'_>>) -> R,
+    ) -> R {
+        let client = offline_client();
+
+        let configured_address = slave.configured_address;
+        let slave = AtomicRefCell::new(slave);
+
+        f(SlaveRef::new(
+            &client,
+            configured_address,
+            SlavePdi::new(slave.borrow_mut(), inputs, outputs),
+        ))
+    }
+
+    #[derive(Debug, PartialEq, ethercrab_wire::EtherCrabWireReadWrite)]
+    #[wire(bytes = 3)]
+    struct Io {
+        #[wire(bytes = 2)]
+        word: u16,
+        #[wire(bits = 1, post_skip = 7)]
+        flag: bool,
+    }
+
+    #[test]
+    fn typed_io() {
+        let slave = Slave {
+            configured_address: 0x1000,
+            ..Slave::default()
+        };
+
+        let inputs = [0x34, 0x12, 0x01];
+        let mut outputs = [0u8; 3];
+
+        with_slave_pdi(slave, &inputs, &mut outputs, |mut slave| {
+            assert_eq!(
+                slave.inputs::<Io>(),
+                Ok(Io {
+                    word: 0x1234,
+                    flag: true
+                })
+            );
+
+            assert_eq!(
+                slave.set_outputs(&Io {
+                    word: 0xabcd,
+                    flag: true
+                }),
+                Ok(())
+            );
+            assert_eq!(slave.outputs_raw(), &[0xcd, 0xab, 0x01]);
+
+            assert_eq!(
+                slave.inputs::<u16>(),
+                Err(Error::ProcessDataLength {
+                    configured_address: 0x1000,
+                    pdi_length: 3,
+                    type_length: 2
+                })
+            );
+            assert_eq!(
+                slave.set_outputs(&0u32),
+                Err(Error::ProcessDataLength {
+                    configured_address: 0x1000,
+                    pdi_length: 3,
+                    type_length: 4
+                })
+            );
+        });
+    }
 }
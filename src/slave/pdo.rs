@@ -0,0 +1,1188 @@
+//! Declarative PDO assignment and mapping over CoE, and metadata for mapped PDO entries.
+
+use super::{pdi::SlavePdi, Slave, SlaveRef};
+use crate::{
+    eeprom::types::SyncManagerType,
+    error::{Error, Item},
+    fmt,
+    sync_manager_channel::SM_BASE_ADDRESS,
+    SdoValue,
+};
+use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
+use core::ops::{Deref, DerefMut};
+use ethercrab_wire::{EtherCrabWireReadSized, EtherCrabWireSized, EtherCrabWireWriteSized};
+
+/// Maximum number of sync managers a slave device can have.
+const MAX_SYNC_MANAGERS: usize = 16;
+
+/// A single object mapped into a PDO.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PdoEntry<'a> {
+    /// Object index, e.g. `0x6040` for the DS402 control word.
+    pub index: u16,
+    /// Object sub-index.
+    pub sub_index: u8,
+    /// Length of the object in bits.
+    pub bit_len: u8,
+    /// Name of the object, used to find its signal with [`SlaveRef::signal_by_name`].
+    pub name: Option<&'a str>,
+}
+
+impl<'a> PdoEntry<'a> {
+    /// Map the given object into a PDO.
+    pub const fn new(index: u16, sub_index: u8, bit_len: u8) -> Self {
+        Self {
+            index,
+            sub_index,
+            bit_len,
+            name: None,
+        }
+    }
+
+    /// A gap of the given number of bits.
+    pub const fn padding(bit_len: u8) -> Self {
+        Self::new(0, 0, bit_len)
+    }
+
+    /// Set the name of the mapped object.
+    pub const fn with_name(self, name: &'a str) -> Self {
+        Self {
+            name: Some(name),
+            ..self
+        }
+    }
+
+    /// The value written to the PDO mapping object, e.g. `0x60400010` for a 16 bit object at
+    /// `0x6040:00`.
+    pub const fn mapping(&self) -> u32 {
+        (self.index as u32) << 16 | (self.sub_index as u32) << 8 | self.bit_len as u32
+    }
+}
+
+/// A PDO to assign to a sync manager.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Pdo<'a> {
+    /// PDO index, e.g. `0x1600` or `0x1a00`.
+    pub index: u16,
+    /// Entries to map into the PDO. If this is `None`, the mapping already stored in the slave
+    /// device is used.
+    pub entries: Option<&'a [PdoEntry<'a>]>,
+    /// Name of the PDO, used to find its entries with [`SlaveRef::signal_by_name`].
+    pub name: Option<&'a str>,
+}
+
+impl<'a> Pdo<'a> {
+    /// A PDO with the given entries. The mapping is written to the slave device.
+    pub const fn new(index: u16, entries: &'a [PdoEntry<'a>]) -> Self {
+        Self {
+            index,
+            entries: Some(entries),
+            name: None,
+        }
+    }
+
+    /// A PDO with a fixed or otherwise preconfigured mapping that is not written to the slave
+    /// device.
+    pub const fn fixed(index: u16) -> Self {
+        Self {
+            index,
+            entries: None,
+            name: None,
+        }
+    }
+
+    /// Set the name of the PDO.
+    pub const fn with_name(self, name: &'a str) -> Self {
+        Self {
+            name: Some(name),
+            ..self
+        }
+    }
+
+    /// Total length of all mapped entries in bits, if the entries are known.
+    pub fn bit_len(&self) -> Option<u16> {
+        self.entries
+            .map(|entries| entries.iter().map(|entry| u16::from(entry.bit_len)).sum())
+    }
+}
+
+/// A builder describing the PDOs assigned to one or more sync managers.
+///
+/// By convention, outputs (RxPDOs) are assigned to SM2 through object `0x1c12` and inputs (TxPDOs)
+/// to SM3 through object `0x1c13`.
+///
+/// # Examples
+///
+/// ```rust
+/// use ethercrab::{Pdo, PdoAssignment, PdoEntry};
+///
+/// const OUTPUTS: [Pdo; 1] = [Pdo::new(
+///     0x1600,
+///     &[
+///         // Control word
+///         PdoEntry::new(0x6040, 0, 16),
+///         // Target velocity
+///         PdoEntry::new(0x60ff, 0, 32),
+///     ],
+/// )];
+///
+/// const INPUTS: [Pdo; 2] = [Pdo::fixed(0x1a00), Pdo::fixed(0x1a02)];
+///
+/// let assignment = PdoAssignment::new().outputs(&OUTPUTS).inputs(&INPUTS);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PdoAssignment<'a> {
+    sync_managers: [Option<&'a [Pdo<'a>]>; MAX_SYNC_MANAGERS],
+}
+
+impl<'a> Default for PdoAssignment<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> PdoAssignment<'a> {
+    /// Create an empty assignment that leaves all sync managers unchanged.
+    pub const fn new() -> Self {
+        Self {
+            sync_managers: [None; MAX_SYNC_MANAGERS],
+        }
+    }
+
+    /// Assign outputs (RxPDOs) to SM2.
+    pub const fn outputs(mut self, pdos: &'a [Pdo<'a>]) -> Self {
+        self.sync_managers[2] = Some(pdos);
+
+        self
+    }
+
+    /// Assign inputs (TxPDOs) to SM3.
+    pub const fn inputs(mut self, pdos: &'a [Pdo<'a>]) -> Self {
+        self.sync_managers[3] = Some(pdos);
+
+        self
+    }
+
+    /// Assign PDOs to the given sync manager. An empty list clears the assignment.
+    ///
+    /// Returns [`Error::NotFound`] if `index` is 16 or greater, as slave devices have at most 16
+    /// sync managers.
+    pub const fn sync_manager(mut self, index: u8, pdos: &'a [Pdo<'a>]) -> Result<Self, Error> {
+        if index as usize >= MAX_SYNC_MANAGERS {
+            return Err(Error::NotFound {
+                item: Item::SyncManager,
+                index: Some(index as usize),
+            });
+        }
+
+        self.sync_managers[index as usize] = Some(pdos);
+
+        Ok(self)
+    }
+
+    /// Iterate over all sync managers with an assignment.
+    fn assigned(&self) -> impl Iterator<Item = (u8, &'a [Pdo<'a>])> + '_ {
+        self.sync_managers
+            .iter()
+            .enumerate()
+            .filter_map(|(index, pdos)| pdos.map(|pdos| (index as u8, pdos)))
+    }
+
+    /// Total bit length of all PDOs assigned to a sync manager, if every PDO's entries are known.
+    fn bit_len(pdos: &[Pdo<'_>]) -> Option<u16> {
+        pdos.iter().map(Pdo::bit_len).sum()
+    }
+
+    /// The `(index, sub index, value)` SDO writes that assign `pdos` to the sync manager at
+    /// `sm_address`, in the order they must be sent.
+    ///
+    /// The sync manager's PDO count is first cleared, then for every non-fixed PDO its entry count
+    /// is cleared, its entries are written and its entry count set. Finally the PDOs are assigned
+    /// and the sync manager's PDO count is set.
+    fn sdo_writes<'p>(
+        sm_address: u16,
+        pdos: &'p [Pdo<'p>],
+    ) -> Result<impl Iterator<Item = (u16, u8, SdoValue)> + 'p, Error> {
+        let num_pdos = u8::try_from(pdos.len()).map_err(|_| Error::IntegerTypeConversion)?;
+
+        if pdos
+            .iter()
+            .any(|pdo| pdo.entries.map_or(false, |entries| entries.len() > 255))
+        {
+            return Err(Error::IntegerTypeConversion);
+        }
+
+        let mappings = pdos.iter().flat_map(|pdo| {
+            let entries = pdo.entries.unwrap_or_default();
+
+            // Checked above
+            let num_entries = entries.len() as u8;
+
+            pdo.entries
+                .map(|_| (pdo.index, 0, SdoValue::U8(0)))
+                .into_iter()
+                .chain((1..=num_entries).zip(entries).map(|(sub_index, entry)| {
+                    (pdo.index, sub_index, SdoValue::U32(entry.mapping()))
+                }))
+                .chain(
+                    pdo.entries
+                        .map(|_| (pdo.index, 0, SdoValue::U8(num_entries))),
+                )
+        });
+
+        let assignments = (1..=num_pdos)
+            .zip(pdos)
+            .map(move |(sub_index, pdo)| (sm_address, sub_index, SdoValue::U16(pdo.index)));
+
+        Ok(core::iter::once((sm_address, 0, SdoValue::U8(0)))
+            .chain(mappings)
+            .chain(assignments)
+            .chain(core::iter::once((sm_address, 0, SdoValue::U8(num_pdos)))))
+    }
+}
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: DerefMut<Target = Slave>,
+{
+    /// Write a PDO assignment and mapping to the slave device.
+    ///
+    /// For each sync manager in the assignment, its PDO count is first cleared, then the mapping
+    /// of every non-fixed PDO is written, the PDOs are assigned, and finally the count is set.
+    ///
+    /// If all entries of a sync manager's PDOs are known, its length is used when the group
+    /// transitions into SAFE-OP instead of reading the mapping back from the device, and the
+    /// entries are recorded in the group's signal table along with their PDO and entry names.
+    ///
+    /// This method must be called in PRE-OP, and requires the slave to support CoE.
+    pub async fn configure_pdos(&mut self, assignment: &PdoAssignment<'_>) -> Result<(), Error> {
+        for (sync_manager_index, pdos) in assignment.assigned() {
+            let sm_address = SM_BASE_ADDRESS + u16::from(sync_manager_index);
+
+            fmt::debug!(
+                "Slave {:#06x} assigning {} PDOs to SM{} ({:#06x})",
+                self.configured_address,
+                pdos.len(),
+                sync_manager_index,
+                sm_address
+            );
+
+            for (index, sub_index, value) in PdoAssignment::sdo_writes(sm_address, pdos)? {
+                value.write(self, index, sub_index).await?;
+            }
+
+            self.state.config.sm_bit_lens[usize::from(sync_manager_index)] =
+                PdoAssignment::bit_len(pdos);
+
+            self.record_assigned_signals(sync_manager_index, pdos)?;
+        }
+
+        Ok(())
+    }
+
+    /// Record the entries of the PDOs assigned to a sync manager in the group's signal table.
+    ///
+    /// If any PDO is fixed, the recorded entries are removed and the mapping is read back from the
+    /// device when the group transitions into SAFE-OP.
+    fn record_assigned_signals(&self, sync_manager: u8, pdos: &[Pdo<'_>]) -> Result<(), Error> {
+        let Some(store) = self.signals else {
+            return Ok(());
+        };
+
+        let direction = self
+            .state
+            .config
+            .mailbox
+            .coe_sync_manager_types
+            .get(usize::from(sync_manager))
+            .copied()
+            .and_then(SignalDirection::from_sm_type);
+
+        match direction {
+            Some(direction) if PdoAssignment::bit_len(pdos).is_some() => {
+                let mut recorder = SignalRecorder::new(
+                    self.signals,
+                    self.configured_address,
+                    direction,
+                    sync_manager,
+                )?;
+
+                for pdo in pdos {
+                    for entry in pdo.entries.unwrap_or_default() {
+                        recorder.push(
+                            entry.index,
+                            entry.sub_index,
+                            entry.bit_len,
+                            pdo.name,
+                            entry.name,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            }
+            _ => store.remove(self.configured_address, sync_manager),
+        }
+    }
+}
+
+/// Whether a mapped PDO entry is a slave input or output.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SignalDirection {
+    /// Data read from the slave device (TxPDO).
+    Input,
+    /// Data written to the slave device (RxPDO).
+    Output,
+}
+
+impl SignalDirection {
+    pub(crate) fn from_sm_type(sm_type: SyncManagerType) -> Option<Self> {
+        match sm_type {
+            SyncManagerType::ProcessDataRead => Some(Self::Input),
+            SyncManagerType::ProcessDataWrite => Some(Self::Output),
+            _ => None,
+        }
+    }
+}
+
+/// A PDO entry mapped into a slave device's process data.
+///
+/// Signals are recorded in a group's signal table when the group transitions into SAFE-OP, and can
+/// be found with [`SlaveRef::signals`], [`SlaveRef::signal`] or [`SlaveRef::signal_by_name`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PdoSignal {
+    /// Whether this entry is part of the slave's inputs or outputs.
+    pub direction: SignalDirection,
+    /// The sync manager the entry's PDO is assigned to.
+    pub sync_manager: u8,
+    /// Object index.
+    pub index: u16,
+    /// Object sub-index.
+    pub sub_index: u8,
+    /// Length of the entry in bits.
+    pub bit_len: u8,
+    /// Offset of the entry in bits from the start of the slave's input or output data.
+    pub bit_offset: u16,
+}
+
+impl PdoSignal {
+    /// Byte range of this signal in the slave's input or output data.
+    fn byte_range(&self) -> core::ops::Range<usize> {
+        let start = usize::from(self.bit_offset / 8);
+        let end = (usize::from(self.bit_offset) + usize::from(self.bit_len) + 7) / 8;
+
+        start..end
+    }
+
+    /// Length of this signal in bytes, rounded up.
+    fn byte_len(&self) -> usize {
+        (usize::from(self.bit_len) + 7) / 8
+    }
+}
+
+/// Computes the offsets of a sync manager's mapped entries, in mapping order.
+struct SmSignals {
+    direction: SignalDirection,
+    sync_manager: u8,
+    bit_offset: u16,
+}
+
+impl SmSignals {
+    fn new(direction: SignalDirection, sync_manager: u8, sm_start_bit: u16) -> Self {
+        Self {
+            direction,
+            sync_manager,
+            bit_offset: sm_start_bit,
+        }
+    }
+
+    /// The signal for the next mapped entry, or `None` if the entry is padding.
+    fn next(&mut self, index: u16, sub_index: u8, bit_len: u8) -> Option<PdoSignal> {
+        let signal = PdoSignal {
+            direction: self.direction,
+            sync_manager: self.sync_manager,
+            index,
+            sub_index,
+            bit_len,
+            bit_offset: self.bit_offset,
+        };
+
+        self.bit_offset += u16::from(bit_len);
+
+        (index != 0).then_some(signal)
+    }
+}
+
+/// Maximum length of a recorded signal name, including the name of its PDO.
+pub(crate) const MAX_SIGNAL_NAME: usize = 64;
+
+/// A mapped PDO entry recorded in a group's signal table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SignalEntry {
+    pub(in crate::slave) configured_address: u16,
+    /// The mapped entry, with its offset relative to the start of its sync manager.
+    pub(in crate::slave) signal: PdoSignal,
+    /// The PDO name and entry name separated by `" / "`, or only the entry name if the PDO has no
+    /// name or the combined name is too long. Empty if the entry has no name.
+    name: heapless::String<MAX_SIGNAL_NAME>,
+    /// Start of the entry name in `name`.
+    entry_name_start: usize,
+}
+
+impl SignalEntry {
+    fn new(
+        configured_address: u16,
+        signal: PdoSignal,
+        pdo_name: Option<&str>,
+        entry_name: Option<&str>,
+    ) -> Self {
+        let mut entry = Self {
+            configured_address,
+            signal,
+            name: heapless::String::new(),
+            entry_name_start: 0,
+        };
+
+        if let Some(entry_name) = entry_name {
+            entry.set_name(pdo_name, entry_name);
+        }
+
+        entry
+    }
+
+    pub(in crate::slave) fn is_named(&self) -> bool {
+        !self.name.is_empty()
+    }
+
+    pub(in crate::slave) fn set_name(&mut self, pdo_name: Option<&str>, entry_name: &str) {
+        self.name.clear();
+        self.entry_name_start = 0;
+
+        if let Some(pdo_name) = pdo_name {
+            let name = &mut self.name;
+
+            if name
+                .push_str(pdo_name)
+                .and_then(|_| name.push_str(" / "))
+                .is_ok()
+            {
+                self.entry_name_start = name.len();
+            } else {
+                name.clear();
+            }
+        }
+
+        if self.name.push_str(entry_name).is_err() {
+            self.name.clear();
+            self.entry_name_start = 0;
+
+            if self.name.push_str(entry_name).is_err() {
+                fmt::warn!(
+                    "Slave {:#06x} PDO entry {:#06x}:{} name is longer than {} bytes",
+                    self.configured_address,
+                    self.signal.index,
+                    self.signal.sub_index,
+                    MAX_SIGNAL_NAME
+                );
+            }
+        }
+    }
+
+    /// Whether `name` is either the name of this entry, or the name of its PDO and the entry name
+    /// separated by `" / "`.
+    fn name_matches(&self, name: &str) -> bool {
+        self.is_named() && (name == self.name || name == &self.name[self.entry_name_start..])
+    }
+}
+
+/// Storage for the signals of a group's slave devices, with the capacity erased.
+pub(crate) trait SignalStore: Sync {
+    /// Borrow all recorded signals.
+    fn entries(&self) -> Result<AtomicRef<'_, [SignalEntry]>, Error>;
+
+    /// Borrow all recorded signals mutably.
+    fn entries_mut(&self) -> Result<AtomicRefMut<'_, [SignalEntry]>, Error>;
+
+    /// Record a signal, returning [`Error::Capacity`] if the table is full.
+    fn push(&self, entry: SignalEntry) -> Result<(), Error>;
+
+    /// Remove all signals recorded for the given slave device and sync manager.
+    fn remove(&self, configured_address: u16, sync_manager: u8) -> Result<(), Error>;
+}
+
+impl<'a> core::fmt::Debug for dyn SignalStore + 'a {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("SignalStore")
+    }
+}
+
+/// A group's signal table, holding up to `N` signals.
+#[derive(Debug, Default)]
+pub(crate) struct SignalTable<const N: usize> {
+    entries: AtomicRefCell<heapless::Vec<SignalEntry, N>>,
+}
+
+impl<const N: usize> SignalTable<N> {
+    /// This table, or `None` if it cannot hold any signals.
+    pub(crate) fn store(&self) -> Option<&dyn SignalStore> {
+        if N > 0 {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+impl<const N: usize> SignalStore for SignalTable<N> {
+    fn entries(&self) -> Result<AtomicRef<'_, [SignalEntry]>, Error> {
+        let entries = self.entries.try_borrow().map_err(|_| Error::Borrow)?;
+
+        Ok(AtomicRef::map(entries, |entries| entries.as_slice()))
+    }
+
+    fn entries_mut(&self) -> Result<AtomicRefMut<'_, [SignalEntry]>, Error> {
+        let entries = self.entries.try_borrow_mut().map_err(|_| Error::Borrow)?;
+
+        Ok(AtomicRefMut::map(entries, |entries| entries.as_mut_slice()))
+    }
+
+    fn push(&self, entry: SignalEntry) -> Result<(), Error> {
+        let mut entries = self.entries.try_borrow_mut().map_err(|_| Error::Borrow)?;
+
+        entries.push(entry).map_err(|entry| {
+            fmt::error!(
+                "Slave {:#06x} PDO entry {:#06x}:{} does not fit in group signal table of {} entries",
+                entry.configured_address,
+                entry.signal.index,
+                entry.signal.sub_index,
+                N
+            );
+
+            Error::Capacity(Item::PdoEntry)
+        })
+    }
+
+    fn remove(&self, configured_address: u16, sync_manager: u8) -> Result<(), Error> {
+        let mut entries = self.entries.try_borrow_mut().map_err(|_| Error::Borrow)?;
+
+        entries.retain(|entry| {
+            entry.configured_address != configured_address
+                || entry.signal.sync_manager != sync_manager
+        });
+
+        Ok(())
+    }
+}
+
+/// Records the mapped entries of one sync manager in a group's signal table, replacing any entries
+/// previously recorded for it.
+pub(crate) struct SignalRecorder<'a> {
+    store: Option<&'a dyn SignalStore>,
+    configured_address: u16,
+    offsets: SmSignals,
+}
+
+impl<'a> SignalRecorder<'a> {
+    pub(crate) fn new(
+        store: Option<&'a dyn SignalStore>,
+        configured_address: u16,
+        direction: SignalDirection,
+        sync_manager: u8,
+    ) -> Result<Self, Error> {
+        if let Some(store) = store {
+            store.remove(configured_address, sync_manager)?;
+        }
+
+        Ok(Self {
+            store,
+            configured_address,
+            offsets: SmSignals::new(direction, sync_manager, 0),
+        })
+    }
+
+    /// Record the next mapped entry of the sync manager. Padding is skipped.
+    pub(crate) fn push(
+        &mut self,
+        index: u16,
+        sub_index: u8,
+        bit_len: u8,
+        pdo_name: Option<&str>,
+        entry_name: Option<&str>,
+    ) -> Result<(), Error> {
+        let signal = self.offsets.next(index, sub_index, bit_len);
+
+        match (self.store, signal) {
+            (Some(store), Some(signal)) => store.push(SignalEntry::new(
+                self.configured_address,
+                signal,
+                pdo_name,
+                entry_name,
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Copy `bit_len` bits starting at `bit_offset` in `src` into the beginning of `dst`, LSB first.
+fn read_bits(src: &[u8], bit_offset: usize, bit_len: usize, dst: &mut [u8]) {
+    for bit in 0..bit_len {
+        let src_bit = bit_offset + bit;
+
+        let value = src[src_bit / 8] >> (src_bit % 8) & 1;
+
+        dst[bit / 8] |= value << (bit % 8);
+    }
+}
+
+/// Copy the first `bit_len` bits of `src` into `dst`, starting at `bit_offset`, LSB first.
+fn write_bits(dst: &mut [u8], bit_offset: usize, bit_len: usize, src: &[u8]) {
+    for bit in 0..bit_len {
+        let dst_bit = bit_offset + bit;
+
+        let value = src[bit / 8] >> (bit % 8) & 1;
+
+        dst[dst_bit / 8] = dst[dst_bit / 8] & !(1 << (dst_bit % 8)) | value << (dst_bit % 8);
+    }
+}
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Copy every PDO entry mapped into this slave device's process data into `signals`.
+    ///
+    /// Signals are recorded in the group's signal table when the group transitions into SAFE-OP,
+    /// so are only found once the group has been configured. No signals are found if the group
+    /// has no signal table.
+    ///
+    /// [`Error::Capacity`] is returned if `signals` cannot hold every mapped entry.
+    pub fn signals<const N: usize>(
+        &self,
+        signals: &mut heapless::Vec<PdoSignal, N>,
+    ) -> Result<(), Error> {
+        signals.clear();
+
+        let Some(store) = self.signals else {
+            return Ok(());
+        };
+
+        for signal in store
+            .entries()?
+            .iter()
+            .filter_map(|entry| self.signal_of(entry))
+        {
+            signals.push(signal).map_err(|_| {
+                fmt::error!(
+                    "Slave {:#06x} has more than {} mapped PDO entries",
+                    self.configured_address,
+                    N
+                );
+
+                Error::Capacity(Item::PdoEntry)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Find a mapped PDO entry by its object index and sub-index.
+    pub fn signal(&self, index: u16, sub_index: u8) -> Result<Option<PdoSignal>, Error> {
+        self.find_signal(|entry| entry.signal.index == index && entry.signal.sub_index == sub_index)
+    }
+
+    /// Find a mapped PDO entry by name.
+    ///
+    /// The name may either be the name of the entry, e.g. `"Value"`, or the name of the PDO
+    /// followed by the entry name, separated by `" / "`, e.g. `"AI Standard Channel 1 / Value"`.
+    /// The first matching entry is returned.
+    ///
+    /// Names are taken from the PDOs passed to [`configure_pdos`](SlaveRef::configure_pdos), or
+    /// from the slave's EEPROM for PDOs that EtherCrab did not map.
+    pub fn signal_by_name(&self, name: &str) -> Result<Option<PdoSignal>, Error> {
+        self.find_signal(|entry| entry.name_matches(name))
+    }
+
+    /// The first recorded signal of this slave device that matches `f`.
+    fn find_signal(&self, f: impl Fn(&SignalEntry) -> bool) -> Result<Option<PdoSignal>, Error> {
+        let Some(store) = self.signals else {
+            return Ok(None);
+        };
+
+        let entries = store.entries()?;
+
+        Ok(entries
+            .iter()
+            .filter(|entry| f(entry))
+            .find_map(|entry| self.signal_of(entry)))
+    }
+
+    /// A recorded signal of this slave device with its offset from the start of the slave's
+    /// inputs or outputs, if its sync manager is mapped into the PDI.
+    fn signal_of(&self, entry: &SignalEntry) -> Option<PdoSignal> {
+        if entry.configured_address != self.configured_address {
+            return None;
+        }
+
+        let sm_start_bit = self
+            .state
+            .config
+            .sm_start_bits
+            .get(usize::from(entry.signal.sync_manager))
+            .copied()
+            .flatten()?;
+
+        Some(PdoSignal {
+            bit_offset: sm_start_bit + entry.signal.bit_offset,
+            ..entry.signal
+        })
+    }
+}
+
+/// Methods to access individual PDO entries in the process data.
+impl<'a, 'group> SlaveRef<'a, SlavePdi<'group>> {
+    /// Decode a mapped PDO entry from this slave's process data.
+    ///
+    /// The packed length of `T` must be equal to the length of the signal rounded up to whole
+    /// bytes, otherwise [`Error::ProcessDataLength`] is returned. Signals that are not byte
+    /// aligned, e.g. single bits, are shifted into the first byte before decoding.
+    pub fn read_signal<T>(&self, signal: &PdoSignal) -> Result<T, Error>
+    where
+        T: EtherCrabWireReadSized,
+    {
+        let data = match signal.direction {
+            SignalDirection::Input => self.inputs_raw(),
+            SignalDirection::Output => self.outputs_raw(),
+        };
+
+        self.check_signal::<T>(signal, data)?;
+
+        let mut buf = T::buffer();
+
+        read_bits(
+            data,
+            usize::from(signal.bit_offset),
+            usize::from(signal.bit_len),
+            buf.as_mut(),
+        );
+
+        T::unpack_from_slice(buf.as_ref()).map_err(Error::from)
+    }
+
+    /// Encode a value into a mapped output PDO entry in this slave's process data.
+    ///
+    /// The packed length of `T` must be equal to the length of the signal rounded up to whole
+    /// bytes, otherwise [`Error::ProcessDataLength`] is returned. Only the signal's bits are
+    /// written.
+    pub fn write_signal<T>(&mut self, signal: &PdoSignal, value: &T) -> Result<(), Error>
+    where
+        T: EtherCrabWireWriteSized,
+    {
+        if signal.direction != SignalDirection::Output {
+            fmt::error!(
+                "Slave {:#06x} PDO entry {:#06x}:{} is an input and cannot be written",
+                self.configured_address,
+                signal.index,
+                signal.sub_index
+            );
+
+            return Err(Error::ReadOnlySignal {
+                configured_address: self.configured_address,
+                index: signal.index,
+                sub_index: signal.sub_index,
+            });
+        }
+
+        self.check_signal::<T>(signal, self.outputs_raw())?;
+
+        let buf = value.pack();
+
+        write_bits(
+            self.outputs_raw_mut(),
+            usize::from(signal.bit_offset),
+            usize::from(signal.bit_len),
+            buf.as_ref(),
+        );
+
+        Ok(())
+    }
+
+    fn check_signal<T>(&self, signal: &PdoSignal, data: &[u8]) -> Result<(), Error>
+    where
+        T: EtherCrabWireSized,
+    {
+        if signal.byte_range().end > data.len() {
+            fmt::error!(
+                "Slave {:#06x} PDO entry {:#06x}:{} is outside of process data",
+                self.configured_address,
+                signal.index,
+                signal.sub_index
+            );
+
+            return Err(Error::NotFound {
+                item: Item::PdoEntry,
+                index: None,
+            });
+        }
+
+        if signal.byte_len() != T::PACKED_LEN {
+            return Err(Error::ProcessDataLength {
+                configured_address: self.configured_address,
+                pdi_length: signal.byte_len(),
+                type_length: T::PACKED_LEN,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slave::pdi::tests::with_slave_pdi;
+
+    #[test]
+    fn entry_mapping() {
+        assert_eq!(PdoEntry::new(0x6040, 0, 16).mapping(), 0x6040_0010);
+        assert_eq!(PdoEntry::new(0x7000, 0x11, 1).mapping(), 0x7000_1101);
+        assert_eq!(PdoEntry::padding(8).mapping(), 0x0000_0008);
+    }
+
+    #[test]
+    fn sync_manager_bit_len() {
+        const OUTPUTS: [Pdo; 2] = [
+            Pdo::new(0x1600, &[PdoEntry::new(0x6040, 0, 16)]),
+            Pdo::new(
+                0x1601,
+                &[PdoEntry::new(0x60ff, 0, 32), PdoEntry::padding(8)],
+            ),
+        ];
+
+        const INPUTS: [Pdo; 2] = [
+            Pdo::new(0x1a00, &[PdoEntry::new(0x6041, 0, 16)]),
+            Pdo::fixed(0x1a01),
+        ];
+
+        let assignment = PdoAssignment::new().outputs(&OUTPUTS).inputs(&INPUTS);
+
+        assert_eq!(
+            assignment
+                .assigned()
+                .map(|(index, pdos)| (index, PdoAssignment::bit_len(pdos)))
+                .collect::<Vec<_>>(),
+            [(2, Some(56)), (3, None)]
+        );
+    }
+
+    #[test]
+    fn clear_assignment() {
+        let assignment = PdoAssignment::new().outputs(&[]);
+
+        assert_eq!(
+            assignment
+                .assigned()
+                .map(|(index, pdos)| (index, PdoAssignment::bit_len(pdos)))
+                .collect::<Vec<_>>(),
+            [(2, Some(0))]
+        );
+    }
+
+    #[test]
+    fn checked_sync_manager() {
+        assert!(PdoAssignment::new().sync_manager(15, &[]).is_ok());
+        assert_eq!(
+            PdoAssignment::new().sync_manager(16, &[]),
+            Err(Error::NotFound {
+                item: Item::SyncManager,
+                index: Some(16)
+            })
+        );
+    }
+
+    #[test]
+    fn sdo_write_order() {
+        const OUTPUTS: [Pdo; 2] = [
+            Pdo::new(
+                0x1600,
+                &[PdoEntry::new(0x6040, 0, 16), PdoEntry::new(0x60ff, 0, 32)],
+            ),
+            Pdo::fixed(0x1601),
+        ];
+
+        let writes = PdoAssignment::sdo_writes(0x1c12, &OUTPUTS)
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            writes,
+            [
+                // Clear SM assignment
+                (0x1c12, 0, SdoValue::U8(0)),
+                // Clear, write and count entries of the non-fixed PDO
+                (0x1600, 0, SdoValue::U8(0)),
+                (0x1600, 1, SdoValue::U32(0x6040_0010)),
+                (0x1600, 2, SdoValue::U32(0x60ff_0020)),
+                (0x1600, 0, SdoValue::U8(2)),
+                // Assign PDOs, then set the count
+                (0x1c12, 1, SdoValue::U16(0x1600)),
+                (0x1c12, 2, SdoValue::U16(0x1601)),
+                (0x1c12, 0, SdoValue::U8(2)),
+            ]
+        );
+
+        assert_eq!(
+            PdoAssignment::sdo_writes(0x1c13, &[])
+                .unwrap()
+                .collect::<Vec<_>>(),
+            [(0x1c13, 0, SdoValue::U8(0)), (0x1c13, 0, SdoValue::U8(0))]
+        );
+    }
+
+    #[test]
+    fn signal_offsets() {
+        let mut outputs = SmSignals::new(SignalDirection::Output, 2, 0);
+
+        assert_eq!(
+            outputs.next(0x7000, 1, 1),
+            Some(PdoSignal {
+                direction: SignalDirection::Output,
+                sync_manager: 2,
+                index: 0x7000,
+                sub_index: 1,
+                bit_len: 1,
+                bit_offset: 0
+            })
+        );
+        // Padding is skipped but still takes up space
+        assert_eq!(outputs.next(0, 0, 7), None);
+        assert_eq!(outputs.next(0x7010, 1, 16).map(|s| s.bit_offset), Some(8));
+
+        // Sync managers start at their offset in the slave's data
+        let mut inputs = SmSignals::new(SignalDirection::Input, 3, 16);
+
+        assert_eq!(
+            inputs.next(0x6000, 0x11, 16).map(|s| s.bit_offset),
+            Some(16)
+        );
+        assert_eq!(inputs.next(0x6010, 0x11, 8).map(|s| s.bit_offset), Some(32));
+    }
+
+    #[test]
+    fn signal_names() {
+        let signal = SmSignals::new(SignalDirection::Input, 3, 0)
+            .next(0x6000, 0x11, 16)
+            .unwrap();
+
+        let entry = SignalEntry::new(0x1000, signal, Some("AI Standard Channel 1"), Some("Value"));
+
+        assert!(entry.name_matches("Value"));
+        assert!(entry.name_matches("AI Standard Channel 1 / Value"));
+        assert!(!entry.name_matches("AI Standard Channel 2 / Value"));
+        assert!(!entry.name_matches("Status"));
+
+        let entry = SignalEntry::new(0x1000, signal, None, Some("Value"));
+
+        assert!(entry.name_matches("Value"));
+        assert!(!entry.name_matches("AI Standard Channel 1 / Value"));
+
+        // Unnamed entries never match
+        assert!(
+            !SignalEntry::new(0x1000, signal, Some("AI Standard Channel 1"), None).name_matches("")
+        );
+
+        // Only the entry name is kept if the combined name is too long
+        let pdo_name = "x".repeat(MAX_SIGNAL_NAME - 4);
+        let entry = SignalEntry::new(0x1000, signal, Some(&pdo_name), Some("Value"));
+
+        assert!(entry.name_matches("Value"));
+        assert!(!entry.name_matches(&format!("{} / Value", pdo_name)));
+    }
+
+    #[test]
+    fn signal_table() {
+        let table = SignalTable::<3>::default();
+
+        let mut recorder =
+            SignalRecorder::new(Some(&table), 0x1000, SignalDirection::Output, 2).unwrap();
+
+        assert_eq!(recorder.push(0x7000, 1, 1, None, Some("Flag")), Ok(()));
+        assert_eq!(recorder.push(0, 0, 7, None, None), Ok(()));
+        assert_eq!(recorder.push(0x7010, 1, 16, None, Some("Word")), Ok(()));
+
+        let mut recorder =
+            SignalRecorder::new(Some(&table), 0x1001, SignalDirection::Output, 2).unwrap();
+
+        assert_eq!(recorder.push(0x7000, 1, 8, None, None), Ok(()));
+        assert_eq!(
+            recorder.push(0x7010, 1, 8, None, None),
+            Err(Error::Capacity(Item::PdoEntry))
+        );
+
+        // Padding is not recorded, but offsets within the sync manager are kept
+        assert_eq!(
+            table
+                .entries()
+                .unwrap()
+                .iter()
+                .map(|entry| (entry.configured_address, entry.signal.bit_offset))
+                .collect::<Vec<_>>(),
+            [(0x1000, 0), (0x1000, 8), (0x1001, 0)]
+        );
+
+        // Recording a sync manager again replaces its previous entries
+        SignalRecorder::new(Some(&table), 0x1000, SignalDirection::Output, 2).unwrap();
+
+        assert_eq!(
+            table
+                .entries()
+                .unwrap()
+                .iter()
+                .map(|entry| entry.configured_address)
+                .collect::<Vec<_>>(),
+            [0x1001]
+        );
+    }
+
+    #[test]
+    fn signal_lookup() {
+        let table = SignalTable::<4>::default();
+
+        let mut recorder =
+            SignalRecorder::new(Some(&table), 0x1000, SignalDirection::Input, 3).unwrap();
+
+        recorder
+            .push(
+                0x6000,
+                0x11,
+                16,
+                Some("AI Standard Channel 1"),
+                Some("Value"),
+            )
+            .unwrap();
+
+        // Another slave's signals are not found
+        SignalRecorder::new(Some(&table), 0x1001, SignalDirection::Input, 3)
+            .unwrap()
+            .push(0x6010, 0x11, 16, None, Some("Status"))
+            .unwrap();
+
+        // Sync manager not mapped into the PDI
+        SignalRecorder::new(Some(&table), 0x1000, SignalDirection::Input, 4)
+            .unwrap()
+            .push(0x6020, 0x11, 16, None, Some("Unmapped"))
+            .unwrap();
+
+        let mut slave = Slave {
+            configured_address: 0x1000,
+            ..Slave::default()
+        };
+
+        slave.config.sm_start_bits[3] = Some(16);
+
+        with_slave_pdi(slave, &[0u8; 4], &mut [], |slave| {
+            let expected = PdoSignal {
+                direction: SignalDirection::Input,
+                sync_manager: 3,
+                index: 0x6000,
+                sub_index: 0x11,
+                bit_len: 16,
+                bit_offset: 16,
+            };
+
+            // No signal table
+            assert_eq!(slave.signal(0x6000, 0x11), Ok(None));
+
+            let slave = slave.with_signals(Some(&table));
+
+            let mut signals = heapless::Vec::<PdoSignal, 4>::new();
+
+            assert_eq!(slave.signals(&mut signals), Ok(()));
+            assert_eq!(signals.as_slice(), &[expected]);
+
+            assert_eq!(slave.signal(0x6000, 0x11), Ok(Some(expected)));
+            assert_eq!(slave.signal(0x6010, 0x11), Ok(None));
+            assert_eq!(slave.signal_by_name("Value"), Ok(Some(expected)));
+            assert_eq!(
+                slave.signal_by_name("AI Standard Channel 1 / Value"),
+                Ok(Some(expected))
+            );
+            assert_eq!(slave.signal_by_name("Status"), Ok(None));
+            assert_eq!(slave.signal_by_name("Unmapped"), Ok(None));
+
+            assert_eq!(
+                slave.signals(&mut heapless::Vec::<PdoSignal, 0>::new()),
+                Err(Error::Capacity(Item::PdoEntry))
+            );
+        });
+    }
+
+    #[test]
+    fn bits() {
+        let mut buf = [0u8; 2];
+
+        read_bits(&[0b1011_0000, 0b0000_0001], 4, 5, &mut buf);
+
+        assert_eq!(buf, [0b0001_1011, 0]);
+
+        let mut data = [0xffu8, 0x00];
+
+        write_bits(&mut data, 6, 4, &[0b0000_0110]);
+
+        assert_eq!(data, [0b1011_1111, 0b0000_0001]);
+    }
+
+    #[test]
+    fn typed_signals() {
+        let slave = Slave {
+            configured_address: 0x1000,
+            ..Slave::default()
+        };
+
+        let mut outputs = SmSignals::new(SignalDirection::Output, 2, 0);
+
+        let flag = outputs.next(0x7000, 1, 1).unwrap();
+        let other = outputs.next(0x7010, 1, 1).unwrap();
+        outputs.next(0, 0, 6);
+        let word = outputs.next(0x7020, 1, 16).unwrap();
+
+        let value = SmSignals::new(SignalDirection::Input, 3, 0)
+            .next(0x6000, 0x11, 16)
+            .unwrap();
+
+        let inputs = [0x34, 0x12];
+        let mut outputs = [0u8; 3];
+
+        with_slave_pdi(slave, &inputs, &mut outputs, |mut slave| {
+            assert_eq!(slave.read_signal::<u16>(&value), Ok(0x1234));
+
+            assert_eq!(slave.write_signal(&other, &true), Ok(()));
+            assert_eq!(slave.write_signal(&word, &0xabcdu16), Ok(()));
+            assert_eq!(slave.outputs_raw(), &[0b0000_0010, 0xcd, 0xab]);
+            assert_eq!(slave.read_signal::<bool>(&other), Ok(true));
+            assert_eq!(slave.read_signal::<bool>(&flag), Ok(false));
+
+            assert_eq!(
+                slave.read_signal::<u32>(&value),
+                Err(Error::ProcessDataLength {
+                    configured_address: 0x1000,
+                    pdi_length: 2,
+                    type_length: 4
+                })
+            );
+            assert_eq!(
+                slave.write_signal(&value, &0u16),
+                Err(Error::ReadOnlySignal {
+                    configured_address: 0x1000,
+                    index: 0x6000,
+                    sub_index: 0x11
+                })
+            );
+        });
+    }
+}
@@ -0,0 +1,206 @@
+//! Finding slave devices that have been disconnected or power cycled.
+
+use super::{eeprom::SlaveEeprom, Slave, SlaveRef};
+use crate::{
+    al_control::AlControl,
+    command::Command,
+    eeprom::{device_reader::DeviceEeprom, types::SiiOwner},
+    error::Error,
+    expected::ExpectedSlave,
+    fmt,
+    register::RegisterAddress,
+};
+use core::ops::Deref;
+
+/// Station address given to a reappearing slave device while its identity is checked. This is the
+/// same address SOEM uses.
+const TEMP_ADDRESS: u16 = 0x7fff;
+
+/// Whether a slave device can be reached on the network.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Presence {
+    /// The slave responds at its configured address.
+    Present,
+    /// The slave reappeared without a configured address at the given position in the network and
+    /// was given its old address again. It must be configured again before it can be used.
+    Readdressed { position: usize },
+    /// The slave does not respond at its configured address, and no device without an address and
+    /// with the same identity was found on the network.
+    Missing,
+}
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Check whether this slave device is still on the network, giving it its configured address
+    /// again if it has been power cycled.
+    ///
+    /// Based on SOEM's `ecx_recover_slave`. A power cycled slave loses its configured address, so
+    /// devices with no address are checked for the same identity as this slave. The first match is
+    /// given this slave's configured address.
+    ///
+    /// Positions in the network shift when other devices, e.g. hot connect segments, are connected
+    /// or disconnected, so the slave's position from init is only used as a starting point and
+    /// the devices closest to it are checked first. Devices that still have an address are never
+    /// readdressed.
+    pub(crate) async fn find_on_network(&self) -> Result<Presence, Error> {
+        match self
+            .read(RegisterAddress::AlStatus)
+            .receive::<AlControl>()
+            .await
+        {
+            Ok(_) => return Ok(Presence::Present),
+            Err(Error::WorkingCounter { .. }) => (),
+            Err(e) => return Err(e),
+        }
+
+        let num_devices = self.client.count_slaves().await?;
+
+        let mut mismatch = None;
+        let mut mismatches = 0;
+
+        for position in positions_by_distance(self.state.index, num_devices) {
+            match self.check_position(position).await? {
+                Some(true) => {
+                    fmt::info!(
+                        "Slave {:#06x} found again at position {}",
+                        self.configured_address,
+                        position
+                    );
+
+                    return Ok(Presence::Readdressed {
+                        position: usize::from(position),
+                    });
+                }
+                Some(false) => {
+                    mismatch.get_or_insert(usize::from(position));
+                    mismatches += 1;
+                }
+                None => (),
+            }
+        }
+
+        match mismatch {
+            Some(position) => Err(Error::TopologyMismatch {
+                position,
+                count: mismatches,
+            }),
+            None => Ok(Presence::Missing),
+        }
+    }
+
+    /// Check whether the device at the given position has no address and the same identity as
+    /// this slave, giving it this slave's configured address if it does.
+    ///
+    /// Returns `None` if there is no device without an address at the position.
+    async fn check_position(&self, position: u16) -> Result<Option<bool>, Error> {
+        let address =
+            match Command::aprd(position, RegisterAddress::ConfiguredStationAddress.into())
+                .wrap(self.client)
+                .receive::<u16>()
+                .await
+            {
+                Ok(address) => address,
+                Err(Error::WorkingCounter { .. }) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+
+        if address != 0 {
+            return Ok(None);
+        }
+
+        fmt::debug!(
+            "Found device with no address at position {}, checking identity against slave {:#06x}",
+            position,
+            self.configured_address
+        );
+
+        Command::apwr(position, RegisterAddress::ConfiguredStationAddress.into())
+            .wrap(self.client)
+            .send(TEMP_ADDRESS)
+            .await?;
+
+        let temp = SlaveRef::new(self.client, TEMP_ADDRESS, ());
+
+        let identity = async {
+            temp.set_eeprom_mode(SiiOwner::Master).await?;
+
+            SlaveEeprom::new(DeviceEeprom::new(self.client, TEMP_ADDRESS))
+                .identity()
+                .await
+        }
+        .await;
+
+        let expected = self.state.identity;
+
+        let kind = match identity {
+            Ok(found) => ExpectedSlave::new(expected.vendor_id, expected.product_id)
+                .with_revision(expected.revision)
+                .identity_mismatch(found),
+            Err(e) => {
+                // Remove the temporary address so the device can be found again next time
+                temp.write(RegisterAddress::ConfiguredStationAddress)
+                    .send(0u16)
+                    .await?;
+
+                return Err(e);
+            }
+        };
+
+        if let Some(kind) = kind {
+            fmt::warn!(
+                "Device at position {} does not match slave {:#06x}: {:?}",
+                position,
+                self.configured_address,
+                kind
+            );
+
+            temp.write(RegisterAddress::ConfiguredStationAddress)
+                .send(0u16)
+                .await?;
+
+            return Ok(Some(false));
+        }
+
+        temp.write(RegisterAddress::ConfiguredStationAddress)
+            .send(self.configured_address)
+            .await?;
+
+        Ok(Some(true))
+    }
+}
+
+/// Every position in a network of `num_devices` devices, ordered by distance from `start`.
+fn positions_by_distance(start: usize, num_devices: u16) -> impl Iterator<Item = u16> {
+    let num_devices = usize::from(num_devices);
+
+    (0..=start.max(num_devices))
+        .flat_map(move |distance| {
+            let before = start.checked_sub(distance);
+            let after = Some(start + distance).filter(|_| distance > 0);
+
+            before.into_iter().chain(after)
+        })
+        .filter(move |position| *position < num_devices)
+        .map(|position| position as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_order() {
+        assert_eq!(
+            positions_by_distance(2, 5).collect::<Vec<_>>(),
+            [2, 1, 3, 0, 4]
+        );
+
+        // Devices before the slave were removed from the network
+        assert_eq!(positions_by_distance(7, 3).collect::<Vec<_>>(), [2, 1, 0]);
+
+        assert_eq!(positions_by_distance(0, 3).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(positions_by_distance(0, 0).count(), 0);
+    }
+}
@@ -1,11 +1,14 @@
 use crate::{
+    dc::DcSync,
     eeprom::types::{MailboxProtocols, SyncManagerType},
     pdi::PdiSegment,
+    timer_factory::StateTimeouts,
+    watchdog::Watchdog,
 };
 use core::fmt::{self, Debug};
 
 /// Slave identity information (vendor ID, product ID, etc).
-#[derive(Default, Copy, Clone, PartialEq, ethercrab_wire::EtherCrabWireRead)]
+#[derive(Default, Copy, Clone, PartialEq, Eq, Hash, ethercrab_wire::EtherCrabWireRead)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[wire(bytes = 16)]
 pub struct SlaveIdentity {
@@ -47,6 +50,19 @@ impl Debug for SlaveIdentity {
 pub struct SlaveConfig {
     pub io: IoRanges,
     pub mailbox: MailboxConfig,
+    pub dc_sync: DcSync,
+    /// Process data bit length of each sync manager, if known from a PDO assignment written by
+    /// EtherCrab.
+    pub sm_bit_lens: [Option<u16>; 16],
+    /// Offset in bits of each sync manager's data from the start of the slave's inputs or
+    /// outputs, if the sync manager is mapped into the PDI.
+    pub sm_start_bits: [Option<u16>; 16],
+    /// State transition timeouts overriding the client's defaults.
+    pub state_timeouts: Option<StateTimeouts>,
+    /// Watchdog configuration overriding the slave's group configuration.
+    pub watchdog: Option<Watchdog>,
+    /// Whether the AL status register is mapped into logical memory by a spare FMMU.
+    pub al_status_mapped: bool,
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -1,16 +1,21 @@
 use crate::{
+    driver,
     error::Error,
     fmt,
     pdi::PdiOffset,
-    slave::{Slave, SlaveRef},
-    Client, SlaveGroup,
+    slave::{SignalStore, Slave, SlaveRef},
+    slave_group::hot_connect::HotConnectSegment,
+    Client, SlaveGroup, Transition,
 };
+#[cfg(feature = "alloc")]
+use crate::{hook, SlaveHook};
 use atomic_refcell::AtomicRefCell;
 
 #[derive(Debug)]
 struct GroupInnerRef<'a> {
     slaves: &'a mut [AtomicRefCell<Slave>],
     pdi_start: &'a mut PdiOffset,
+    segments: &'a [HotConnectSegment],
 }
 
 // TODO: Prove if this is safe. All this stuff is internal to the crate and short lived so I think
@@ -24,21 +29,33 @@ unsafe impl<'a> Send for SlaveGroupRef<'a> {}
 /// [`Client::init`](crate::Client::init).
 pub struct SlaveGroupRef<'a> {
     max_pdi_len: usize,
+    #[cfg(feature = "alloc")]
+    hook: Option<&'a dyn SlaveHook>,
+    signals: Option<&'a dyn SignalStore>,
     inner: GroupInnerRef<'a>,
 }
 
 impl<'a> SlaveGroupRef<'a> {
-    pub(in crate::slave_group) fn new<const MAX_SLAVES: usize, const MAX_PDI: usize, S>(
-        group: &'a SlaveGroup<MAX_SLAVES, MAX_PDI, S>,
+    pub(in crate::slave_group) fn new<
+        const MAX_SLAVES: usize,
+        const MAX_PDI: usize,
+        S,
+        const MAX_SIGNALS: usize,
+    >(
+        group: &'a SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>,
     ) -> Self {
         Self {
             max_pdi_len: MAX_PDI,
+            #[cfg(feature = "alloc")]
+            hook: group.hook,
+            signals: group.signals.store(),
             inner: {
                 let inner = unsafe { fmt::unwrap_opt!(group.inner.get().as_mut()) };
 
                 GroupInnerRef {
                     slaves: &mut inner.slaves,
                     pdi_start: &mut inner.pdi_start,
+                    segments: &inner.segments,
                 }
             },
         }
@@ -64,13 +81,35 @@ impl<'a> SlaveGroupRef<'a> {
         );
 
         // Configure master read PDI mappings in the first section of the PDI
-        for slave in inner.slaves.iter_mut() {
+        for (index, slave) in inner.slaves.iter_mut().enumerate() {
+            // Slaves of hot connect segments that were not found during init are configured when
+            // the segment is connected.
+            if inner
+                .segments
+                .iter()
+                .any(|segment| !segment.is_present() && segment.slaves().contains(&index))
+            {
+                continue;
+            }
+
             let slave = slave.get_mut();
 
-            let mut slave_config = SlaveRef::new(client, slave.configured_address, slave);
+            let mut slave_config = SlaveRef::new(client, slave.configured_address, &mut *slave);
 
             // TODO: Move PRE-OP transition out of this so we can do it for the group just once
             slave_config.configure_mailboxes().await?;
+
+            driver::configure(client, Transition::INIT_TO_PRE_OP, slave, self.signals).await?;
+
+            #[cfg(feature = "alloc")]
+            hook::call_hooks(
+                client,
+                self.hook,
+                Transition::INIT_TO_PRE_OP,
+                slave,
+                self.signals,
+            )
+            .await?;
         }
 
         Ok(pdi_position.increment(self.max_pdi_len as u16))
@@ -0,0 +1,674 @@
+use super::{HasPdi, SlaveGroup};
+use crate::{
+    al_control::AlControl,
+    command::Command,
+    error::Error,
+    fmt,
+    register::RegisterAddress,
+    slave::{configuration::AL_STATUS_LOGICAL_START, Slave, SlaveRef},
+    AlStatusCode, Client, ErrorCounters, SlaveState, WatchdogStatus,
+};
+use core::future::Future;
+use ethercrab_wire::EtherCrabWireRead;
+
+/// The number of slave devices that are read from or written to concurrently by group
+/// diagnostics, each taking one PDU frame.
+const BATCH: usize = 4;
+
+/// Length of the AL status registers from 0x0130 to 0x0135, including the AL status code.
+const AL_STATUS_LEN: usize = 6;
+
+/// The most mapped AL status bytes read by one `LRD` in [`SlaveGroup::check_health`], which is
+/// the largest span of configured addresses it covers.
+const AL_STATUS_WINDOW: u16 = 64;
+
+/// A slave device that is not in its group's state, or has flagged an error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SlaveHealth {
+    /// Slave address.
+    pub configured_address: u16,
+    /// Whether the slave responded. The other fields are only meaningful if it did.
+    pub responding: bool,
+    /// The slave's current state.
+    pub state: SlaveState,
+    /// Whether the slave's AL status error flag is set.
+    pub error: bool,
+    /// The reason for the error, read from the slave's AL status code register.
+    pub status_code: AlStatusCode,
+}
+
+impl SlaveHealth {
+    fn from_registers(
+        configured_address: u16,
+        registers: [u8; AL_STATUS_LEN],
+    ) -> Result<Self, Error> {
+        let status = AlControl::unpack_from_slice(&registers[0..2])?;
+
+        Ok(Self {
+            configured_address,
+            responding: true,
+            state: status.state,
+            error: status.error,
+            status_code: AlStatusCode::unpack_from_slice(&registers[4..6])?,
+        })
+    }
+
+    /// Health from a slave's mapped AL status byte, or `None` if the AL status code must be read
+    /// from the slave to complete it.
+    ///
+    /// A byte of zero means the slave did not fill in its byte, e.g. because it did not respond or
+    /// lost its FMMU configuration, as no slave reports a state of zero.
+    fn from_mapped(configured_address: u16, status: u8) -> Result<Option<Self>, Error> {
+        let status = AlControl::unpack_from_slice(&[status, 0])?;
+
+        if status.state == SlaveState::None || status.error {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            configured_address,
+            responding: true,
+            state: status.state,
+            error: false,
+            status_code: AlStatusCode::NoError,
+        }))
+    }
+
+    fn not_responding(configured_address: u16) -> Self {
+        Self {
+            configured_address,
+            responding: false,
+            state: SlaveState::None,
+            error: false,
+            status_code: AlStatusCode::NoError,
+        }
+    }
+}
+
+impl core::fmt::Display for SlaveHealth {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if !self.responding {
+            return write!(f, "slave {:#06x} not responding", self.configured_address);
+        }
+
+        write!(
+            f,
+            "slave {:#06x} in {}",
+            self.configured_address, self.state
+        )?;
+
+        if self.error {
+            write!(f, " with error: {}", self.status_code)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
+{
+    /// Configured addresses of the slave devices in this group that are on the network, skipping
+    /// lost slaves and absent hot connect segments.
+    fn present_addresses(&self) -> Result<heapless::Vec<u16, MAX_SLAVES>, Error> {
+        self.present_slaves(|slave| slave.configured_address)
+    }
+
+    /// Call `f` with each slave device in this group that is on the network, skipping lost slaves
+    /// and absent hot connect segments.
+    pub(crate) fn present_slaves<T>(
+        &self,
+        f: impl Fn(&Slave) -> T,
+    ) -> Result<heapless::Vec<T, MAX_SLAVES>, Error> {
+        let mut slaves = heapless::Vec::new();
+
+        for (index, slave) in self.inner().slaves.iter().enumerate() {
+            let slave = slave.try_borrow().map_err(|_e| {
+                fmt::error!("Slave index {} already borrowed", index);
+
+                Error::Borrow
+            })?;
+
+            if slave.lost || self.is_absent(index) {
+                continue;
+            }
+
+            // Never more items than slaves in the group
+            let _ = slaves.push(f(&slave));
+        }
+
+        Ok(slaves)
+    }
+
+    /// Read the link and frame error counters of every slave device in this group, in group order.
+    ///
+    /// Slaves that have been lost, or are part of a hot connect segment that is not connected,
+    /// are skipped. Returns [`Error::Borrow`] if a slave in the group is already borrowed.
+    ///
+    /// Errors on a damaged cable or connector are counted by the port that receives frames over
+    /// it, then counted as forwarded errors by every following slave. The first port in network
+    /// order with [local errors](crate::PortErrorCounters::local_errors) therefore shows where
+    /// errors start to accumulate.
+    pub async fn error_counters(
+        &self,
+        client: &Client<'_>,
+    ) -> Result<heapless::Vec<ErrorCounters, MAX_SLAVES>, Error> {
+        self.read_error_counters(client, false).await
+    }
+
+    /// Read then clear the link and frame error counters of every slave device in this group.
+    ///
+    /// This behaves like [`error_counters`](SlaveGroup::error_counters), but each slave's counters
+    /// are cleared straight after they are read, so calling this method periodically returns the
+    /// errors since the previous call. Slaves are read then cleared a few at a time, and errors
+    /// counted by a slave between reading and clearing its counters are lost.
+    pub async fn take_error_counters(
+        &self,
+        client: &Client<'_>,
+    ) -> Result<heapless::Vec<ErrorCounters, MAX_SLAVES>, Error> {
+        self.read_error_counters(client, true).await
+    }
+
+    /// Clear the link and frame error counters of every slave device in this group.
+    pub async fn clear_error_counters(&self, client: &Client<'_>) -> Result<(), Error> {
+        for batch in self.present_addresses()?.chunks(BATCH) {
+            clear_batch(client, batch).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Read the sync manager and PDI watchdog status of every slave device in this group, in group
+    /// order, skipping lost and absent slaves.
+    ///
+    /// A slave whose sync manager watchdog has expired has reset its outputs, usually because
+    /// process data was not exchanged within the [configured timeout](crate::Watchdog).
+    pub async fn watchdog_status(
+        &self,
+        client: &Client<'_>,
+    ) -> Result<heapless::Vec<WatchdogStatus, MAX_SLAVES>, Error> {
+        let mut all = heapless::Vec::new();
+
+        for batch in self.present_addresses()?.chunks(BATCH) {
+            let statuses = join_batch(batch, |configured_address| async move {
+                SlaveRef::new(client, configured_address, ())
+                    .watchdog_status()
+                    .await
+            })
+            .await;
+
+            for (configured_address, status) in batch.iter().zip(statuses.into_iter().flatten()) {
+                let status = status?;
+
+                if !status.sync_manager_ok {
+                    fmt::warn!("Slave {:#06x} SM watchdog expired", configured_address);
+                }
+
+                // Never more statuses than slaves in the group
+                let _ = all.push(status);
+            }
+        }
+
+        Ok(all)
+    }
+
+    async fn read_error_counters(
+        &self,
+        client: &Client<'_>,
+        clear: bool,
+    ) -> Result<heapless::Vec<ErrorCounters, MAX_SLAVES>, Error> {
+        let mut all = heapless::Vec::new();
+
+        for batch in self.present_addresses()?.chunks(BATCH) {
+            let counters = join_batch(batch, |configured_address| async move {
+                SlaveRef::new(client, configured_address, ())
+                    .error_counters()
+                    .await
+            })
+            .await;
+
+            for counters in counters.into_iter().flatten() {
+                let counters = counters?;
+
+                if counters.has_errors() {
+                    fmt::debug!(
+                        "Slave {:#06x} error counters {:?}",
+                        counters.configured_address,
+                        counters
+                    );
+                }
+
+                // Never more counters than slaves in the group
+                let _ = all.push(counters);
+            }
+
+            if clear {
+                clear_batch(client, batch).await?;
+            }
+        }
+
+        Ok(all)
+    }
+}
+
+/// Call `f` with each configured address in a batch of up to [`BATCH`] slave devices, running the
+/// returned futures concurrently so their PDUs are sent together. Results are in batch order.
+async fn join_batch<F, Fut, T>(batch: &[u16], f: F) -> [Option<T>; BATCH]
+where
+    F: Fn(u16) -> Fut,
+    Fut: Future<Output = T>,
+{
+    let futures = core::array::from_fn::<_, BATCH, _>(|i| {
+        let future = batch.get(i).copied().map(&f);
+
+        async move {
+            match future {
+                Some(future) => Some(future.await),
+                None => None,
+            }
+        }
+    });
+
+    embassy_futures::join::join_array(futures).await
+}
+
+/// Clear the error counters of a batch of slave devices.
+async fn clear_batch(client: &Client<'_>, batch: &[u16]) -> Result<(), Error> {
+    join_batch(batch, |configured_address| async move {
+        SlaveRef::new(client, configured_address, ())
+            .clear_error_counters()
+            .await
+    })
+    .await
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
+where
+    S: HasPdi,
+{
+    /// Check that every slave device in this group is in the group's state with no error flagged,
+    /// returning the slaves that deviate, in group order.
+    ///
+    /// The AL status of the whole network is first read with a single `BRD`. If every device on
+    /// the network responds in the group's state with no error, the check ends there, so a
+    /// healthy network costs one frame. Because the `BRD` covers all groups, this is not enough
+    /// while other groups are in a different state. The AL status of each slave in the group,
+    /// mapped into logical memory by a spare FMMU when the group moved into SAFE-OP, is then read
+    /// with a single `LRD` per 64 configured addresses. Only slaves without a spare FMMU, slaves
+    /// that did not fill in their mapped status, and slaves with an error flagged are read
+    /// individually, a few at a time.
+    ///
+    /// A slave that does not respond is reported with [`responding`](SlaveHealth::responding) set
+    /// to `false`. Slaves that have been lost, or are part of a hot connect segment that is not
+    /// connected, are skipped; use [`recover`](SlaveGroup::recover) to find them again.
+    ///
+    /// Like [`tx_rx`](SlaveGroup::tx_rx), this method does not change any slave's state.
+    /// Deviating slaves can be returned to the group's state with
+    /// [`recover`](SlaveGroup::recover).
+    pub async fn check_health(
+        &self,
+        client: &Client<'_>,
+    ) -> Result<heapless::Vec<SlaveHealth, MAX_SLAVES>, Error> {
+        match Command::brd(RegisterAddress::AlStatus.into())
+            .wrap(client)
+            .with_wkc(client.num_slaves() as u16)
+            .receive::<AlControl>()
+            .await
+        {
+            Ok(status) if status.state == S::STATE && !status.error => {
+                return Ok(heapless::Vec::new())
+            }
+            // Some device is in another state, or some devices did not respond
+            Ok(_) | Err(Error::WorkingCounter { .. }) => (),
+            Err(e) => return Err(e),
+        }
+
+        let slaves =
+            self.present_slaves(|slave| (slave.configured_address, slave.config.al_status_mapped))?;
+
+        let mapped = read_mapped_al_status::<MAX_SLAVES>(
+            client,
+            slaves
+                .iter()
+                .filter(|(_, mapped)| *mapped)
+                .map(|(configured_address, _)| *configured_address),
+        )
+        .await?;
+
+        // Health of each slave in group order, or `None` if it must be read from the slave.
+        let mut healths = heapless::Vec::<_, MAX_SLAVES>::new();
+        let mut unknown = heapless::Vec::<u16, MAX_SLAVES>::new();
+
+        for &(configured_address, _) in slaves.iter() {
+            let health = match mapped.binary_search_by_key(&configured_address, |(a, _)| *a) {
+                Ok(i) => SlaveHealth::from_mapped(configured_address, mapped[i].1)?,
+                Err(_) => None,
+            };
+
+            // Never more slaves than in the group
+            if health.is_none() {
+                let _ = unknown.push(configured_address);
+            }
+
+            let _ = healths.push(health);
+        }
+
+        let mut read = heapless::Vec::<SlaveHealth, MAX_SLAVES>::new();
+
+        for batch in unknown.chunks(BATCH) {
+            let reads = join_batch(batch, |configured_address| async move {
+                SlaveRef::new(client, configured_address, ())
+                    .read(RegisterAddress::AlStatus)
+                    .receive::<[u8; AL_STATUS_LEN]>()
+                    .await
+            })
+            .await;
+
+            for (&configured_address, registers) in batch.iter().zip(reads.into_iter().flatten()) {
+                let health = match registers {
+                    Ok(registers) => SlaveHealth::from_registers(configured_address, registers)?,
+                    Err(Error::WorkingCounter { .. }) => {
+                        SlaveHealth::not_responding(configured_address)
+                    }
+                    Err(e) => return Err(e),
+                };
+
+                // Never more slaves than in the group
+                let _ = read.push(health);
+            }
+        }
+
+        let mut read = read.into_iter();
+        let mut deviating = heapless::Vec::new();
+
+        for health in healths
+            .into_iter()
+            .filter_map(|health| health.or_else(|| read.next()))
+        {
+            if !health.responding || health.state != S::STATE || health.error {
+                fmt::warn!("Unhealthy {}", health);
+
+                // Never more deviating slaves than slaves in the group
+                let _ = deviating.push(health);
+            }
+        }
+
+        Ok(deviating)
+    }
+}
+
+/// Read the mapped AL status byte of each of the given slave devices with as few `LRD`s as
+/// possible, returning `(configured address, status)` pairs sorted by address.
+async fn read_mapped_al_status<const N: usize>(
+    client: &Client<'_>,
+    addresses: impl Iterator<Item = u16>,
+) -> Result<heapless::Vec<(u16, u8), N>, Error> {
+    let mut addresses = addresses.collect::<heapless::Vec<u16, N>>();
+
+    addresses.sort_unstable();
+
+    let mut statuses = heapless::Vec::new();
+    let mut rest = &addresses[..];
+
+    while let Some(&start) = rest.first() {
+        let (window, after) = rest.split_at(
+            rest.iter()
+                .take_while(|&&address| address - start < AL_STATUS_WINDOW)
+                .count(),
+        );
+
+        let end = window.last().copied().unwrap_or(start);
+
+        // Slaves that don't have their status mapped leave their byte as zero, and slaves of other
+        // groups in the window are ignored, so the working counter is not checked.
+        let data = Command::lrd(AL_STATUS_LOGICAL_START + u32::from(start))
+            .wrap(client)
+            .ignore_wkc()
+            .receive_slice(end - start + 1)
+            .await?;
+
+        for &address in window {
+            // Never more statuses than addresses
+            let _ = statuses.push((address, data[usize::from(address - start)]));
+        }
+
+        rest = after;
+    }
+
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::tests::fake_network,
+        command::{Reads, Writes},
+        slave_group::{PreOp, SafeOp},
+        SlaveGroupHandle,
+    };
+    use core::cell::RefCell;
+    use embassy_futures::select::{select, Either};
+
+    #[test]
+    fn take_error_counters() {
+        let group = SlaveGroup::<4, 16, PreOp>::default();
+
+        let slave = |configured_address, lost| Slave {
+            configured_address,
+            lost,
+            ..Slave::default()
+        };
+
+        unsafe {
+            group.push(slave(0x1000, false)).unwrap();
+            group.push(slave(0x1001, true)).unwrap();
+            group.push(slave(0x1002, false)).unwrap();
+        }
+
+        let sent = RefCell::new(Vec::new());
+
+        let (client, network) = fake_network(|command, data| {
+            sent.borrow_mut().push((command, data.to_vec()));
+
+            // One invalid frame on port 0 of every slave
+            if let Command::Read(Reads::Fprd { .. }) = command {
+                data[0] = 1;
+            }
+
+            1
+        });
+
+        let counters = smol::block_on(async {
+            match select(network, group.take_error_counters(client)).await {
+                Either::First(_) => unreachable!(),
+                Either::Second(counters) => counters,
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            counters
+                .iter()
+                .map(|counters| (
+                    counters.configured_address,
+                    counters.ports[0].local_errors()
+                ))
+                .collect::<Vec<_>>(),
+            [(0x1000, 1), (0x1002, 1)]
+        );
+
+        let register = u16::from(RegisterAddress::RxErrorCounter);
+
+        // Both slaves are read, then both are cleared, skipping the lost slave.
+        assert_eq!(
+            sent.into_inner(),
+            [
+                (
+                    Command::Read(Reads::Fprd {
+                        address: 0x1000,
+                        register
+                    }),
+                    vec![0; 0x14]
+                ),
+                (
+                    Command::Read(Reads::Fprd {
+                        address: 0x1002,
+                        register
+                    }),
+                    vec![0; 0x14]
+                ),
+                (
+                    Command::Write(Writes::Fpwr {
+                        address: 0x1000,
+                        register
+                    }),
+                    vec![0; 0x14]
+                ),
+                (
+                    Command::Write(Writes::Fpwr {
+                        address: 0x1002,
+                        register
+                    }),
+                    vec![0; 0x14]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_health() {
+        // SAFE-OP with error flag, reserved, sync manager watchdog code
+        let health = SlaveHealth::from_registers(0x1002, [0x14, 0x00, 0xff, 0xff, 0x1b, 0x00]);
+
+        assert_eq!(
+            health,
+            Ok(SlaveHealth {
+                configured_address: 0x1002,
+                responding: true,
+                state: SlaveState::SafeOp,
+                error: true,
+                status_code: AlStatusCode::SyncManagerWatchdog,
+            })
+        );
+    }
+
+    #[test]
+    fn check_health() {
+        let group = SlaveGroup::<8, 16, SafeOp>::default();
+
+        let slave = |configured_address, mapped| {
+            let mut slave = Slave {
+                configured_address,
+                ..Slave::default()
+            };
+
+            slave.config.al_status_mapped = mapped;
+
+            slave
+        };
+
+        unsafe {
+            // Healthy
+            group.push(slave(0x1000, true)).unwrap();
+            // In PRE-OP
+            group.push(slave(0x1001, true)).unwrap();
+            // Flagged an error
+            group.push(slave(0x1002, true)).unwrap();
+            // No spare FMMU, not responding
+            group.push(slave(0x1003, false)).unwrap();
+            // Lost its FMMU configuration, but healthy
+            group.push(slave(0x1004, true)).unwrap();
+        }
+
+        let sent = RefCell::new(Vec::new());
+
+        let (client, network) = fake_network(|command, data| {
+            sent.borrow_mut().push(command);
+
+            match command {
+                // Some slaves in the network are in PRE-OP
+                Command::Read(Reads::Brd { .. }) => {
+                    data[0] = 0x02;
+
+                    0
+                }
+                Command::Read(Reads::Lrd { address }) => {
+                    let start = (address - AL_STATUS_LOGICAL_START) as u16;
+
+                    for (configured_address, status) in (start..).zip(data.iter_mut()) {
+                        *status = match configured_address {
+                            0x1000 => 0x04,
+                            0x1001 => 0x02,
+                            0x1002 => 0x14,
+                            _ => 0,
+                        };
+                    }
+
+                    3
+                }
+                Command::Read(Reads::Fprd { address, .. }) => {
+                    let registers = match address {
+                        0x1002 => [0x14, 0x00, 0x00, 0x00, 0x1b, 0x00],
+                        0x1003 => return 0,
+                        _ => [0x04, 0x00, 0x00, 0x00, 0x00, 0x00],
+                    };
+
+                    data.copy_from_slice(&registers);
+
+                    1
+                }
+                _ => 0,
+            }
+        });
+
+        let deviating = smol::block_on(async {
+            match select(network, group.check_health(client)).await {
+                Either::First(_) => unreachable!(),
+                Either::Second(deviating) => deviating,
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            deviating,
+            [
+                SlaveHealth {
+                    configured_address: 0x1001,
+                    responding: true,
+                    state: SlaveState::PreOp,
+                    error: false,
+                    status_code: AlStatusCode::NoError,
+                },
+                SlaveHealth {
+                    configured_address: 0x1002,
+                    responding: true,
+                    state: SlaveState::SafeOp,
+                    error: true,
+                    status_code: AlStatusCode::SyncManagerWatchdog,
+                },
+                SlaveHealth::not_responding(0x1003),
+            ]
+        );
+
+        let register = u16::from(RegisterAddress::AlStatus);
+
+        // Only slaves without a usable mapped status are read individually.
+        assert_eq!(
+            sent.into_inner(),
+            [
+                Command::brd(register).into(),
+                Command::lrd(AL_STATUS_LOGICAL_START + 0x1000).into(),
+                Command::fprd(0x1002, register).into(),
+                Command::fprd(0x1003, register).into(),
+                Command::fprd(0x1004, register).into(),
+            ]
+        );
+    }
+}
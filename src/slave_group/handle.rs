@@ -1,34 +1,102 @@
-use crate::{error::Error, slave_group::SlaveGroupRef, GroupId, Slave, SlaveGroup};
+use crate::{error::Error, fmt, slave_group::SlaveGroupRef, GroupId, Slave, SlaveGroup};
 use atomic_refcell::AtomicRefCell;
+use core::sync::atomic::Ordering;
 
 /// A trait implemented only by [`SlaveGroup`] so multiple groups with different const params can be
 /// stored in a hashmap, `Vec`, etc.
 #[doc(hidden)]
 #[sealed::sealed]
-pub trait SlaveGroupHandle {
+pub trait SlaveGroupHandle: Sync {
     /// Get the group's ID.
     fn id(&self) -> GroupId;
 
     /// Add a slave device to this group.
-    unsafe fn push(&self, slave: Slave) -> Result<(), Error>;
+    ///
+    /// If the slave is the first slave of one of the group's hot connect segments, the number of
+    /// following slaves in the segment is returned. These must be added to the group next.
+    unsafe fn push(&self, slave: Slave) -> Result<usize, Error>;
+
+    /// Add placeholder slave devices for hot connect segments that were not found during init,
+    /// using consecutive configured addresses starting from `next_address`.
+    unsafe fn reserve_hot_connect(&self, next_address: &mut u16) -> Result<(), Error>;
 
     /// Get a reference to the group with const generic params erased.
     fn as_ref(&self) -> SlaveGroupRef<'_>;
 }
 
 #[sealed::sealed]
-impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> SlaveGroupHandle
-    for SlaveGroup<MAX_SLAVES, MAX_PDI, S>
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize> SlaveGroupHandle
+    for SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
 {
     fn id(&self) -> GroupId {
         self.id
     }
 
-    unsafe fn push(&self, slave: Slave) -> Result<(), Error> {
-        (*self.inner.get())
+    unsafe fn push(&self, slave: Slave) -> Result<usize, Error> {
+        let inner = &mut *self.inner.get();
+
+        let index = inner.slaves.len();
+        let alias = slave.alias;
+
+        inner
             .slaves
             .push(AtomicRefCell::new(slave))
-            .map_err(|_| Error::Capacity(crate::error::Item::Slave))
+            .map_err(|_| Error::Capacity(crate::error::Item::Slave))?;
+
+        // Slaves that follow a segment's first slave may share its alias, but are not the start
+        // of another segment.
+        if alias == 0
+            || inner
+                .segments
+                .iter()
+                .any(|segment| segment.slaves().contains(&index))
+        {
+            return Ok(0);
+        }
+
+        let followers = inner
+            .segments
+            .iter_mut()
+            .find(|segment| segment.config.alias == alias && segment.first_slave.is_none())
+            .map(|segment| {
+                segment.first_slave = Some(index);
+                segment.present.store(true, Ordering::Release);
+
+                segment.config.slaves.saturating_sub(1)
+            })
+            .unwrap_or(0);
+
+        Ok(followers)
+    }
+
+    unsafe fn reserve_hot_connect(&self, next_address: &mut u16) -> Result<(), Error> {
+        let inner = &mut *self.inner.get();
+
+        for segment in inner
+            .segments
+            .iter_mut()
+            .filter(|segment| segment.first_slave.is_none())
+        {
+            fmt::debug!(
+                "Hot connect segment {:#06x} not found, reserving {} slave(s) from address {:#06x}",
+                segment.config.alias,
+                segment.config.slaves,
+                *next_address
+            );
+
+            segment.first_slave = Some(inner.slaves.len());
+
+            for _ in 0..segment.config.slaves {
+                inner
+                    .slaves
+                    .push(AtomicRefCell::new(Slave::absent(*next_address)))
+                    .map_err(|_| Error::Capacity(crate::error::Item::Slave))?;
+
+                *next_address = next_address.wrapping_add(1);
+            }
+        }
+
+        Ok(())
     }
 
     fn as_ref(&self) -> SlaveGroupRef<'_> {
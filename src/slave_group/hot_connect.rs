@@ -0,0 +1,549 @@
+use super::{
+    working_counter::{AtomicWkcSlaves, WkcSlaves},
+    HasPdi, SlaveGroup,
+};
+#[cfg(feature = "alloc")]
+use crate::hook;
+use crate::{
+    command::Command,
+    dc, driver,
+    error::Error,
+    fmt,
+    pdi::PdiOffset,
+    register::RegisterAddress,
+    slave::{configuration::PdoDirection, SignalStore, Slave, SlaveRef},
+    slave_group::{recovery::push_event, RecoveryEvent},
+    watchdog::{self, Watchdog},
+    Client, SlaveState, Transition,
+};
+use atomic_refcell::AtomicRefMut;
+use core::{
+    ops::Range,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// The maximum number of hot connect segments that can be declared on one [`SlaveGroup`].
+pub const MAX_HOT_CONNECT_SEGMENTS: usize = 4;
+
+/// A hot connect segment: a chain of slave devices, identified by the station alias of its first
+/// slave, that may be connected to or disconnected from the network at any time.
+///
+/// Segments are declared on the [`SlaveGroup`] that owns them with
+/// [`SlaveGroup::with_hot_connect`]. The group reserves a fixed area of its PDI for each segment,
+/// so the rest of the group's process data does not move when a segment is connected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HotConnect {
+    /// Station alias of the first slave in the segment, e.g. the coupler of a tool.
+    pub alias: u16,
+    /// The number of slaves in the segment, including the first. The segment is made up of the
+    /// slave with the alias followed by the next `slaves - 1` slaves in network order.
+    pub slaves: usize,
+    /// The number of bytes reserved in the group's PDI for the segment's inputs.
+    pub input_len: usize,
+    /// The number of bytes reserved in the group's PDI for the segment's outputs.
+    pub output_len: usize,
+}
+
+impl HotConnect {
+    /// A segment of `slaves` slave devices starting with the slave with the given station alias,
+    /// with no process data.
+    pub const fn new(alias: u16, slaves: usize) -> Self {
+        Self {
+            alias,
+            slaves,
+            input_len: 0,
+            output_len: 0,
+        }
+    }
+
+    /// Reserve `len` bytes of the group's PDI for the segment's inputs.
+    pub const fn with_inputs(self, len: usize) -> Self {
+        Self {
+            input_len: len,
+            ..self
+        }
+    }
+
+    /// Reserve `len` bytes of the group's PDI for the segment's outputs.
+    pub const fn with_outputs(self, len: usize) -> Self {
+        Self {
+            output_len: len,
+            ..self
+        }
+    }
+}
+
+/// A hot connect segment declared on a group, and where its slaves and process data are stored.
+#[derive(Debug)]
+pub(in crate::slave_group) struct HotConnectSegment {
+    pub config: HotConnect,
+    /// Index of the segment's first slave in the group, once its slaves have been added to the
+    /// group during init.
+    pub first_slave: Option<usize>,
+    pub present: AtomicBool,
+    /// Slaves in the segment that increment the working counter, when it was last connected.
+    pub wkc_slaves: AtomicWkcSlaves,
+    /// Start of the segment's reserved input area.
+    pub inputs_start: PdiOffset,
+    /// Start of the segment's reserved output area.
+    pub outputs_start: PdiOffset,
+}
+
+impl HotConnectSegment {
+    pub fn new(config: HotConnect) -> Self {
+        Self {
+            config,
+            first_slave: None,
+            present: AtomicBool::new(false),
+            wkc_slaves: AtomicWkcSlaves::default(),
+            inputs_start: PdiOffset::default(),
+            outputs_start: PdiOffset::default(),
+        }
+    }
+
+    /// Indices of the segment's slaves in the group.
+    pub fn slaves(&self) -> Range<usize> {
+        self.first_slave
+            .map(|first| first..(first + self.config.slaves))
+            .unwrap_or(0..0)
+    }
+
+    pub fn is_present(&self) -> bool {
+        self.present.load(Ordering::Acquire)
+    }
+}
+
+/// Map the process data of a hot connect segment's slaves into the segment's reserved input or
+/// output area, checking it fits.
+pub(in crate::slave_group) async fn configure_segment_fmmus<'slave>(
+    client: &Client<'_>,
+    segment: &HotConnectSegment,
+    slaves: impl Iterator<Item = &'slave mut Slave>,
+    group_start_address: u32,
+    direction: PdoDirection,
+    watchdog: Option<Watchdog>,
+    signals: Option<&dyn SignalStore>,
+) -> Result<(), Error> {
+    let config = segment.config;
+
+    let (start, reserved_len, kind) = match direction {
+        PdoDirection::MasterRead => (segment.inputs_start, config.input_len, "input"),
+        PdoDirection::MasterWrite => (segment.outputs_start, config.output_len, "output"),
+    };
+
+    let mut position = start;
+    let mut wkc_slaves = WkcSlaves::default();
+
+    for slave in slaves {
+        position = SlaveRef::new(client, slave.configured_address, &mut *slave)
+            .with_signals(signals)
+            .configure_fmmus(position, group_start_address, direction)
+            .await?;
+
+        if matches!(direction, PdoDirection::MasterWrite) {
+            // Delays and offsets of segments connected after init are not measured, as that
+            // would disturb the DC system time of every other slave on the network.
+            dc::configure_dc_sync(client, slave).await?;
+            watchdog::configure_watchdog(client, slave, watchdog).await?;
+
+            SlaveRef::new(client, slave.configured_address, &mut *slave)
+                .map_al_status()
+                .await?;
+
+            wkc_slaves.add(slave);
+        }
+    }
+
+    // Both mappings are known once outputs are configured
+    if matches!(direction, PdoDirection::MasterWrite) {
+        segment.wkc_slaves.store(wkc_slaves);
+    }
+
+    let len = (position.start_address - start.start_address) as usize;
+
+    if len > reserved_len {
+        fmt::error!(
+            "Hot connect segment {:#06x} needs {} {} bytes but only {} are reserved",
+            config.alias,
+            len,
+            kind,
+            reserved_len
+        );
+
+        return Err(Error::PdiTooLong {
+            max_length: reserved_len,
+            desired_length: len,
+        });
+    }
+
+    Ok(())
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
+{
+    /// Check whether the hot connect segment with the given station alias is connected.
+    ///
+    /// Returns `None` if no segment with this alias was declared with
+    /// [`with_hot_connect`](SlaveGroup::with_hot_connect).
+    pub fn hot_connect_present(&self, alias: u16) -> Option<bool> {
+        self.inner()
+            .segments
+            .iter()
+            .find(|segment| segment.config.alias == alias)
+            .map(|segment| segment.is_present())
+    }
+
+    /// Whether the slave at the given index in this group is part of a hot connect segment.
+    pub(in crate::slave_group) fn in_segment(&self, index: usize) -> bool {
+        self.inner()
+            .segments
+            .iter()
+            .any(|segment| segment.slaves().contains(&index))
+    }
+
+    /// Whether the slave at the given index in this group is part of a hot connect segment that is
+    /// not connected, so must be skipped when communicating with the group's slaves.
+    pub(in crate::slave_group) fn is_absent(&self, index: usize) -> bool {
+        self.inner()
+            .segments
+            .iter()
+            .any(|segment| !segment.is_present() && segment.slaves().contains(&index))
+    }
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
+where
+    S: HasPdi,
+{
+    /// Check every hot connect segment in this group, connecting absent segments found on the
+    /// network and disconnecting present segments that have stopped responding.
+    pub(in crate::slave_group) async fn recover_hot_connect(
+        &self,
+        client: &Client<'_>,
+        events: &mut heapless::Vec<RecoveryEvent, MAX_SLAVES>,
+    ) -> Result<(), Error> {
+        let segments = &self.inner().segments;
+
+        if segments.is_empty() {
+            return Ok(());
+        }
+
+        // Absent segments can only have been connected if there are more slaves on the network
+        // than expected.
+        let num_devices = client.count_slaves().await?;
+        let new_devices = usize::from(num_devices) > client.num_slaves();
+
+        for segment in segments.iter() {
+            let Ok(mut slaves) = self.borrow_segment(segment) else {
+                fmt::warn!(
+                    "Hot connect segment {:#06x} already borrowed, skipping recovery",
+                    segment.config.alias
+                );
+
+                continue;
+            };
+
+            let head_address = slaves
+                .first()
+                .map(|slave| slave.configured_address)
+                .unwrap_or_default();
+
+            let alias = segment.config.alias;
+
+            if segment.is_present() {
+                self.check_segment(client, segment, &mut slaves, events)
+                    .await?;
+            } else if new_devices {
+                let Some(position) = find_head(client, num_devices, alias, head_address).await?
+                else {
+                    continue;
+                };
+
+                fmt::info!(
+                    "Hot connect segment {:#06x} found at position {}",
+                    alias,
+                    position
+                );
+
+                let event = match self
+                    .connect_segment(client, segment, position, &mut slaves)
+                    .await
+                {
+                    Ok(()) => RecoveryEvent::SegmentConnected { alias },
+                    Err(error) => {
+                        fmt::error!(
+                            "Failed to connect hot connect segment {:#06x}: {}",
+                            alias,
+                            error
+                        );
+
+                        for slave in slaves.iter_mut() {
+                            slave.lost = true;
+                        }
+
+                        RecoveryEvent::Failed {
+                            configured_address: head_address,
+                            error,
+                        }
+                    }
+                };
+
+                push_event(events, event);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Borrow all slaves in a hot connect segment.
+    fn borrow_segment(
+        &self,
+        segment: &HotConnectSegment,
+    ) -> Result<heapless::Vec<AtomicRefMut<'_, Slave>, MAX_SLAVES>, Error> {
+        let mut slaves = heapless::Vec::new();
+
+        for index in segment.slaves() {
+            let slave = self
+                .inner()
+                .slaves
+                .get(index)
+                .ok_or(Error::Internal)?
+                .try_borrow_mut()
+                .map_err(|_e| {
+                    fmt::error!("Slave index {} already borrowed", index);
+
+                    Error::Borrow
+                })?;
+
+            // Segments never have more slaves than the group
+            let _ = slaves.push(slave);
+        }
+
+        Ok(slaves)
+    }
+
+    /// Check the slaves of a connected segment, disconnecting the whole segment if any of them
+    /// have stopped responding.
+    async fn check_segment(
+        &self,
+        client: &Client<'_>,
+        segment: &HotConnectSegment,
+        slaves: &mut [AtomicRefMut<'_, Slave>],
+        events: &mut heapless::Vec<RecoveryEvent, MAX_SLAVES>,
+    ) -> Result<(), Error> {
+        let alias = segment.config.alias;
+
+        for slave in slaves.iter() {
+            match SlaveRef::new(client, slave.configured_address, ())
+                .read(RegisterAddress::AlStatus)
+                .receive::<u16>()
+                .await
+            {
+                Ok(_) => (),
+                Err(Error::WorkingCounter { .. }) => {
+                    fmt::warn!("Hot connect segment {:#06x} disconnected", alias);
+
+                    for slave in slaves.iter_mut() {
+                        slave.lost = true;
+                    }
+
+                    segment.present.store(false, Ordering::Release);
+
+                    client.remove_slaves(slaves.len());
+
+                    push_event(events, RecoveryEvent::SegmentDisconnected { alias });
+
+                    return Ok(());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        for slave in slaves.iter_mut() {
+            self.check_slave(client, slave, events).await;
+        }
+
+        Ok(())
+    }
+
+    /// Find, address and configure the slaves of a hot connect segment whose first slave is at the
+    /// given position in the network, then move them into the group's state.
+    async fn connect_segment(
+        &self,
+        client: &Client<'_>,
+        segment: &HotConnectSegment,
+        head_position: u16,
+        slaves: &mut [AtomicRefMut<'_, Slave>],
+    ) -> Result<(), Error> {
+        let group_start_address = self.inner().pdi_start.start_address;
+
+        for (position, slave) in (head_position..).zip(slaves.iter_mut()) {
+            let configured_address = slave.configured_address;
+
+            let address = Command::aprd(position, RegisterAddress::ConfiguredStationAddress.into())
+                .wrap(client)
+                .receive::<u16>()
+                .await?;
+
+            // The segment's slaves are either new, or were given their address by a previous
+            // attempt to connect the segment.
+            if address != 0 && address != configured_address {
+                fmt::error!(
+                    "Device at position {} already has address {:#06x}",
+                    position,
+                    address
+                );
+
+                return Err(Error::Topology);
+            }
+
+            Command::apwr(position, RegisterAddress::ConfiguredStationAddress.into())
+                .wrap(client)
+                .send(configured_address)
+                .await?;
+
+            // Slaves are read again every time the segment is connected, as a different device
+            // may have been connected, e.g. when a tool is changed.
+            let mut found = Slave::new(client, usize::from(position), configured_address).await?;
+
+            found.lost = false;
+
+            **slave = found;
+        }
+
+        for slave in slaves.iter_mut() {
+            SlaveRef::new(client, slave.configured_address, &mut **slave)
+                .configure_mailboxes()
+                .await?;
+
+            for transition in [Transition::INIT_TO_PRE_OP, Transition::PRE_OP_TO_SAFE_OP] {
+                driver::configure(client, transition, slave, self.signals.store()).await?;
+
+                #[cfg(feature = "alloc")]
+                hook::call_hooks(client, self.hook, transition, slave, self.signals.store())
+                    .await?;
+            }
+        }
+
+        for direction in [PdoDirection::MasterRead, PdoDirection::MasterWrite] {
+            configure_segment_fmmus(
+                client,
+                segment,
+                slaves.iter_mut().map(|slave| &mut **slave),
+                group_start_address,
+                direction,
+                self.watchdog,
+                self.signals.store(),
+            )
+            .await?;
+        }
+
+        for slave in slaves.iter() {
+            let slave = SlaveRef::new(client, slave.configured_address, &**slave);
+
+            slave.move_to_state(SlaveState::SafeOp).await?;
+
+            if S::STATE == SlaveState::Op {
+                slave.move_to_state(SlaveState::Op).await?;
+            }
+        }
+
+        segment.present.store(true, Ordering::Release);
+
+        client.add_slaves(slaves.len());
+
+        Ok(())
+    }
+}
+
+/// Find the position of the first slave of a hot connect segment: a device with the given station
+/// alias that has either no configured address or the address reserved for it.
+async fn find_head(
+    client: &Client<'_>,
+    num_devices: u16,
+    alias: u16,
+    head_address: u16,
+) -> Result<Option<u16>, Error> {
+    for position in 0..num_devices {
+        // Configured station address followed by configured station alias
+        let [address, found_alias] =
+            Command::aprd(position, RegisterAddress::ConfiguredStationAddress.into())
+                .wrap(client)
+                .receive::<[u16; 2]>()
+                .await?;
+
+        if found_alias == alias && (address == 0 || address == head_address) {
+            return Ok(Some(position));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{slave_group::PreOp, SlaveGroupHandle};
+
+    #[test]
+    fn segment_slaves_follow_head() {
+        let group = SlaveGroup::<16, 16, PreOp>::default()
+            .with_hot_connect(&[HotConnect::new(0x10, 3), HotConnect::new(0x20, 2)])
+            .unwrap();
+
+        let slave = |configured_address, alias| Slave {
+            configured_address,
+            alias,
+            ..Slave::default()
+        };
+
+        unsafe {
+            assert_eq!(group.push(slave(0x1000, 0)), Ok(0));
+            assert_eq!(group.push(slave(0x1001, 0x10)), Ok(2));
+            // Followers may have the same alias as the head
+            assert_eq!(group.push(slave(0x1002, 0x10)), Ok(0));
+            assert_eq!(group.push(slave(0x1003, 0)), Ok(0));
+
+            let mut next_address = 0x1004;
+
+            group.reserve_hot_connect(&mut next_address).unwrap();
+
+            assert_eq!(next_address, 0x1006);
+        }
+
+        assert_eq!(group.hot_connect_present(0x10), Some(true));
+        assert_eq!(group.hot_connect_present(0x20), Some(false));
+        assert_eq!(group.hot_connect_present(0x30), None);
+
+        assert_eq!(
+            (0..6)
+                .map(|index| group.in_segment(index))
+                .collect::<Vec<_>>(),
+            [false, true, true, true, true, true]
+        );
+        assert_eq!(
+            (0..6)
+                .map(|index| group.is_absent(index))
+                .collect::<Vec<_>>(),
+            [false, false, false, false, true, true]
+        );
+    }
+
+    #[test]
+    fn too_many_segments() {
+        let segments = [HotConnect::new(0x10, 1); MAX_HOT_CONNECT_SEGMENTS + 1];
+
+        assert!(SlaveGroup::<16, 16, PreOp>::default()
+            .with_hot_connect(&segments[1..])
+            .is_ok());
+        assert_eq!(
+            SlaveGroup::<16, 16, PreOp>::default()
+                .with_hot_connect(&segments)
+                .err(),
+            Some(Error::Capacity(crate::error::Item::HotConnectSegment))
+        );
+    }
+}
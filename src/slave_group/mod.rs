@@ -4,29 +4,55 @@
 //! potentially at different tick rates.
 
 mod configurator;
+mod diagnostics;
 mod group_id;
 mod handle;
+mod hot_connect;
 mod iterator;
+mod recovery;
+mod working_counter;
 
 use crate::{
     command::Command,
-    error::{Error, Item},
+    dc, driver,
+    error::{Error, Item, SlaveStateError, StateTransitionError},
     fmt,
     pdi::PdiOffset,
-    slave::{configuration::PdoDirection, pdi::SlavePdi, IoRanges, Slave, SlaveRef},
+    register::RegisterAddress,
+    slave::{configuration::PdoDirection, pdi::SlavePdi, IoRanges, SignalTable, Slave, SlaveRef},
     timer_factory::timeout,
-    Client, SlaveState,
+    watchdog::{self, Watchdog},
+    AlStatusCode, Client, NetworkTopology, SlaveState, Transition,
 };
+#[cfg(feature = "alloc")]
+use crate::{hook, SlaveHook};
 use atomic_refcell::{AtomicRefCell, AtomicRefMut};
-use core::{cell::UnsafeCell, marker::PhantomData, slice, sync::atomic::AtomicUsize};
+use core::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    slice,
+    sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+};
+use hot_connect::{configure_segment_fmmus, HotConnectSegment};
+use working_counter::WkcSlaves;
 
+pub use self::diagnostics::SlaveHealth;
 pub use self::group_id::GroupId;
 pub use self::handle::SlaveGroupHandle;
+pub use self::hot_connect::{HotConnect, MAX_HOT_CONNECT_SEGMENTS};
 pub use self::iterator::GroupSlaveIterator;
+pub use self::recovery::RecoveryEvent;
 pub use configurator::SlaveGroupRef;
 
 static GROUP_ID: AtomicUsize = AtomicUsize::new(0);
 
+/// A typestate for [`SlaveGroup`] representing a group that has been moved back down to INIT.
+///
+/// Mailboxes are not configured in this state, so the only available operation is moving the group
+/// back into PRE-OP with [`SlaveGroup::into_pre_op`].
+#[derive(Copy, Clone, Debug)]
+pub struct Init;
+
 /// A typestate for [`SlaveGroup`] representing a group that is undergoing initialisation.
 ///
 /// This corresponds to the EtherCAT states INIT and PRE-OP.
@@ -45,13 +71,36 @@ pub struct Op;
 struct GroupInner<const MAX_SLAVES: usize> {
     slaves: heapless::Vec<AtomicRefCell<Slave>, MAX_SLAVES>,
     pdi_start: PdiOffset,
+    /// Hot connect segments. The slaves of segments found during init are stored in network order
+    /// among the other slaves, and those of absent segments are stored at the end of `slaves`.
+    segments: heapless::Vec<HotConnectSegment, MAX_HOT_CONNECT_SEGMENTS>,
 }
 
 /// A group of one or more EtherCAT slaves.
 ///
 /// Groups are created during EtherCrab initialisation, and are the only way to access individual
 /// slave PDI sections.
-pub struct SlaveGroup<const MAX_SLAVES: usize, const MAX_PDI: usize, S = PreOp> {
+///
+/// The PDO entries mapped into each slave's process data are recorded in a signal table of up to
+/// `MAX_SIGNALS` entries when the group transitions into SAFE-OP, and can then be found with
+/// [`SlaveRef::signal`] or [`SlaveRef::signal_by_name`]. The table is disabled if `MAX_SIGNALS`
+/// is `0`.
+///
+/// # Examples
+///
+/// A group of up to 16 slaves with 128 bytes of process data and 256 mapped PDO entries:
+///
+/// ```rust
+/// use ethercrab::{slave_group::PreOp, SlaveGroup};
+///
+/// let group = SlaveGroup::<16, 128, PreOp, 256>::default();
+/// ```
+pub struct SlaveGroup<
+    const MAX_SLAVES: usize,
+    const MAX_PDI: usize,
+    S = PreOp,
+    const MAX_SIGNALS: usize = 0,
+> {
     id: GroupId,
     pdi: UnsafeCell<[u8; MAX_PDI]>,
     /// The number of bytes at the beginning of the PDI reserved for slave inputs.
@@ -59,13 +108,27 @@ pub struct SlaveGroup<const MAX_SLAVES: usize, const MAX_PDI: usize, S = PreOp>
     /// The total length (I and O) of the PDI for this group.
     pdi_len: usize,
     inner: UnsafeCell<GroupInner<MAX_SLAVES>>,
+    /// Optional configuration hook called for each slave in this group during state transitions.
+    #[cfg(feature = "alloc")]
+    hook: Option<&'static dyn SlaveHook>,
+    /// Watchdog configuration for slaves in this group that don't override it.
+    watchdog: Option<Watchdog>,
+    /// Slaves outside hot connect segments that increment the working counter.
+    wkc_slaves: WkcSlaves,
+    /// Consecutive cycles with an unexpected working counter.
+    bad_wkc_cycles: AtomicU32,
+    /// PDO entries mapped into the process data of slaves in this group.
+    signals: SignalTable<MAX_SIGNALS>,
     _state: PhantomData<S>,
 }
 
-impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp> {
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp, MAX_SIGNALS>
+{
     /// Configure read/write FMMUs and PDI for this group.
     async fn configure_fmmus(&mut self, client: &Client<'_>) -> Result<(), Error> {
         let inner = self.inner.get_mut();
+        let signals = self.signals.store();
 
         let mut pdi_position = inner.pdi_start;
 
@@ -75,10 +138,25 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroup<MAX_SLAVES, MAX_P
             inner.pdi_start.start_address
         );
 
+        // Hot connect segments are mapped after all other slaves into their reserved areas, which
+        // are kept whether the segment is present or not.
+        let in_segment = |segments: &[HotConnectSegment], index: usize| {
+            segments
+                .iter()
+                .any(|segment| segment.slaves().contains(&index))
+        };
+
         // Configure master read PDI mappings in the first section of the PDI
-        for slave in inner.slaves.iter_mut().map(|slave| slave.get_mut()) {
+        for (index, slave) in inner.slaves.iter_mut().enumerate() {
+            if in_segment(&inner.segments, index) {
+                continue;
+            }
+
+            let slave = slave.get_mut();
+
             // We're in PRE-OP at this point
             pdi_position = SlaveRef::new(client, slave.configured_address, slave)
+                .with_signals(signals)
                 .configure_fmmus(
                     pdi_position,
                     inner.pdi_start.start_address,
@@ -87,6 +165,27 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroup<MAX_SLAVES, MAX_P
                 .await?;
         }
 
+        for segment in inner.segments.iter_mut() {
+            segment.inputs_start = pdi_position;
+
+            pdi_position = pdi_position.increment(segment.config.input_len as u16);
+        }
+
+        for segment in inner.segments.iter().filter(|segment| segment.is_present()) {
+            configure_segment_fmmus(
+                client,
+                segment,
+                inner.slaves[segment.slaves()]
+                    .iter_mut()
+                    .map(|slave| slave.get_mut()),
+                inner.pdi_start.start_address,
+                PdoDirection::MasterRead,
+                self.watchdog,
+                signals,
+            )
+            .await?;
+        }
+
         self.read_pdi_len = (pdi_position.start_address - inner.pdi_start.start_address) as usize;
 
         fmt::debug!("Slave mailboxes configured and init hooks called");
@@ -94,10 +193,18 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroup<MAX_SLAVES, MAX_P
         // We configured all read PDI mappings as a contiguous block in the previous loop. Now we'll
         // configure the write mappings in a separate loop. This means we have IIIIOOOO instead of
         // IOIOIO.
-        for slave in inner.slaves.iter_mut().map(|slave| slave.get_mut()) {
+        let mut wkc_slaves = WkcSlaves::default();
+
+        for (index, slave) in inner.slaves.iter_mut().enumerate() {
+            if in_segment(&inner.segments, index) {
+                continue;
+            }
+
+            let slave = slave.get_mut();
+
             let addr = slave.configured_address;
 
-            let mut slave_config = SlaveRef::new(client, addr, slave);
+            let mut slave_config = SlaveRef::new(client, addr, &mut *slave).with_signals(signals);
 
             // Still in PRE-OP
             pdi_position = slave_config
@@ -108,54 +215,37 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroup<MAX_SLAVES, MAX_P
                 )
                 .await?;
 
-            // TODO: DC active sync/events/SYNC0/etc
-            // // FIXME: Just first slave or all slaves?
-            // // if name == "EL2004" {
-            // // if i == 0 {
-            // if false {
-            //     log::info!("Slave {:#06x} {} DC", addr, name);
-            //     // let slave_config = SlaveRef::new(client, slave.configured_address, ());
-
-            //     // TODO: Pass in as config
-            //     let cycle_time = Duration::from_millis(2).as_nanos() as u32;
-
-            //     // Disable sync signals
-            //     slave_config
-            //         .write(RegisterAddress::DcSyncActive, 0x00u8, "disable sync")
-            //         .await?;
-
-            //     let local_time: u32 = slave_config
-            //         .read(RegisterAddress::DcSystemTime, "local time")
-            //         .await?;
-
-            //     // TODO: Pass in as config
-            //     // let startup_delay = Duration::from_millis(100).as_nanos() as u32;
-            //     let startup_delay = 0;
-
-            //     // TODO: Pass in as config
-            //     let start_time = local_time + cycle_time + startup_delay;
-
-            //     slave_config
-            //         .write(
-            //             RegisterAddress::DcSyncStartTime,
-            //             start_time,
-            //             "sync start time",
-            //         )
-            //         .await?;
-
-            //     slave_config
-            //         .write(
-            //             RegisterAddress::DcSync0CycleTime,
-            //             cycle_time,
-            //             "sync cycle time",
-            //         )
-            //         .await?;
-
-            //     // Enable cyclic operation (0th bit) and sync0 signal (1st bit)
-            //     slave_config
-            //         .write(RegisterAddress::DcSyncActive, 0b11u8, "enable sync0")
-            //         .await?;
-            // }
+            dc::configure_dc_sync(client, slave).await?;
+            watchdog::configure_watchdog(client, slave, self.watchdog).await?;
+
+            SlaveRef::new(client, addr, &mut *slave)
+                .map_al_status()
+                .await?;
+
+            wkc_slaves.add(slave);
+        }
+
+        self.wkc_slaves = wkc_slaves;
+
+        for segment in inner.segments.iter_mut() {
+            segment.outputs_start = pdi_position;
+
+            pdi_position = pdi_position.increment(segment.config.output_len as u16);
+        }
+
+        for segment in inner.segments.iter().filter(|segment| segment.is_present()) {
+            configure_segment_fmmus(
+                client,
+                segment,
+                inner.slaves[segment.slaves()]
+                    .iter_mut()
+                    .map(|slave| slave.get_mut()),
+                inner.pdi_start.start_address,
+                PdoDirection::MasterWrite,
+                self.watchdog,
+                signals,
+            )
+            .await?;
         }
 
         fmt::debug!("Slave FMMUs configured for group. Able to move to SAFE-OP");
@@ -179,6 +269,105 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroup<MAX_SLAVES, MAX_P
         Ok(())
     }
 
+    /// Call the given hook for every slave device in this group as it transitions from INIT to
+    /// PRE-OP and from PRE-OP to SAFE-OP.
+    ///
+    /// The hook must be registered before the group is passed to [`Client::init`], e.g. in a
+    /// manual `Default` implementation of the groups container. See [`SlaveHook`] for details.
+    #[cfg(feature = "alloc")]
+    pub fn with_hook(self, hook: &'static dyn SlaveHook) -> Self {
+        Self {
+            hook: Some(hook),
+            ..self
+        }
+    }
+
+    /// Configure the sync manager and PDI watchdogs of every slave device in this group, unless
+    /// overridden per slave with [`SlaveRef::set_watchdog`].
+    ///
+    /// The configuration is written to each slave when the group transitions from PRE-OP to
+    /// SAFE-OP, or when a slave is reconfigured after recovery or hot connection. If no
+    /// configuration is given, slaves keep their default watchdog timeouts.
+    ///
+    /// # Examples
+    ///
+    /// Disable the sync manager watchdog while debugging:
+    ///
+    /// ```rust
+    /// use ethercrab::{SlaveGroup, Watchdog};
+    /// use core::time::Duration;
+    ///
+    /// let group = SlaveGroup::<8, 64>::default()
+    ///     .with_watchdog(Watchdog::default().with_sync_manager(Duration::ZERO));
+    /// ```
+    pub fn with_watchdog(self, watchdog: Watchdog) -> Self {
+        Self {
+            watchdog: Some(watchdog),
+            ..self
+        }
+    }
+
+    /// Declare hot connect segments owned by this group, which may be absent during init and may be
+    /// connected or disconnected at any time.
+    ///
+    /// Like [`with_hook`](SlaveGroup::with_hook), segments must be declared before the group is
+    /// passed to [`Client::init`], e.g. in a manual `Default` implementation of the groups
+    /// container. During init, the group filter must assign the first slave of each present
+    /// segment, found by its station alias, to this group. The rest of the segment's slaves are
+    /// then added to the group without calling the filter. Slaves of absent segments are added to
+    /// the end of the group with a reserved configured address, an empty name and a default
+    /// identity, and are skipped by all group operations until the segment is connected.
+    ///
+    /// Each segment's inputs and outputs are mapped into reserved areas after those of all other
+    /// slaves in the group. Segments are connected and disconnected by
+    /// [`SlaveGroup::recover`], and [`hot_connect_present`](SlaveGroup::hot_connect_present)
+    /// shows whether a segment is connected.
+    ///
+    /// Distributed clock propagation delays and system time offsets are only measured during
+    /// init, as measuring them latches receive times on every slave in the network. Slaves of a
+    /// segment connected after init have their SYNC outputs configured, but their delay and offset
+    /// are left at zero, so their system time is not aligned with the rest of the network. A
+    /// segment that relies on synchronised distributed clocks should be present during init.
+    ///
+    /// A group is only configured during init if the group filter assigns at least one slave to
+    /// it, so a group must not be made up of only segments that may be absent.
+    ///
+    /// Returns [`Error::Capacity`] if there are more than [`MAX_HOT_CONNECT_SEGMENTS`] segments.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ethercrab::{HotConnect, SlaveGroup};
+    ///
+    /// /// A tool changer whose tools have an EK1100 coupler with alias `0x10` and two terminals.
+    /// const TOOL: HotConnect = HotConnect::new(0x10, 3).with_inputs(4).with_outputs(2);
+    ///
+    /// struct Groups {
+    ///     robot: SlaveGroup<16, 64>,
+    /// }
+    ///
+    /// impl Default for Groups {
+    ///     fn default() -> Self {
+    ///         Self {
+    ///             robot: SlaveGroup::default()
+    ///                 .with_hot_connect(&[TOOL])
+    ///                 .expect("too many segments"),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn with_hot_connect(mut self, segments: &[HotConnect]) -> Result<Self, Error> {
+        for segment in segments {
+            self.inner
+                .get_mut()
+                .segments
+                .push(HotConnectSegment::new(*segment))
+                .map_err(|_| Error::Capacity(Item::HotConnectSegment))?;
+        }
+
+        Ok(self)
+    }
+
     /// Get an iterator over all slaves in this group.
     pub fn iter<'group, 'client>(
         &'group mut self,
@@ -194,7 +383,7 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroup<MAX_SLAVES, MAX_P
     pub async fn into_op(
         self,
         client: &Client<'_>,
-    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Op>, Error> {
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Op, MAX_SIGNALS>, Error> {
         let self_ = self.into_safe_op(client).await?;
 
         self_.into_op(client).await
@@ -204,44 +393,176 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroup<MAX_SLAVES, MAX_P
     pub async fn into_safe_op(
         mut self,
         client: &Client<'_>,
-    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp>, Error> {
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp, MAX_SIGNALS>, Error> {
+        for (index, slave) in self.inner().slaves.iter().enumerate() {
+            if self.is_absent(index) {
+                continue;
+            }
+
+            let mut slave = slave.try_borrow_mut().map_err(|_| Error::Borrow)?;
+            let slave = &mut *slave;
+
+            driver::configure(
+                client,
+                Transition::PRE_OP_TO_SAFE_OP,
+                slave,
+                self.signals.store(),
+            )
+            .await?;
+
+            #[cfg(feature = "alloc")]
+            hook::call_hooks(
+                client,
+                self.hook,
+                Transition::PRE_OP_TO_SAFE_OP,
+                slave,
+                self.signals.store(),
+            )
+            .await?;
+        }
+
         self.configure_fmmus(client).await?;
 
         // We're done configuring FMMUs, etc, now we can request all slaves in this group go into
         // SAFE-OP
-        self.transition_to(client, SlaveState::SafeOp).await
+        self.transition_to(client, Transition::PRE_OP_TO_SAFE_OP)
+            .await
+    }
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp, MAX_SIGNALS>
+{
+    /// Transition all slave devices in the group from PRE-OP to INIT.
+    ///
+    /// Slave mailboxes are no longer available in INIT.
+    pub async fn into_init(
+        self,
+        client: &Client<'_>,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Init, MAX_SIGNALS>, Error> {
+        self.transition_to(client, Transition::PRE_OP_TO_INIT).await
+    }
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, Init, MAX_SIGNALS>
+{
+    /// Transition all slave devices in the group from INIT back to PRE-OP.
+    ///
+    /// Mailboxes are configured again, and any [`SlaveDriver`](crate::SlaveDriver) startup
+    /// configuration and hooks for the INIT to PRE-OP transition are run as they are during
+    /// [`Client::init`].
+    pub async fn into_pre_op(
+        self,
+        client: &Client<'_>,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp, MAX_SIGNALS>, Error> {
+        let pdi_start = self.inner().pdi_start;
+
+        SlaveGroupRef::new(&self)
+            .into_pre_op(pdi_start, client)
+            .await?;
+
+        self.wait_for_state(client, Transition::INIT_TO_PRE_OP)
+            .await?;
+
+        Ok(self.into_typestate())
     }
 }
 
-impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp> {
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp, MAX_SIGNALS>
+{
     /// Transition all slave devices in the group from SAFE-OP to OP.
     pub async fn into_op(
         self,
         client: &Client<'_>,
-    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Op>, Error> {
-        self.transition_to(client, SlaveState::Op).await
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Op, MAX_SIGNALS>, Error> {
+        self.transition_to(client, Transition::SAFE_OP_TO_OP).await
+    }
+
+    /// Transition all slave devices in the group from SAFE-OP back to PRE-OP.
+    ///
+    /// The group's process data mapping is removed, and is configured again when the group is next
+    /// moved into SAFE-OP. This allows PDO assignments to be changed, e.g. with
+    /// [`SlaveRef::configure_pdos`](crate::SlaveRef::configure_pdos).
+    pub async fn into_pre_op(
+        self,
+        client: &Client<'_>,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp, MAX_SIGNALS>, Error> {
+        let mut self_ = self
+            .transition_to::<PreOp>(client, Transition::SAFE_OP_TO_PRE_OP)
+            .await?;
+
+        self_.clear_fmmus(client).await?;
+
+        Ok(self_)
+    }
+
+    /// Transition all slave devices in the group from SAFE-OP down to INIT.
+    pub async fn into_init(
+        self,
+        client: &Client<'_>,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Init, MAX_SIGNALS>, Error> {
+        self.into_pre_op(client).await?.into_init(client).await
     }
 }
 
-unsafe impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> Sync
-    for SlaveGroup<MAX_SLAVES, MAX_PDI, S>
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, Op, MAX_SIGNALS>
 {
+    /// Transition all slave devices in the group from OP back to SAFE-OP.
+    ///
+    /// Slave devices put their outputs into a safe state in SAFE-OP, but continue to update their
+    /// inputs.
+    pub async fn into_safe_op(
+        self,
+        client: &Client<'_>,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp, MAX_SIGNALS>, Error> {
+        self.transition_to(client, Transition::OP_TO_SAFE_OP).await
+    }
+
+    /// Transition all slave devices in the group from OP down to INIT, e.g. when stopping the
+    /// application.
+    ///
+    /// All outputs in the group's PDI are zeroed and sent to the slave devices before the group
+    /// leaves OP.
+    pub async fn into_init(
+        self,
+        client: &Client<'_>,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Init, MAX_SIGNALS>, Error> {
+        self.pdi_mut()[self.read_pdi_len..].fill(0);
+
+        self.tx_rx(client).await?;
+
+        self.into_safe_op(client).await?.into_init(client).await
+    }
 }
-unsafe impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> Send
-    for SlaveGroup<MAX_SLAVES, MAX_PDI, S>
+
+unsafe impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize> Sync
+    for SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
+{
+}
+unsafe impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize> Send
+    for SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
 {
 }
 
-impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> Default
-    for SlaveGroup<MAX_SLAVES, MAX_PDI, S>
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize> Default
+    for SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
 {
     fn default() -> Self {
         Self {
-            id: GroupId(GROUP_ID.fetch_add(1, core::sync::atomic::Ordering::Relaxed)),
+            id: GroupId(GROUP_ID.fetch_add(1, Ordering::Relaxed)),
             pdi: UnsafeCell::new([0u8; MAX_PDI]),
             read_pdi_len: Default::default(),
             pdi_len: Default::default(),
             inner: UnsafeCell::new(GroupInner::default()),
+            #[cfg(feature = "alloc")]
+            hook: None,
+            watchdog: None,
+            wkc_slaves: WkcSlaves::default(),
+            bad_wkc_cycles: AtomicU32::new(0),
+            signals: SignalTable::default(),
             _state: PhantomData,
         }
     }
@@ -250,11 +571,63 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> Default
 /// Returned when a slave device's input or output PDI segment is empty.
 static EMPTY_PDI_SLICE: &[u8] = &[];
 
-impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> SlaveGroup<MAX_SLAVES, MAX_PDI, S> {
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
+{
     fn inner(&self) -> &GroupInner<MAX_SLAVES> {
         unsafe { &*self.inner.get() }
     }
 
+    /// Add all slave devices in this group to a topology, skipping absent hot connect slaves.
+    pub(crate) fn add_to_topology<const N: usize>(
+        &self,
+        topology: &mut NetworkTopology<N>,
+    ) -> Result<(), Error> {
+        for (index, slave) in self.inner().slaves.iter().enumerate() {
+            if self.is_absent(index) {
+                continue;
+            }
+
+            let slave = slave.try_borrow().map_err(|_e| {
+                fmt::error!("Slave index {} already borrowed", index);
+
+                Error::Borrow
+            })?;
+
+            topology.push(&slave)?;
+        }
+
+        Ok(())
+    }
+
+    /// Find the index of the slave device in this group with the given configured station alias.
+    fn alias_index(&self, alias: u16) -> Result<usize, Error> {
+        let not_found = Error::NotFound {
+            item: Item::Slave,
+            index: None,
+        };
+
+        if alias == 0 {
+            return Err(not_found);
+        }
+
+        for (index, slave) in self.inner().slaves.iter().enumerate() {
+            let slave = slave.try_borrow().map_err(|_e| {
+                fmt::error!("Slave index {} already borrowed", index);
+
+                Error::Borrow
+            })?;
+
+            if slave.alias == alias {
+                return Ok(index);
+            }
+        }
+
+        fmt::error!("No slave with alias {:#06x} in group", alias);
+
+        Err(not_found)
+    }
+
     /// Get the number of slave devices in this group.
     pub fn len(&self) -> usize {
         self.inner().slaves.len()
@@ -278,52 +651,118 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> SlaveGroup<MAX_SLAVES, MA
         &all_buf[0..self.pdi_len]
     }
 
-    /// Wait for all slaves in this group to transition to the given state.
+    /// Wait for all slaves in this group to complete the given state transition.
+    ///
+    /// The timeout is the longest of all slaves' timeouts for the transition, as slaves may override
+    /// the client's defaults. If the transition times out, [`Error::StateTransition`] is returned
+    /// and every slave that did not reach the desired state is recorded with
+    /// [`Client::record_state_error`].
     async fn wait_for_state(
         &self,
         client: &Client<'_>,
-        desired_state: SlaveState,
+        transition: Transition,
     ) -> Result<(), Error> {
-        timeout(client.timeouts.state_transition, async {
+        let desired_state = transition.to;
+
+        let transition_timeout = self
+            .inner()
+            .slaves
+            .iter()
+            .map(|slave| {
+                slave
+                    .borrow()
+                    .config
+                    .state_timeouts
+                    .unwrap_or(client.timeouts.state_transitions)
+                    .transition(transition)
+            })
+            .max()
+            .unwrap_or_else(|| client.timeouts.state_transitions.transition(transition));
+
+        // Slaves that have not reached the desired state yet, as of the last check.
+        let mut pending = heapless::Vec::<SlaveStateError, MAX_SLAVES>::new();
+
+        let result = timeout(transition_timeout, async {
             loop {
-                let mut all_transitioned = true;
+                let mut failures = None;
 
-                for slave in self.inner().slaves.iter().map(|slave| slave.borrow()) {
-                    // TODO: Add a way to queue up a bunch of PDUs and send all at once
-                    let slave_state = SlaveRef::new(client, slave.configured_address, slave)
-                        .state()
-                        .await?;
+                pending.clear();
 
-                    if slave_state != desired_state {
-                        all_transitioned = false;
+                for (index, slave) in self.inner().slaves.iter().enumerate() {
+                    if self.is_absent(index) {
+                        continue;
+                    }
+
+                    let slave = slave.borrow();
+                    let slave = SlaveRef::new(client, slave.configured_address, slave);
+
+                    // TODO: Add a way to queue up a bunch of PDUs and send all at once
+                    match slave.checked_state(desired_state).await {
+                        Ok(state) if state == desired_state => (),
+                        Ok(state) => {
+                            // A group holds at most `MAX_SLAVES` slaves, so this can't overflow.
+                            let _ = pending.push(SlaveStateError {
+                                configured_address: slave.configured_address(),
+                                current_state: state,
+                                requested_state: desired_state,
+                                status_code: AlStatusCode::NoError,
+                            });
+                        }
+                        Err(Error::StateTransition(e)) => {
+                            StateTransitionError::merge(&mut failures, e)
+                        }
+                        Err(e) => return Err(e),
                     }
                 }
 
-                if all_transitioned {
+                if let Some(failures) = failures {
+                    break Err(Error::StateTransition(failures));
+                }
+
+                if pending.is_empty() {
                     break Ok(());
                 }
 
                 client.timeouts.loop_tick().await;
             }
         })
-        .await
+        .await;
+
+        match result {
+            Err(Error::Timeout) if !pending.is_empty() => {
+                let mut failures = None;
+
+                for failure in pending {
+                    StateTransitionError::merge(&mut failures, client.record_state_error(failure));
+                }
+
+                Err(failures.map_or(Error::Timeout, Error::StateTransition))
+            }
+            result => result,
+        }
     }
 
     /// Transition to a new state.
     async fn transition_to<TO>(
-        mut self,
+        self,
         client: &Client<'_>,
-        desired_state: SlaveState,
-    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, TO>, Error> {
+        transition: Transition,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, TO, MAX_SIGNALS>, Error> {
+        let desired_state = transition.to;
+
         // We're done configuring FMMUs, etc, now we can request all slaves in this group go into
         // SAFE-OP
-        for slave in self
-            .inner
-            .get_mut()
-            .slaves
-            .iter_mut()
-            .map(|slave| slave.get_mut())
-        {
+        for (index, slave) in self.inner().slaves.iter().enumerate() {
+            if self.is_absent(index) {
+                continue;
+            }
+
+            let slave = slave.borrow();
+
+            // The whole group changes state, so slaves are no longer held in a state requested by
+            // the application.
+            slave.requested_state.store(0, Ordering::Relaxed);
+
             SlaveRef::new(client, slave.configured_address, slave)
                 .request_slave_state_nowait(desired_state)
                 .await?;
@@ -331,18 +770,56 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> SlaveGroup<MAX_SLAVES, MA
 
         fmt::debug!("Waiting for group state {}", desired_state);
 
-        self.wait_for_state(client, desired_state).await?;
+        self.wait_for_state(client, transition).await?;
 
         fmt::debug!("--> Group reached state {}", desired_state);
 
-        Ok(SlaveGroup {
+        Ok(self.into_typestate())
+    }
+
+    /// Change the typestate of this group without communicating with any slave devices.
+    fn into_typestate<TO>(self) -> SlaveGroup<MAX_SLAVES, MAX_PDI, TO, MAX_SIGNALS> {
+        SlaveGroup {
             id: self.id,
             pdi: self.pdi,
             read_pdi_len: self.read_pdi_len,
             pdi_len: self.pdi_len,
             inner: UnsafeCell::new(self.inner.into_inner()),
+            #[cfg(feature = "alloc")]
+            hook: self.hook,
+            watchdog: self.watchdog,
+            wkc_slaves: self.wkc_slaves,
+            bad_wkc_cycles: AtomicU32::new(0),
+            signals: self.signals,
             _state: PhantomData,
-        })
+        }
+    }
+
+    /// Remove all FMMU mappings and process data ranges from the slave devices in this group so the
+    /// PDI can be configured again.
+    async fn clear_fmmus(&mut self, client: &Client<'_>) -> Result<(), Error> {
+        for (index, slave) in self.inner().slaves.iter().enumerate() {
+            if self.is_absent(index) {
+                continue;
+            }
+
+            let mut slave = slave.try_borrow_mut().map_err(|_| Error::Borrow)?;
+            let slave = &mut *slave;
+
+            // FMMU memory section is 0xff (255) bytes long - see ETG1000.4 Table 57
+            SlaveRef::new(client, slave.configured_address, &mut *slave)
+                .write(RegisterAddress::Fmmu0)
+                .send(&[0u8; 0xff][..])
+                .await?;
+
+            slave.config.io = IoRanges::default();
+            slave.config.al_status_mapped = false;
+        }
+
+        self.read_pdi_len = 0;
+        self.pdi_len = 0;
+
+        Ok(())
     }
 }
 
@@ -371,7 +848,22 @@ pub trait SlaveGroupState {
         &'group self,
         client: &'client Client<'client>,
         index: usize,
-    ) -> Result<SlaveRef<'client, Self::RefType<'group>>, Error>;
+    ) -> Result<SlaveRef<'client, Self::RefType<'group>>, Error>
+    where
+        'group: 'client;
+
+    /// Borrow the slave device with the given configured station alias.
+    ///
+    /// Borrowing works the same as [`slave`](SlaveGroupState::slave). Returns [`Error::NotFound`]
+    /// if no slave device in the group has the alias, or if `alias` is `0`. As every slave's alias
+    /// is checked, [`Error::Borrow`] is returned if any slave in the group is already borrowed.
+    fn slave_by_alias<'client, 'group>(
+        &'group self,
+        client: &'client Client<'client>,
+        alias: u16,
+    ) -> Result<SlaveRef<'client, Self::RefType<'group>>, Error>
+    where
+        'group: 'client;
 
     /// Returns `true` if there are no slave devices in the group.
     fn is_empty(&self) -> bool {
@@ -383,8 +875,8 @@ pub trait SlaveGroupState {
 }
 
 #[sealed::sealed]
-impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroupState
-    for SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp>
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, const MAX_SIGNALS: usize> SlaveGroupState
+    for SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp, MAX_SIGNALS>
 {
     type RefType<'group> = AtomicRefMut<'group, Slave>;
 
@@ -392,7 +884,10 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroupState
         &'group self,
         client: &'client Client<'client>,
         index: usize,
-    ) -> Result<SlaveRef<'client, Self::RefType<'group>>, Error> {
+    ) -> Result<SlaveRef<'client, Self::RefType<'group>>, Error>
+    where
+        'group: 'client,
+    {
         let slave = self
             .inner()
             .slaves
@@ -408,7 +903,19 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroupState
                 Error::Borrow
             })?;
 
-        Ok(SlaveRef::new(client, slave.configured_address, slave))
+        Ok(SlaveRef::new(client, slave.configured_address, slave)
+            .with_signals(self.signals.store()))
+    }
+
+    fn slave_by_alias<'client, 'group>(
+        &'group self,
+        client: &'client Client<'client>,
+        alias: u16,
+    ) -> Result<SlaveRef<'client, Self::RefType<'group>>, Error>
+    where
+        'group: 'client,
+    {
+        self.slave(client, self.alias_index(alias)?)
     }
 
     fn len(&self) -> usize {
@@ -417,14 +924,21 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SlaveGroupState
 }
 
 #[doc(hidden)]
-pub trait HasPdi {}
+pub trait HasPdi {
+    /// The EtherCAT state of a group in this typestate.
+    const STATE: SlaveState;
+}
 
-impl HasPdi for SafeOp {}
-impl HasPdi for Op {}
+impl HasPdi for SafeOp {
+    const STATE: SlaveState = SlaveState::SafeOp;
+}
+impl HasPdi for Op {
+    const STATE: SlaveState = SlaveState::Op;
+}
 
 #[sealed::sealed]
-impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> SlaveGroupState
-    for SlaveGroup<MAX_SLAVES, MAX_PDI, S>
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize> SlaveGroupState
+    for SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
 where
     S: HasPdi,
 {
@@ -434,7 +948,10 @@ where
         &'group self,
         client: &'client Client<'client>,
         index: usize,
-    ) -> Result<SlaveRef<'client, Self::RefType<'group>>, Error> {
+    ) -> Result<SlaveRef<'client, Self::RefType<'group>>, Error>
+    where
+        'group: 'client,
+    {
         let slave = self
             .inner()
             .slaves
@@ -495,7 +1012,19 @@ where
             // enforced by `AtomicRefCell`). If it is borrowed more than once, immutable APIs in
             // `SlaveRef<SlavePdi>` will be unsound.
             SlavePdi::new(slave, inputs, outputs),
-        ))
+        )
+        .with_signals(self.signals.store()))
+    }
+
+    fn slave_by_alias<'client, 'group>(
+        &'group self,
+        client: &'client Client<'client>,
+        alias: u16,
+    ) -> Result<SlaveRef<'client, Self::RefType<'group>>, Error>
+    where
+        'group: 'client,
+    {
+        self.slave(client, self.alias_index(alias)?)
     }
 
     fn len(&self) -> usize {
@@ -504,7 +1033,8 @@ where
 }
 
 // Methods for any state where a PDI has been configured.
-impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> SlaveGroup<MAX_SLAVES, MAX_PDI, S>
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
 where
     S: HasPdi,
 {
@@ -516,12 +1046,43 @@ where
         GroupSlaveIterator::new(client, self)
     }
 
+    /// Run the [`cycle`](crate::SlaveDriver::cycle) logic of every slave device in the group that
+    /// has a matching [`SlaveDriver`](crate::SlaveDriver).
+    ///
+    /// This is typically called once per cycle after [`tx_rx`](SlaveGroup::tx_rx).
+    pub fn run_drivers(&self, client: &Client<'_>) -> Result<(), Error> {
+        if client.drivers.is_empty() {
+            return Ok(());
+        }
+
+        for index in 0..self.len() {
+            let mut slave = self.slave(client, index)?;
+
+            if let Some(driver) = driver::find(client, &slave.identity()) {
+                driver.cycle(&mut slave)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Drive the slave group's inputs and outputs.
     ///
     /// A `SlaveGroup` will not process any inputs or outputs unless this method is called
     /// periodically. It will send an `LRW` to update slave outputs and read slave inputs.
     ///
     /// This method returns the working counter on success.
+    ///
+    /// The working counter is not checked, so a slave device that is not in OP does not cause an
+    /// error. Each slave in OP increments the working counter by 1 if it has inputs and by 2 if it
+    /// has outputs. A slave moved into SAFE-OP with [`SlaveRef::request_slave_state`] only updates
+    /// its inputs, and one in a lower state neither reads its outputs nor updates its inputs, which
+    /// keep their last values. The working counter drops by the missing increments until the slave
+    /// is returned to OP.
+    ///
+    /// Use [`tx_rx_checked`](SlaveGroup::tx_rx_checked) to get an error when the working counter
+    /// does not match the [`expected_wkc`](SlaveGroup::expected_wkc). Either method updates the
+    /// count of consecutive [`bad_wkc_cycles`](SlaveGroup::bad_wkc_cycles).
     pub async fn tx_rx<'sto>(&self, client: &'sto Client<'sto>) -> Result<u16, Error> {
         fmt::trace!(
             "Group TX/RX, start address {:#010x}, data len {}, of which read bytes: {}",
@@ -535,6 +1096,66 @@ where
             .send_receive_slice_mut(self.pdi_mut(), self.read_pdi_len)
             .await?;
 
+        self.count_wkc(wkc);
+
         Ok(wkc)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::offline_client;
+
+    #[test]
+    fn slave_by_alias() {
+        let client = offline_client();
+        let group = SlaveGroup::<4, 16, PreOp>::default();
+
+        let slave = |configured_address, alias| Slave {
+            configured_address,
+            alias,
+            ..Slave::default()
+        };
+
+        unsafe {
+            group.push(slave(0x1000, 0)).unwrap();
+            group.push(slave(0x0010, 0x10)).unwrap();
+            group.push(slave(0x0020, 0x20)).unwrap();
+        }
+
+        let not_found = Err(Error::NotFound {
+            item: Item::Slave,
+            index: None,
+        });
+
+        assert_eq!(
+            group
+                .slave_by_alias(&client, 0x20)
+                .map(|slave| slave.configured_address()),
+            Ok(0x0020)
+        );
+        assert_eq!(
+            group
+                .slave_by_alias(&client, 0x30)
+                .map(|slave| slave.configured_address()),
+            not_found
+        );
+        // Slaves without an alias can't be found by it.
+        assert_eq!(
+            group
+                .slave_by_alias(&client, 0)
+                .map(|slave| slave.configured_address()),
+            not_found
+        );
+
+        let _held = group.slave(&client, 1).unwrap();
+
+        assert_eq!(
+            group
+                .slave_by_alias(&client, 0x20)
+                .map(|slave| slave.configured_address()),
+            Err(Error::Borrow)
+        );
+    }
+}
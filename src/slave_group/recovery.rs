@@ -0,0 +1,426 @@
+use super::{HasPdi, SlaveGroup};
+#[cfg(feature = "alloc")]
+use crate::hook;
+use crate::{
+    al_control::AlControl,
+    dc, driver,
+    error::Error,
+    fmt,
+    register::RegisterAddress,
+    slave::{configuration::PdoDirection, recovery::Presence, Slave, SlaveRef},
+    watchdog, Client, PortLinkStatus, SlaveState, Transition,
+};
+use core::sync::atomic::Ordering;
+
+/// A change in the availability of a slave device found by [`SlaveGroup::recover`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RecoveryEvent {
+    /// The slave device stopped responding at its configured address.
+    Lost {
+        /// Slave address.
+        configured_address: u16,
+    },
+    /// The slave device was configured again and returned to the group's state, after either
+    /// being lost or leaving the group's state.
+    Recovered {
+        /// Slave address.
+        configured_address: u16,
+    },
+    /// The slave device could not be recovered. Recovery is attempted again by the next call to
+    /// [`SlaveGroup::recover`].
+    Failed {
+        /// Slave address.
+        configured_address: u16,
+        /// The error that stopped recovery.
+        error: Error,
+    },
+    /// A hot connect segment was found on the network and its slaves were configured and moved
+    /// into the group's state.
+    SegmentConnected {
+        /// Station alias of the segment's first slave.
+        alias: u16,
+    },
+    /// A hot connect segment stopped responding and was removed from the network.
+    SegmentDisconnected {
+        /// Station alias of the segment's first slave.
+        alias: u16,
+    },
+    /// A physical link was connected to or disconnected from one or more ports of the slave
+    /// device, e.g. because a cable to a downstream slave was unplugged.
+    LinkChanged {
+        /// Slave address.
+        configured_address: u16,
+        /// The status of each port, indexed by EtherCAT port number.
+        ports: [PortLinkStatus; 4],
+    },
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
+where
+    S: HasPdi,
+{
+    /// Find slave devices in this group that have been lost, and recover any that are back on the
+    /// network or have left the group's state.
+    ///
+    /// Each slave device is checked in turn, similar to SOEM's `ecx_recover_slave` and
+    /// `ecx_reconfig_slave`:
+    ///
+    /// - A slave that no longer responds at its configured address is reported as
+    ///   [`RecoveryEvent::Lost`] once, until it is recovered.
+    /// - A power cycled slave loses its configured address. Devices with no address are searched
+    ///   for on the network, starting at the lost slave's position during init as positions shift
+    ///   when other devices are connected or disconnected. The first device found with the same
+    ///   identity is given the slave's configured address again. If only devices with a different
+    ///   identity are found, [`RecoveryEvent::Failed`] is reported with
+    ///   [`Error::TopologyMismatch`].
+    /// - A slave that has dropped to PRE-OP or below, e.g. because it was readdressed, is
+    ///   configured again: its mailboxes, the [`SlaveDriver`](crate::SlaveDriver) configuration and
+    ///   hooks for INIT to PRE-OP and PRE-OP to SAFE-OP, and its FMMUs and DC sync. Its process
+    ///   data must map to the same part of the group's PDI as before, otherwise
+    ///   [`Error::ProcessDataChanged`] is reported. PDO assignments made outside a driver or hook
+    ///   are lost if the slave was power cycled, so must be made again by a hook.
+    /// - A slave that is in SAFE-OP, or has flagged an error, has its error acknowledged and is
+    ///   requested to move back into the group's state.
+    /// - A slave moved into another state with
+    ///   [`SlaveRef::request_slave_state`](crate::SlaveRef::request_slave_state), e.g. into PRE-OP
+    ///   to change a parameter, is left in that state unless it was power cycled.
+    /// - A change in the physical link of any port of a responding slave, read from its DL status
+    ///   register, is reported as [`RecoveryEvent::LinkChanged`].
+    ///
+    /// Slaves in the group's state with no error are not changed. Distributed clock propagation
+    /// delays and offsets are not measured again.
+    ///
+    /// The cyclic [`tx_rx`](SlaveGroup::tx_rx) loop must keep running while this method runs, as
+    /// a slave may need valid outputs before it can enter OP, and other slaves in the group may
+    /// have process data watchdogs. This method should therefore be called from a separate task,
+    /// e.g. when the working counter returned by `tx_rx` drops. Each slave device is borrowed
+    /// while it is checked, so [`slave`](crate::SlaveGroupState::slave) may return
+    /// [`Error::Borrow`] for it during this time. Slaves that are already borrowed, e.g. by the
+    /// application, are skipped and checked again by the next call to this method.
+    ///
+    /// Events that do not fit in the returned list are logged and dropped.
+    ///
+    /// Hot connect segments declared with [`with_hot_connect`](SlaveGroup::with_hot_connect) are
+    /// handled as a whole. If any slave in a connected segment stops responding, the segment is
+    /// reported as [`RecoveryEvent::SegmentDisconnected`] and its slaves are skipped until it is
+    /// connected again. When more devices are found on the network than expected, each
+    /// disconnected segment's first slave is searched for by its station alias. If found, the
+    /// segment's slaves are configured from INIT into the group's state using the segment's
+    /// reserved part of the PDI, and [`RecoveryEvent::SegmentConnected`] is reported.
+    pub async fn recover(
+        &self,
+        client: &Client<'_>,
+    ) -> Result<heapless::Vec<RecoveryEvent, MAX_SLAVES>, Error> {
+        let mut events = heapless::Vec::new();
+
+        for (index, slave) in self.inner().slaves.iter().enumerate() {
+            if self.in_segment(index) {
+                continue;
+            }
+
+            let Ok(mut slave) = slave.try_borrow_mut() else {
+                fmt::warn!("Slave index {} already borrowed, skipping recovery", index);
+
+                continue;
+            };
+
+            self.check_slave(client, &mut slave, &mut events).await;
+        }
+
+        self.recover_hot_connect(client, &mut events).await?;
+
+        Ok(events)
+    }
+
+    /// Check a single slave device, recovering it if required, and record any events.
+    pub(in crate::slave_group) async fn check_slave(
+        &self,
+        client: &Client<'_>,
+        slave: &mut Slave,
+        events: &mut heapless::Vec<RecoveryEvent, MAX_SLAVES>,
+    ) {
+        let configured_address = slave.configured_address;
+
+        let result = match self.recover_slave(client, slave).await {
+            Ok(event) if !slave.lost => self
+                .check_links(client, slave)
+                .await
+                .map(|link_event| [event, link_event]),
+            result => result.map(|event| [event, None]),
+        };
+
+        let new_events = result.unwrap_or_else(|error| {
+            fmt::error!(
+                "Failed to recover slave {:#06x}: {}",
+                configured_address,
+                error
+            );
+
+            [
+                Some(RecoveryEvent::Failed {
+                    configured_address,
+                    error,
+                }),
+                None,
+            ]
+        });
+
+        for event in new_events.into_iter().flatten() {
+            push_event(events, event);
+        }
+    }
+
+    /// Check a single slave device and recover it if required.
+    async fn recover_slave(
+        &self,
+        client: &Client<'_>,
+        slave: &mut Slave,
+    ) -> Result<Option<RecoveryEvent>, Error> {
+        let configured_address = slave.configured_address;
+
+        let presence = SlaveRef::new(client, configured_address, &*slave)
+            .find_on_network()
+            .await?;
+
+        let recovered = match presence {
+            Presence::Missing => {
+                if slave.lost {
+                    return Ok(None);
+                }
+
+                fmt::warn!("Slave {:#06x} lost", configured_address);
+
+                slave.lost = true;
+
+                return Ok(Some(RecoveryEvent::Lost { configured_address }));
+            }
+            Presence::Readdressed { position } => {
+                slave.index = position;
+
+                self.reconfigure_slave(client, slave).await?;
+
+                true
+            }
+            Presence::Present if held_state(slave, S::STATE).is_some() => false,
+            Presence::Present => {
+                let status = SlaveRef::new(client, configured_address, &*slave)
+                    .read(RegisterAddress::AlStatus)
+                    .receive::<AlControl>()
+                    .await?;
+
+                match status.state {
+                    state if state == S::STATE && !status.error => false,
+                    SlaveState::SafeOp | SlaveState::Op => {
+                        fmt::info!(
+                            "Slave {:#06x} in {} (error: {:?}), returning to {}",
+                            configured_address,
+                            status.state,
+                            status.error,
+                            S::STATE
+                        );
+
+                        SlaveRef::new(client, configured_address, &*slave)
+                            .move_to_state(S::STATE)
+                            .await?;
+
+                        true
+                    }
+                    state => {
+                        fmt::info!(
+                            "Slave {:#06x} in {}, configuring again",
+                            configured_address,
+                            state
+                        );
+
+                        self.reconfigure_slave(client, slave).await?;
+
+                        true
+                    }
+                }
+            }
+        };
+
+        if recovered || slave.lost {
+            fmt::info!("Slave {:#06x} recovered", configured_address);
+
+            slave.lost = false;
+
+            Ok(Some(RecoveryEvent::Recovered { configured_address }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Compare the physical link of each port of a slave device to when it was last checked.
+    async fn check_links(
+        &self,
+        client: &Client<'_>,
+        slave: &mut Slave,
+    ) -> Result<Option<RecoveryEvent>, Error> {
+        let configured_address = slave.configured_address;
+
+        let ports = SlaveRef::new(client, configured_address, &*slave)
+            .link_status()
+            .await?;
+
+        let links = ports.map(|port| port.link);
+
+        if links == slave.links {
+            return Ok(None);
+        }
+
+        fmt::info!(
+            "Slave {:#06x} port links changed from {:?} to {:?}",
+            configured_address,
+            slave.links,
+            links
+        );
+
+        slave.links = links;
+
+        Ok(Some(RecoveryEvent::LinkChanged {
+            configured_address,
+            ports,
+        }))
+    }
+
+    /// Configure a slave device again from INIT and move it into the group's state, keeping its
+    /// place in the group's PDI.
+    async fn reconfigure_slave(&self, client: &Client<'_>, slave: &mut Slave) -> Result<(), Error> {
+        let configured_address = slave.configured_address;
+        let pdi_start = self.inner().pdi_start;
+        let io = slave.config.io.clone();
+
+        SlaveRef::new(client, configured_address, &*slave)
+            .move_to_state(SlaveState::Init)
+            .await?;
+
+        // Process data lengths are found again from PDO assignments written by drivers or hooks,
+        // or read back from the slave.
+        slave.config.sm_bit_lens = [None; 16];
+        slave.config.sm_start_bits = [None; 16];
+        slave.config.al_status_mapped = false;
+
+        {
+            let mut slave = SlaveRef::new(client, configured_address, &mut *slave);
+
+            // FMMU and SM memory sections, cleared as during `Client::init`
+            slave
+                .write(RegisterAddress::Fmmu0)
+                .send(&[0u8; 0xff][..])
+                .await?;
+            slave
+                .write(RegisterAddress::Sm0)
+                .send(&[0u8; 0x7f][..])
+                .await?;
+
+            slave.reload_identity().await?;
+
+            // Leaves the slave in PRE-OP
+            slave.configure_mailboxes().await?;
+        }
+
+        for transition in [Transition::INIT_TO_PRE_OP, Transition::PRE_OP_TO_SAFE_OP] {
+            driver::configure(client, transition, slave, self.signals.store()).await?;
+
+            #[cfg(feature = "alloc")]
+            hook::call_hooks(client, self.hook, transition, slave, self.signals.store()).await?;
+        }
+
+        {
+            let mut slave = SlaveRef::new(client, configured_address, &mut *slave)
+                .with_signals(self.signals.store());
+
+            for (direction, segment) in [
+                (PdoDirection::MasterRead, &io.input),
+                (PdoDirection::MasterWrite, &io.output),
+            ] {
+                let start =
+                    u16::try_from(segment.bytes.start).map_err(|_| Error::IntegerTypeConversion)?;
+
+                slave
+                    .configure_fmmus(
+                        pdi_start.increment(start),
+                        pdi_start.start_address,
+                        direction,
+                    )
+                    .await?;
+            }
+        }
+
+        if slave.config.io != io {
+            fmt::error!(
+                "Slave {:#06x} process data changed from {:?} to {:?}",
+                configured_address,
+                io,
+                slave.config.io
+            );
+
+            slave.config.io = io;
+
+            return Err(Error::ProcessDataChanged { configured_address });
+        }
+
+        dc::configure_dc_sync(client, slave).await?;
+        watchdog::configure_watchdog(client, slave, self.watchdog).await?;
+
+        SlaveRef::new(client, configured_address, &mut *slave)
+            .map_al_status()
+            .await?;
+
+        let slave = SlaveRef::new(client, configured_address, &*slave);
+
+        slave.move_to_state(SlaveState::SafeOp).await?;
+
+        if S::STATE == SlaveState::Op {
+            slave.move_to_state(SlaveState::Op).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The state a slave device was moved into by the application, if it is not the group's state.
+fn held_state(slave: &Slave, group_state: SlaveState) -> Option<SlaveState> {
+    match SlaveState::from(slave.requested_state.load(Ordering::Relaxed)) {
+        SlaveState::None => None,
+        state => Some(state).filter(|state| *state != group_state),
+    }
+}
+
+/// Add an event to the list returned by [`SlaveGroup::recover`], logging it if the list is full.
+pub(in crate::slave_group) fn push_event<const N: usize>(
+    events: &mut heapless::Vec<RecoveryEvent, N>,
+    event: RecoveryEvent,
+) {
+    if let Err(event) = events.push(event) {
+        fmt::warn!("Too many recovery events, dropping {:?}", event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn held_slaves() {
+        let slave = Slave::default();
+
+        assert_eq!(held_state(&slave, SlaveState::Op), None);
+
+        slave
+            .requested_state
+            .store(u8::from(SlaveState::PreOp), Ordering::Relaxed);
+
+        assert_eq!(held_state(&slave, SlaveState::Op), Some(SlaveState::PreOp));
+
+        // Moved back into the group's state
+        slave
+            .requested_state
+            .store(u8::from(SlaveState::Op), Ordering::Relaxed);
+
+        assert_eq!(held_state(&slave, SlaveState::Op), None);
+    }
+}
@@ -0,0 +1,193 @@
+use super::{HasPdi, SlaveGroup};
+use crate::{error::Error, fmt, slave::Slave, Client, SlaveState};
+use core::sync::atomic::{AtomicU16, Ordering};
+
+/// The number of slave devices with input and output FMMUs in a group or hot connect segment.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub(in crate::slave_group) struct WkcSlaves {
+    pub inputs: u16,
+    pub outputs: u16,
+}
+
+impl WkcSlaves {
+    pub fn add(&mut self, slave: &Slave) {
+        self.inputs += u16::from(!slave.config.io.input.is_empty());
+        self.outputs += u16::from(!slave.config.io.output.is_empty());
+    }
+
+    /// The working counter of an `LRW` when every slave device is in the given state.
+    ///
+    /// Each slave increments the working counter by 1 when its inputs are read. Slaves in OP also
+    /// increment it by 2 when their outputs are written.
+    pub fn expected(&self, state: SlaveState) -> u16 {
+        if state == SlaveState::Op {
+            self.inputs + self.outputs * 2
+        } else {
+            self.inputs
+        }
+    }
+}
+
+/// [`WkcSlaves`] of a hot connect segment, updated whenever the segment is connected.
+#[derive(Debug, Default)]
+pub(in crate::slave_group) struct AtomicWkcSlaves {
+    inputs: AtomicU16,
+    outputs: AtomicU16,
+}
+
+impl AtomicWkcSlaves {
+    pub fn load(&self) -> WkcSlaves {
+        WkcSlaves {
+            inputs: self.inputs.load(Ordering::Acquire),
+            outputs: self.outputs.load(Ordering::Acquire),
+        }
+    }
+
+    pub fn store(&self, slaves: WkcSlaves) {
+        self.inputs.store(slaves.inputs, Ordering::Release);
+        self.outputs.store(slaves.outputs, Ordering::Release);
+    }
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize>
+    SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>
+where
+    S: HasPdi,
+{
+    /// The working counter [`tx_rx`](SlaveGroup::tx_rx) returns when every slave device in this
+    /// group is in the group's state.
+    ///
+    /// This is computed from the FMMUs configured when the group moved into SAFE-OP: each slave
+    /// with inputs counts 1, and in OP each slave with outputs counts 2 more. Hot connect segments
+    /// are only counted while they are connected. Lost slave devices are still counted, so a
+    /// missing slave shows up as a working counter mismatch.
+    pub fn expected_wkc(&self) -> u16 {
+        let slaves = self
+            .inner()
+            .segments
+            .iter()
+            .filter(|segment| segment.is_present())
+            .map(|segment| segment.wkc_slaves.load())
+            .fold(self.wkc_slaves, |total, segment| WkcSlaves {
+                inputs: total.inputs + segment.inputs,
+                outputs: total.outputs + segment.outputs,
+            });
+
+        slaves.expected(S::STATE)
+    }
+
+    /// The number of consecutive cycles the working counter returned by
+    /// [`tx_rx`](SlaveGroup::tx_rx) or [`tx_rx_checked`](SlaveGroup::tx_rx_checked) did not match
+    /// the [`expected_wkc`](SlaveGroup::expected_wkc), or `0` if the last cycle was good.
+    ///
+    /// This can be used to tolerate the occasional bad cycle, e.g. a frame corrupted by noise,
+    /// while still reacting to a persistent fault.
+    pub fn bad_wkc_cycles(&self) -> u32 {
+        self.bad_wkc_cycles.load(Ordering::Relaxed)
+    }
+
+    /// Drive the slave group's inputs and outputs like [`tx_rx`](SlaveGroup::tx_rx), checking the
+    /// working counter.
+    ///
+    /// Returns [`Error::GroupWorkingCounter`] if the working counter does not match the
+    /// [`expected_wkc`](SlaveGroup::expected_wkc), e.g. because a slave device has left OP or has
+    /// been disconnected. Process data is still exchanged with the remaining slaves in this case,
+    /// so inputs from slaves that are not in the group's state may be stale.
+    pub async fn tx_rx_checked<'sto>(&self, client: &'sto Client<'sto>) -> Result<u16, Error> {
+        let received = self.tx_rx(client).await?;
+
+        self.check_wkc(received)
+    }
+
+    /// Check the working counter from a cycle already passed to
+    /// [`count_wkc`](SlaveGroup::count_wkc).
+    fn check_wkc(&self, received: u16) -> Result<u16, Error> {
+        let expected = self.expected_wkc();
+
+        if received != expected {
+            return Err(Error::GroupWorkingCounter {
+                expected,
+                received,
+                bad_cycles: self.bad_wkc_cycles(),
+            });
+        }
+
+        Ok(received)
+    }
+
+    /// Update the consecutive bad cycle counter with the working counter from a cycle.
+    pub(in crate::slave_group) fn count_wkc(&self, received: u16) {
+        let expected = self.expected_wkc();
+
+        let bad_cycles = if received == expected {
+            0
+        } else {
+            let bad_cycles = self.bad_wkc_cycles().saturating_add(1);
+
+            if bad_cycles == 1 {
+                fmt::warn!(
+                    "Group working counter {} does not match expected {}",
+                    received,
+                    expected
+                );
+            }
+
+            bad_cycles
+        };
+
+        // Only written by the task calling `tx_rx` for this group, so a load and store is enough
+        self.bad_wkc_cycles.store(bad_cycles, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slave_group::Op;
+
+    #[test]
+    fn expected_wkc() {
+        let slaves = WkcSlaves {
+            inputs: 3,
+            outputs: 2,
+        };
+
+        assert_eq!(slaves.expected(SlaveState::SafeOp), 3);
+        assert_eq!(slaves.expected(SlaveState::Op), 7);
+        assert_eq!(WkcSlaves::default().expected(SlaveState::Op), 0);
+    }
+
+    #[test]
+    fn bad_cycles() {
+        let group = SlaveGroup::<4, 16, Op> {
+            wkc_slaves: WkcSlaves {
+                inputs: 1,
+                outputs: 1,
+            },
+            ..SlaveGroup::default()
+        };
+
+        assert_eq!(group.expected_wkc(), 3);
+
+        group.count_wkc(3);
+        assert_eq!(group.bad_wkc_cycles(), 0);
+        assert_eq!(group.check_wkc(3), Ok(3));
+
+        group.count_wkc(1);
+        group.count_wkc(0);
+        assert_eq!(group.bad_wkc_cycles(), 2);
+        assert_eq!(
+            group.check_wkc(0),
+            Err(Error::GroupWorkingCounter {
+                expected: 3,
+                received: 0,
+                bad_cycles: 2
+            })
+        );
+
+        // A single good cycle resets the count
+        group.count_wkc(3);
+        assert_eq!(group.bad_wkc_cycles(), 0);
+        assert_eq!(group.check_wkc(3), Ok(3));
+    }
+}
@@ -44,3 +44,79 @@ impl core::fmt::Display for SlaveState {
         }
     }
 }
+
+/// A transition between two [`SlaveState`]s, e.g. `PRE-OP -> SAFE-OP`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Transition {
+    /// The state being transitioned from.
+    pub from: SlaveState,
+    /// The state being transitioned to.
+    pub to: SlaveState,
+}
+
+impl Transition {
+    /// `INIT -> PRE-OP`.
+    pub const INIT_TO_PRE_OP: Self = Self::new(SlaveState::Init, SlaveState::PreOp);
+    /// `PRE-OP -> SAFE-OP`.
+    pub const PRE_OP_TO_SAFE_OP: Self = Self::new(SlaveState::PreOp, SlaveState::SafeOp);
+    /// `SAFE-OP -> OP`.
+    pub const SAFE_OP_TO_OP: Self = Self::new(SlaveState::SafeOp, SlaveState::Op);
+    /// `OP -> SAFE-OP`.
+    pub const OP_TO_SAFE_OP: Self = Self::new(SlaveState::Op, SlaveState::SafeOp);
+    /// `SAFE-OP -> PRE-OP`.
+    pub const SAFE_OP_TO_PRE_OP: Self = Self::new(SlaveState::SafeOp, SlaveState::PreOp);
+    /// `PRE-OP -> INIT`.
+    pub const PRE_OP_TO_INIT: Self = Self::new(SlaveState::PreOp, SlaveState::Init);
+
+    /// Create a new transition.
+    pub const fn new(from: SlaveState, to: SlaveState) -> Self {
+        Self { from, to }
+    }
+
+    /// Parse a two letter transition code as used in ESI and ENI files, e.g. `PS` for
+    /// `PRE-OP -> SAFE-OP`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        fn state(c: u8) -> Option<SlaveState> {
+            match c {
+                b'I' => Some(SlaveState::Init),
+                b'P' => Some(SlaveState::PreOp),
+                b'B' => Some(SlaveState::Bootstrap),
+                b'S' => Some(SlaveState::SafeOp),
+                b'O' => Some(SlaveState::Op),
+                _ => None,
+            }
+        }
+
+        match code.trim().as_bytes() {
+            [from, to] => Some(Self::new(state(*from)?, state(*to)?)),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for Transition {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} -> {}", self.from, self.to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transition_codes() {
+        assert_eq!(
+            Transition::from_code("PS"),
+            Some(Transition::PRE_OP_TO_SAFE_OP)
+        );
+        assert_eq!(
+            Transition::from_code("IB"),
+            Some(Transition::new(SlaveState::Init, SlaveState::Bootstrap))
+        );
+        assert_eq!(Transition::from_code("PX"), None);
+        assert_eq!(Transition::from_code("PSO"), None);
+    }
+}
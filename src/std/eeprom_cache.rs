@@ -0,0 +1,237 @@
+//! An EEPROM cache held in memory that can be persisted to and loaded from disk.
+
+use crate::{EepromCache, SlaveIdentity};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// An in-memory [`EepromCache`] that can be saved to and loaded from a directory.
+///
+/// Each device's EEPROM image is stored in a separate file named after the device's vendor ID,
+/// product ID, revision and serial number, e.g. `00000002_0b093052_00110000_00000000.hex`. Files
+/// contain the raw EEPROM bytes, in the same format as dumps read from a device.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ethercrab::{std::EepromImageCache, Client, ClientConfig, PduStorage, Timeouts};
+///
+/// static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+///
+/// let cache = EepromImageCache::load("./eeprom-cache").expect("Load cache");
+///
+/// let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+///
+/// let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default())
+///     .with_eeprom_cache(&cache);
+///
+/// // Initialise slave devices with `client`...
+///
+/// cache.save("./eeprom-cache").expect("Save cache");
+/// ```
+#[derive(Debug, Default)]
+pub struct EepromImageCache {
+    images: Mutex<HashMap<SlaveIdentity, Vec<u8>>>,
+    /// Images that are still being read from a device, not yet committed.
+    pending: Mutex<HashMap<SlaveIdentity, Vec<u8>>>,
+}
+
+impl EepromImageCache {
+    /// Create a new, empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load all cached images from the given directory.
+    ///
+    /// If the directory does not exist, an empty cache is returned. Files whose names are not a
+    /// valid device identity are ignored.
+    pub fn load(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref();
+
+        let mut images = HashMap::new();
+
+        if !dir.exists() {
+            return Ok(Self::default());
+        }
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            let Some(identity) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(parse_file_stem)
+            else {
+                continue;
+            };
+
+            images.insert(identity, fs::read(&path)?);
+        }
+
+        Ok(Self {
+            images: Mutex::new(images),
+            pending: Mutex::default(),
+        })
+    }
+
+    /// Save all cached images into the given directory, creating it if it does not exist.
+    pub fn save(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref();
+
+        fs::create_dir_all(dir)?;
+
+        for (identity, image) in self.images.lock().unwrap().iter() {
+            fs::write(image_path(dir, identity), image)?;
+        }
+
+        Ok(())
+    }
+
+    /// Get a copy of the cached EEPROM image for the given device.
+    pub fn image(&self, identity: &SlaveIdentity) -> Option<Vec<u8>> {
+        self.images.lock().unwrap().get(identity).cloned()
+    }
+}
+
+impl EepromCache for EepromImageCache {
+    fn contains(&self, identity: &SlaveIdentity) -> bool {
+        self.images.lock().unwrap().contains_key(identity)
+    }
+
+    fn read(&self, identity: &SlaveIdentity, start_word: u16, buf: &mut [u8]) -> Option<usize> {
+        let images = self.images.lock().unwrap();
+
+        let image = images.get(identity)?;
+
+        let start = usize::from(start_word) * 2;
+
+        let data = image.get(start..)?;
+
+        if data.is_empty() {
+            return None;
+        }
+
+        let len = buf.len().min(data.len());
+
+        buf[0..len].copy_from_slice(&data[0..len]);
+
+        Some(len)
+    }
+
+    fn write(&self, identity: &SlaveIdentity, start_word: u16, data: &[u8]) {
+        let mut pending = self.pending.lock().unwrap();
+
+        let image = pending.entry(*identity).or_default();
+
+        // A new attempt to read the image discards anything left from a failed one
+        if start_word == 0 {
+            image.clear();
+        }
+
+        let start = usize::from(start_word) * 2;
+        let end = start + data.len();
+
+        if image.len() < end {
+            image.resize(end, 0);
+        }
+
+        image[start..end].copy_from_slice(data);
+    }
+
+    fn commit(&self, identity: &SlaveIdentity) {
+        if let Some(image) = self.pending.lock().unwrap().remove(identity) {
+            self.images.lock().unwrap().insert(*identity, image);
+        }
+    }
+}
+
+fn image_path(dir: &Path, identity: &SlaveIdentity) -> PathBuf {
+    dir.join(format!(
+        "{:08x}_{:08x}_{:08x}_{:08x}.hex",
+        identity.vendor_id, identity.product_id, identity.revision, identity.serial
+    ))
+}
+
+fn parse_file_stem(stem: &str) -> Option<SlaveIdentity> {
+    let mut parts = stem.split('_').map(|part| u32::from_str_radix(part, 16));
+
+    let identity = SlaveIdentity {
+        vendor_id: parts.next()?.ok()?,
+        product_id: parts.next()?.ok()?,
+        revision: parts.next()?.ok()?,
+        serial: parts.next()?.ok()?,
+    };
+
+    // Too many parts
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some(identity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_name_round_trip() {
+        let identity = SlaveIdentity {
+            vendor_id: 0x02,
+            product_id: 0x0b093052,
+            revision: 0x00110000,
+            serial: 0,
+        };
+
+        let path = image_path(Path::new("cache"), &identity);
+
+        assert_eq!(
+            path,
+            Path::new("cache/00000002_0b093052_00110000_00000000.hex")
+        );
+
+        assert_eq!(
+            parse_file_stem(path.file_stem().unwrap().to_str().unwrap()),
+            Some(identity)
+        );
+        assert_eq!(parse_file_stem("00000002_0b093052_00110000"), None);
+        assert_eq!(parse_file_stem("akd"), None);
+    }
+
+    #[test]
+    fn read_past_end() {
+        let identity = SlaveIdentity::default();
+
+        let cache = EepromImageCache::new();
+
+        cache.write(&identity, 0, &[1, 2, 3, 4, 5, 6, 7, 8]);
+        cache.write(&identity, 4, &[9, 10]);
+
+        assert!(!cache.contains(&identity));
+
+        cache.commit(&identity);
+
+        assert!(cache.contains(&identity));
+
+        let mut buf = [0u8; 8];
+
+        assert_eq!(cache.read(&identity, 2, &mut buf), Some(6));
+        assert_eq!(buf[0..6], [5, 6, 7, 8, 9, 10]);
+        assert_eq!(cache.read(&identity, 5, &mut buf), None);
+        assert_eq!(
+            cache.read(
+                &SlaveIdentity {
+                    serial: 1,
+                    ..identity
+                },
+                0,
+                &mut buf
+            ),
+            None
+        );
+    }
+}
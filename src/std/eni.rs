@@ -0,0 +1,1654 @@
+//! Load EtherCAT Network Information (ENI) XML configuration files.
+//!
+//! ENI files are exported by network configuration tools such as TwinCAT, and describe the
+//! expected slave devices and how they're connected, as well as the startup commands, PDO
+//! assignments, sync manager layout and cycle time for each slave.
+//!
+//! [`EniFile::init`] discovers the network and checks every discovered slave against the slave at
+//! the same position in the ENI file before anything is configured. [`EniFile::configure`] then
+//! applies the startup commands and DC configuration from the file to a group in PRE-OP, and
+//! [`EniFile::into_safe_op`] and [`EniFile::into_op`] send the register commands for each
+//! transition around the group's state change. Once the group's process data is laid out,
+//! [`EniFile::into_safe_op`] checks it against the `Send`/`Recv` process image offsets in the file.
+//!
+//! EtherCrab addresses slaves and configures their mailboxes, sync managers, FMMUs and DC itself,
+//! so register commands that write to these registers are skipped, as are broadcast and logical
+//! commands. The sync manager and FMMU layout from the file are available through [`EniSlave`],
+//! but not applied.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ethercrab::{std::eni::EniFile, Client, ClientConfig, PduStorage, Timeouts};
+//!
+//! static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+//!
+//! let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+//!
+//! let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+//!
+//! # async {
+//! let eni = EniFile::load("./network.xml").expect("Load ENI");
+//!
+//! let group = eni
+//!     .init_single_group::<16, 64>(&client)
+//!     .await
+//!     .expect("Init");
+//!
+//! let group = eni
+//!     .into_safe_op(group, &client)
+//!     .await
+//!     .expect("PRE-OP -> SAFE-OP");
+//!
+//! let group = eni.into_op(group, &client).await.expect("SAFE-OP -> OP");
+//! # };
+//! ```
+
+use super::{
+    esi::{EsiPdo, EsiSyncManager, EsiSyncManagerType},
+    xml::{
+        attribute_bool, child, child_bool, child_text, children, decode, optional_number,
+        parse_hex_bytes, parse_number, required_number,
+    },
+};
+use crate::{
+    error::{Error, MailboxError, XmlError},
+    fmt,
+    pdi::PdiSegment,
+    register::RegisterAddress,
+    slave_group::{HasPdi, Op, PreOp, SafeOp, SlaveGroupHandle},
+    Client, DcSync, ExpectedSlave, SignalDirection, Slave, SlaveGroup, SlaveIdentity, SlaveRef,
+    TopologyMismatch, TopologyMismatchKind, Transition,
+};
+use core::{
+    ops::{Deref, Range},
+    time::Duration,
+};
+use roxmltree::{Document, Node};
+use std::path::Path;
+
+/// Registers EtherCrab configures itself, which ENI register commands are not allowed to change.
+const MANAGED_REGISTERS: [Range<u16>; 6] = [
+    // Configured station address and alias
+    0x0010..0x0014,
+    // AL control and status
+    0x0120..0x0140,
+    // SII EEPROM interface
+    0x0500..0x0510,
+    // FMMUs
+    0x0600..0x0700,
+    // Sync managers
+    0x0800..0x0880,
+    // Distributed Clocks, configured from `EniSlave::dc_sync`
+    0x0900..0x0a00,
+];
+
+/// A parsed ENI file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EniFile {
+    master_name: String,
+    cycle_time: Option<Duration>,
+    slaves: Vec<EniSlave>,
+}
+
+impl EniFile {
+    /// Parse an ENI document from a string.
+    pub fn parse(xml: &str) -> Result<Self, XmlError> {
+        let doc = Document::parse(xml)?;
+
+        let root = doc.root_element();
+
+        if !root.has_tag_name("EtherCATConfig") {
+            return Err(XmlError::InvalidRoot {
+                expected: "EtherCATConfig",
+                actual: root.tag_name().name().to_string(),
+            });
+        }
+
+        let config = child(root, "Config").ok_or_else(|| XmlError::Missing {
+            parent: "EtherCATConfig".to_string(),
+            name: "Config",
+        })?;
+
+        let master_name = child(config, "Master")
+            .and_then(|master| child(master, "Info"))
+            .and_then(|info| child_text(info, "Name"))
+            .unwrap_or_default()
+            .to_string();
+
+        // Cycle times are given in microseconds.
+        let cycle_time = child(config, "Cyclic")
+            .map(|cyclic| optional_number::<u64>(cyclic, "CycleTime"))
+            .transpose()?
+            .flatten()
+            .map(Duration::from_micros);
+
+        let slaves = children(config, "Slave")
+            .enumerate()
+            .map(|(position, slave)| EniSlave::parse(position, slave))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            master_name,
+            cycle_time,
+            slaves,
+        })
+    }
+
+    /// Load and parse an ENI file from disk.
+    ///
+    /// Files encoded as ISO-8859-1 are transcoded to UTF-8.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, XmlError> {
+        let xml = decode(std::fs::read(path)?)?;
+
+        Self::parse(&xml)
+    }
+
+    /// The name of the master configuration.
+    pub fn master_name(&self) -> &str {
+        &self.master_name
+    }
+
+    /// The configured process data cycle time.
+    pub fn cycle_time(&self) -> Option<Duration> {
+        self.cycle_time
+    }
+
+    /// All expected slave devices, in network order.
+    pub fn slaves(&self) -> &[EniSlave] {
+        &self.slaves
+    }
+
+    /// Get the expected slave device at the given position in the network.
+    pub fn slave(&self, position: usize) -> Option<&EniSlave> {
+        self.slaves.get(position)
+    }
+
+    /// The expected network described by this ENI file, as passed to
+    /// [`Client::init_expected`].
+    ///
+    /// Each slave must match the ENI vendor ID, product code and revision, and be connected to the
+    /// same port of the same previous slave.
+    pub fn expected_slaves(&self) -> Vec<ExpectedSlave> {
+        self.slaves
+            .iter()
+            .map(|slave| {
+                let expected = ExpectedSlave::from(slave.identity);
+
+                let parent = slave.previous_port.and_then(|previous| {
+                    self.slaves
+                        .iter()
+                        .find(|parent| parent.configured_address == previous.configured_address)
+                        .map(|parent| (parent.position, usize::from(previous.port)))
+                });
+
+                match parent {
+                    Some((index, port)) => expected.with_parent(index, port),
+                    None => expected,
+                }
+            })
+            .collect()
+    }
+
+    /// Compare the identities of discovered slave devices, in network order, against the slaves
+    /// expected by this ENI file.
+    ///
+    /// Every deviating position is returned. An empty list means the network matches. Connections
+    /// between slaves are only checked by [`EniFile::init`].
+    pub fn verify(
+        &self,
+        discovered: impl IntoIterator<Item = SlaveIdentity>,
+    ) -> Vec<TopologyMismatch> {
+        let mut discovered = discovered.into_iter();
+        let mut expected = self
+            .slaves
+            .iter()
+            .map(|slave| ExpectedSlave::from(slave.identity));
+        let mut mismatches = Vec::new();
+        let mut position = 0;
+
+        loop {
+            let kind = match (expected.next(), discovered.next()) {
+                (None, None) => break,
+                (Some(expected), Some(found)) => expected.identity_mismatch(found),
+                (Some(expected), None) => Some(TopologyMismatchKind::Missing { expected }),
+                (None, Some(found)) => Some(TopologyMismatchKind::Extra { found }),
+            };
+
+            if let Some(kind) = kind {
+                mismatches.push(TopologyMismatch { position, kind });
+            }
+
+            position += 1;
+        }
+
+        mismatches
+    }
+
+    /// Initialise the network as [`Client::init_expected`] does, checking that the discovered
+    /// slave devices match those in this ENI file before any of them are configured.
+    ///
+    /// If the network does not match, [`EniError::Mismatch`] is returned with a list of every
+    /// deviating position.
+    pub async fn init<const MAX_SLAVES: usize, G>(
+        &self,
+        client: &Client<'_>,
+        group_filter: impl for<'g> FnMut(&'g G, &Slave) -> Result<&'g dyn SlaveGroupHandle, Error>,
+    ) -> Result<G, EniError>
+    where
+        G: Default,
+    {
+        let mut mismatches = heapless::Vec::<_, MAX_SLAVES>::new();
+
+        let result = client
+            .init_expected::<MAX_SLAVES, G>(&self.expected_slaves(), &mut mismatches, group_filter)
+            .await;
+
+        result.map_err(|e| match e {
+            Error::TopologyMismatch { .. } => EniError::Mismatch(mismatches.to_vec()),
+            e => EniError::Ethercat(e),
+        })
+    }
+
+    /// Initialise all slave devices into a single group, check them against this ENI file, and
+    /// apply the ENI configuration with [`EniFile::configure`].
+    pub async fn init_single_group<const MAX_SLAVES: usize, const MAX_PDI: usize>(
+        &self,
+        client: &Client<'_>,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp>, EniError> {
+        let mut group = self
+            .init::<MAX_SLAVES, _>(client, |group, _slave| Ok(group))
+            .await?;
+
+        self.configure(&mut group, client).await?;
+
+        Ok(group)
+    }
+
+    /// Apply the startup commands and DC configuration from this ENI file to every slave in a
+    /// group in PRE-OP.
+    ///
+    /// EtherCrab moves slaves from INIT to PRE-OP during [`Client::init`], so the
+    /// `INIT -> PRE-OP` register and CoE commands are sent now, followed by the
+    /// `PRE-OP -> SAFE-OP` CoE commands. The PDO assignment written by these commands is then used
+    /// to lay out the group's process data when it is moved into SAFE-OP with
+    /// [`EniFile::into_safe_op`].
+    pub async fn configure<
+        const MAX_SLAVES: usize,
+        const MAX_PDI: usize,
+        const MAX_SIGNALS: usize,
+    >(
+        &self,
+        group: &mut SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp, MAX_SIGNALS>,
+        client: &Client<'_>,
+    ) -> Result<(), Error> {
+        for mut slave in group.iter(client) {
+            let Some(expected) = self.slave(slave.index()) else {
+                fmt::warn!(
+                    "Slave {:#06x} at position {} is not in ENI",
+                    slave.configured_address(),
+                    slave.index()
+                );
+
+                continue;
+            };
+
+            if let Some(dc_sync) = expected.dc_sync() {
+                slave.set_dc_sync(dc_sync);
+            }
+
+            for before_slave in [true, false] {
+                expected
+                    .send_register_cmds(&slave, Transition::INIT_TO_PRE_OP, before_slave)
+                    .await?;
+            }
+
+            expected
+                .write_mailbox_cmds(&slave, Transition::INIT_TO_PRE_OP)
+                .await?;
+
+            expected
+                .write_mailbox_cmds(&slave, Transition::PRE_OP_TO_SAFE_OP)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Transition a group configured with [`EniFile::configure`] from PRE-OP to SAFE-OP, sending
+    /// the `PRE-OP -> SAFE-OP` register commands from this ENI file before and after the state
+    /// change.
+    ///
+    /// The process data layout of the group is then checked with
+    /// [`EniFile::verify_process_image`]. If it differs from the ENI file,
+    /// [`EniError::ProcessImage`] is returned with every deviating slave. Call
+    /// [`SlaveGroup::into_safe_op`] directly to accept EtherCrab's layout instead.
+    pub async fn into_safe_op<
+        const MAX_SLAVES: usize,
+        const MAX_PDI: usize,
+        const MAX_SIGNALS: usize,
+    >(
+        &self,
+        group: SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp, MAX_SIGNALS>,
+        client: &Client<'_>,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp, MAX_SIGNALS>, EniError> {
+        self.send_register_cmds(&group, client, Transition::PRE_OP_TO_SAFE_OP, true)
+            .await?;
+
+        let group = group.into_safe_op(client).await?;
+
+        self.send_register_cmds(&group, client, Transition::PRE_OP_TO_SAFE_OP, false)
+            .await?;
+
+        let mismatches = self.verify_process_image(&group)?;
+
+        if !mismatches.is_empty() {
+            return Err(EniError::ProcessImage(mismatches));
+        }
+
+        Ok(group)
+    }
+
+    /// Transition a group from SAFE-OP to OP, sending the `SAFE-OP -> OP` register commands from
+    /// this ENI file before and after the state change.
+    pub async fn into_op<
+        const MAX_SLAVES: usize,
+        const MAX_PDI: usize,
+        const MAX_SIGNALS: usize,
+    >(
+        &self,
+        group: SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp, MAX_SIGNALS>,
+        client: &Client<'_>,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Op, MAX_SIGNALS>, Error> {
+        self.send_register_cmds(&group, client, Transition::SAFE_OP_TO_OP, true)
+            .await?;
+
+        let group = group.into_op(client).await?;
+
+        self.send_register_cmds(&group, client, Transition::SAFE_OP_TO_OP, false)
+            .await?;
+
+        Ok(group)
+    }
+
+    /// Compare the process data layout EtherCrab computed for a group against the `Send` and
+    /// `Recv` process image ranges in this ENI file.
+    ///
+    /// EtherCrab places each group's process data at its own logical address, so the start of
+    /// every range is compared relative to the first input or output range of the slaves in the
+    /// group, both in the ENI file and in EtherCrab's layout. An empty list means the layout
+    /// matches.
+    pub fn verify_process_image<
+        const MAX_SLAVES: usize,
+        const MAX_PDI: usize,
+        S,
+        const MAX_SIGNALS: usize,
+    >(
+        &self,
+        group: &SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>,
+    ) -> Result<Vec<EniProcessImageMismatch>, Error>
+    where
+        S: HasPdi,
+    {
+        let layout = group.present_slaves(|slave| {
+            let io = slave.io_segments();
+
+            (
+                slave.index,
+                EniPdiRange::from_segment(&io.input),
+                EniPdiRange::from_segment(&io.output),
+            )
+        })?;
+
+        Ok(self.process_image_mismatches(&layout))
+    }
+
+    /// Compare the `(position, inputs, outputs)` ranges of the slaves in a group against this ENI
+    /// file.
+    fn process_image_mismatches(
+        &self,
+        layout: &[(usize, Option<EniPdiRange>, Option<EniPdiRange>)],
+    ) -> Vec<EniProcessImageMismatch> {
+        let actual = |(_, inputs, outputs): &(usize, _, _), direction| match direction {
+            SignalDirection::Input => *inputs,
+            SignalDirection::Output => *outputs,
+        };
+
+        let expected = |position: usize, direction| {
+            self.slave(position)
+                .and_then(|slave| slave.pdi_range(direction))
+        };
+
+        let base = |direction| {
+            let actual = layout
+                .iter()
+                .filter_map(|slave| actual(slave, direction))
+                .map(|range| range.bit_start)
+                .min();
+
+            let expected = layout
+                .iter()
+                .filter_map(|(position, ..)| expected(*position, direction))
+                .map(|range| range.bit_start)
+                .min();
+
+            (expected.unwrap_or(0), actual.unwrap_or(0))
+        };
+
+        let bases = [
+            (SignalDirection::Input, base(SignalDirection::Input)),
+            (SignalDirection::Output, base(SignalDirection::Output)),
+        ];
+
+        let mut mismatches = Vec::new();
+
+        for slave in layout {
+            for (direction, (expected_base, actual_base)) in bases {
+                let expected =
+                    expected(slave.0, direction).map(|range| range.relative_to(expected_base));
+                let actual = actual(slave, direction).map(|range| range.relative_to(actual_base));
+
+                if expected != actual {
+                    mismatches.push(EniProcessImageMismatch {
+                        position: slave.0,
+                        direction,
+                        expected,
+                        actual,
+                    });
+                }
+            }
+        }
+
+        mismatches
+    }
+
+    /// Send the register commands for a transition to every slave in a group that is on the
+    /// network.
+    async fn send_register_cmds<
+        const MAX_SLAVES: usize,
+        const MAX_PDI: usize,
+        S,
+        const MAX_SIGNALS: usize,
+    >(
+        &self,
+        group: &SlaveGroup<MAX_SLAVES, MAX_PDI, S, MAX_SIGNALS>,
+        client: &Client<'_>,
+        transition: Transition,
+        before_slave: bool,
+    ) -> Result<(), Error> {
+        let slaves = group.present_slaves(|slave| (slave.index, slave.configured_address))?;
+
+        for (position, configured_address) in slaves {
+            let Some(expected) = self.slave(position) else {
+                continue;
+            };
+
+            expected
+                .send_register_cmds(
+                    &SlaveRef::new(client, configured_address, ()),
+                    transition,
+                    before_slave,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An error returned when initialising a network from an ENI file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EniError {
+    /// An EtherCAT error occurred during initialisation.
+    Ethercat(Error),
+    /// The discovered network does not match the ENI file.
+    Mismatch(Vec<TopologyMismatch>),
+    /// The process data layout of a group does not match the ENI file.
+    ProcessImage(Vec<EniProcessImageMismatch>),
+}
+
+impl From<Error> for EniError {
+    fn from(value: Error) -> Self {
+        Self::Ethercat(value)
+    }
+}
+
+impl std::error::Error for EniError {}
+
+impl core::fmt::Display for EniError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EniError::Ethercat(e) => write!(f, "{}", e),
+            EniError::Mismatch(mismatches) => {
+                f.write_str("network does not match ENI: ")?;
+
+                for (i, mismatch) in mismatches.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str("; ")?;
+                    }
+
+                    write!(f, "{}", mismatch)?;
+                }
+
+                Ok(())
+            }
+            EniError::ProcessImage(mismatches) => {
+                f.write_str("process image does not match ENI: ")?;
+
+                for (i, mismatch) in mismatches.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str("; ")?;
+                    }
+
+                    write!(f, "{}", mismatch)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A slave whose process data EtherCrab lays out differently to the ENI file.
+///
+/// Ranges are relative to the start of the group's inputs or outputs. See
+/// [`EniFile::verify_process_image`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EniProcessImageMismatch {
+    /// Position of the slave in the network, starting at `0`.
+    pub position: usize,
+    /// Whether the slave's inputs or outputs differ.
+    pub direction: SignalDirection,
+    /// The range given by the ENI file, or `None` if the slave has no process data in this
+    /// direction.
+    pub expected: Option<EniPdiRange>,
+    /// The range laid out by EtherCrab, or `None` if the slave has no process data in this
+    /// direction.
+    pub actual: Option<EniPdiRange>,
+}
+
+impl core::fmt::Display for EniProcessImageMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let direction = match self.direction {
+            SignalDirection::Input => "inputs",
+            SignalDirection::Output => "outputs",
+        };
+
+        match self.expected {
+            Some(range) => write!(
+                f,
+                "position {}: expected {} at {}",
+                self.position, direction, range
+            )?,
+            None => write!(f, "position {}: expected no {}", self.position, direction)?,
+        }
+
+        f.write_str(", found ")?;
+
+        match self.actual {
+            Some(range) => write!(f, "{}", range),
+            None => f.write_str("none"),
+        }
+    }
+}
+
+/// An expected slave device and its configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EniSlave {
+    /// Position of the slave in the network, starting at `0`.
+    pub position: usize,
+    /// Slave name.
+    pub name: String,
+    /// Configured station address.
+    pub configured_address: u16,
+    /// Expected identity. The serial number is `0` if not given in the ENI file.
+    pub identity: SlaveIdentity,
+    /// The port of a previous slave this slave is connected to.
+    ///
+    /// This is `None` for the first slave in the network.
+    pub previous_port: Option<EniPreviousPort>,
+    /// Sync manager configuration.
+    pub sync_managers: Vec<EniSyncManager>,
+    /// Outputs (master to slave) PDOs.
+    pub rx_pdos: Vec<EsiPdo>,
+    /// Inputs (slave to master) PDOs.
+    pub tx_pdos: Vec<EsiPdo>,
+    /// Position of the slave's outputs in the logical process image.
+    pub outputs: Option<EniPdiRange>,
+    /// Position of the slave's inputs in the logical process image.
+    pub inputs: Option<EniPdiRange>,
+    /// Register writes and reads sent during state transitions.
+    pub register_cmds: Vec<EniRegisterCmd>,
+    /// CoE mailbox commands sent during state transitions.
+    pub mailbox_cmds: Vec<EniMailboxCmd>,
+    /// Distributed Clocks configuration.
+    pub dc: Option<EniDc>,
+}
+
+impl EniSlave {
+    fn parse(position: usize, slave: Node<'_, '_>) -> Result<Self, XmlError> {
+        let info = child(slave, "Info").ok_or_else(|| XmlError::Missing {
+            parent: "Slave".to_string(),
+            name: "Info",
+        })?;
+
+        let identity = SlaveIdentity {
+            vendor_id: required_number(info, "VendorId")?,
+            product_id: required_number(info, "ProductCode")?,
+            revision: optional_number(info, "RevisionNo")?.unwrap_or(0),
+            serial: optional_number(info, "SerialNo")?.unwrap_or(0),
+        };
+
+        let previous_port = children(slave, "PreviousPort")
+            .find(|port| port.attribute("Selected") != Some("0"))
+            .map(EniPreviousPort::parse)
+            .transpose()?;
+
+        let process_data = child(slave, "ProcessData");
+
+        let sync_managers = process_data
+            .map(|process_data| {
+                process_data
+                    .children()
+                    .filter_map(|node| {
+                        let index = node.tag_name().name().strip_prefix("Sm")?;
+
+                        Some(EniSyncManager::parse(index, node))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let pdos = |name: &'static str| -> Result<Vec<EsiPdo>, XmlError> {
+            process_data
+                .map(|process_data| children(process_data, name).map(EsiPdo::parse).collect())
+                .unwrap_or(Ok(Vec::new()))
+        };
+
+        let range = |name: &'static str| -> Result<Option<EniPdiRange>, XmlError> {
+            process_data
+                .and_then(|process_data| child(process_data, name))
+                .map(EniPdiRange::parse)
+                .transpose()
+        };
+
+        let register_cmds = child(slave, "InitCmds")
+            .map(|cmds| {
+                children(cmds, "InitCmd")
+                    .map(EniRegisterCmd::parse)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let mailbox_cmds = child(slave, "Mailbox")
+            .and_then(|mailbox| child(mailbox, "CoE"))
+            .and_then(|coe| child(coe, "InitCmds"))
+            .map(|cmds| {
+                children(cmds, "InitCmd")
+                    .map(EniMailboxCmd::parse)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(Self {
+            position,
+            name: child_text(info, "Name").unwrap_or_default().to_string(),
+            configured_address: optional_number(info, "PhysAddr")?.unwrap_or(0),
+            identity,
+            previous_port,
+            sync_managers,
+            rx_pdos: pdos("RxPdo")?,
+            tx_pdos: pdos("TxPdo")?,
+            outputs: range("Send")?,
+            inputs: range("Recv")?,
+            register_cmds,
+            mailbox_cmds,
+            dc: child(slave, "DC").map(EniDc::parse).transpose()?,
+        })
+    }
+
+    /// Register commands for the given transition, in the order they are defined in the ENI
+    /// file.
+    pub fn register_cmds(&self, transition: Transition) -> impl Iterator<Item = &EniRegisterCmd> {
+        self.register_cmds
+            .iter()
+            .filter(move |cmd| cmd.transitions.contains(&transition))
+    }
+
+    /// Mailbox commands for the given transition, in the order they are defined in the ENI file.
+    pub fn mailbox_cmds(&self, transition: Transition) -> impl Iterator<Item = &EniMailboxCmd> {
+        self.mailbox_cmds
+            .iter()
+            .filter(move |cmd| cmd.transitions.contains(&transition))
+    }
+
+    /// The DC sync configuration for this slave.
+    ///
+    /// The activation value is taken from the register command that writes to the DC activation
+    /// register `0x0980`. Returns `None` if the slave has no DC configuration.
+    pub fn dc_sync(&self) -> Option<DcSync> {
+        let dc = self.dc.as_ref()?;
+
+        let assign_activate = self
+            .register_cmds
+            .iter()
+            .find_map(|cmd| cmd.assign_activate())?;
+
+        Some(DcSync {
+            assign_activate,
+            sync0_cycle_time: dc.cycle_time_0,
+            sync0_shift_time: dc.shift_time,
+            sync1_cycle_time: dc.cycle_time_1,
+        })
+    }
+
+    /// The position of the slave's inputs or outputs in the logical process image, or `None` if it
+    /// has no process data in that direction.
+    pub fn pdi_range(&self, direction: SignalDirection) -> Option<EniPdiRange> {
+        let range = match direction {
+            SignalDirection::Input => self.inputs,
+            SignalDirection::Output => self.outputs,
+        };
+
+        range.filter(|range| range.bit_len > 0)
+    }
+
+    /// Send the register commands for the given transition to a slave.
+    ///
+    /// `before_slave` selects the commands the ENI file marks to be sent before the slave is
+    /// requested to change state, or those sent once it has.
+    ///
+    /// Commands are sent to the slave's configured address as `FPRD` or `FPWR`, whatever
+    /// addressing mode the ENI file uses, and must be acknowledged by the slave. Read-write
+    /// commands are sent as writes. Read commands with a validation value are repeated until the
+    /// slave responds with the expected data, returning [`Error::Timeout`] if it does not.
+    ///
+    /// Commands that write to registers EtherCrab configures itself, like the station address,
+    /// AL control, sync manager, FMMU and DC registers, are skipped, as are broadcast and logical
+    /// commands that are not addressed to a single slave.
+    pub async fn send_register_cmds<S>(
+        &self,
+        slave: &SlaveRef<'_, S>,
+        transition: Transition,
+        before_slave: bool,
+    ) -> Result<(), Error> {
+        let cmds = self
+            .register_cmds(transition)
+            .filter(|cmd| cmd.before_slave == before_slave);
+
+        for cmd in cmds {
+            if !cmd.is_slave_addressed() {
+                fmt::warn!(
+                    "Slave {:#06x} skipping ENI register command {} to {:#06x}: only single slave commands are supported",
+                    slave.configured_address(),
+                    cmd.command,
+                    cmd.ado
+                );
+
+                continue;
+            }
+
+            if cmd.is_managed() {
+                fmt::debug!(
+                    "Slave {:#06x} skipping ENI register command to {:#06x} ({}): register is configured by EtherCrab",
+                    slave.configured_address(),
+                    cmd.ado,
+                    cmd.comment.as_str()
+                );
+
+                continue;
+            }
+
+            fmt::debug!(
+                "Slave {:#06x} ENI register command {} to {:#06x} ({})",
+                slave.configured_address(),
+                cmd.command,
+                cmd.ado,
+                cmd.comment.as_str()
+            );
+
+            let mut retries = cmd.retries;
+
+            loop {
+                match cmd.send(slave).await {
+                    Err(e) if retries > 0 => {
+                        fmt::debug!("Retrying ENI register command after error {}", e);
+
+                        retries -= 1;
+                    }
+                    result => break result?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write all CoE download commands for the given transition to a slave in PRE-OP.
+    ///
+    /// Note that only expedited SDO downloads (4 bytes maximum) are currently supported. Complete
+    /// access commands or longer values return [`MailboxError::Unsupported`].
+    pub async fn write_mailbox_cmds<S>(
+        &self,
+        slave: &SlaveRef<'_, S>,
+        transition: Transition,
+    ) -> Result<(), Error>
+    where
+        S: Deref<Target = Slave>,
+    {
+        for cmd in self.mailbox_cmds(transition).filter(|cmd| cmd.download) {
+            fmt::debug!(
+                "Slave {:#06x} ENI mailbox command {:#06x}:{} ({})",
+                slave.configured_address(),
+                cmd.index,
+                cmd.sub_index,
+                cmd.comment.as_str()
+            );
+
+            if cmd.complete_access || cmd.data.len() > 4 {
+                fmt::error!("Complete access and SDO writes longer than 4 bytes are not supported");
+
+                return Err(Error::Mailbox(MailboxError::Unsupported {
+                    address: cmd.index,
+                    sub_index: cmd.sub_index,
+                }));
+            }
+
+            slave
+                .sdo_write(cmd.index, cmd.sub_index, cmd.data.as_slice())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The port of a previous slave a slave is connected to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EniPreviousPort {
+    /// Configured station address of the previous slave.
+    pub configured_address: u16,
+    /// Port number on the previous slave, where `0` is port A and `3` is port D.
+    pub port: u8,
+}
+
+impl EniPreviousPort {
+    fn parse(port: Node<'_, '_>) -> Result<Self, XmlError> {
+        let name = child_text(port, "Port").unwrap_or_default();
+
+        let port_number = match name {
+            "A" => 0,
+            "B" => 1,
+            "C" => 2,
+            "D" => 3,
+            other => {
+                return Err(XmlError::InvalidValue {
+                    name: "Port",
+                    value: other.to_string(),
+                })
+            }
+        };
+
+        Ok(Self {
+            configured_address: required_number(port, "PhysAddr")?,
+            port: port_number,
+        })
+    }
+}
+
+/// A slave's sync manager configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EniSyncManager {
+    /// Sync manager index.
+    pub index: u8,
+    /// Sync manager function.
+    pub kind: EsiSyncManagerType,
+    /// Physical start address.
+    pub start_address: Option<u16>,
+    /// Length in bytes.
+    pub default_size: Option<u16>,
+    /// Control register value.
+    pub control_byte: Option<u8>,
+    /// Whether the sync manager is enabled.
+    pub enable: bool,
+    /// Indices of the PDOs assigned to this sync manager.
+    pub pdos: Vec<u16>,
+}
+
+impl EniSyncManager {
+    fn parse(index: &str, sm: Node<'_, '_>) -> Result<Self, XmlError> {
+        Ok(Self {
+            index: parse_number(index, "Sm")?,
+            kind: EsiSyncManager::parse_kind(child_text(sm, "Type").unwrap_or_default()),
+            start_address: optional_number(sm, "StartAddress")?,
+            default_size: optional_number(sm, "DefaultSize")?,
+            control_byte: optional_number(sm, "ControlByte")?,
+            enable: child_bool(sm, "Enable")?,
+            pdos: children(sm, "Pdo")
+                .map(|pdo| parse_number(pdo.text().unwrap_or_default(), "Pdo"))
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+/// A range in the logical process image, in bits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EniPdiRange {
+    /// Start bit.
+    pub bit_start: u32,
+    /// Length in bits.
+    pub bit_len: u32,
+}
+
+impl EniPdiRange {
+    fn parse(range: Node<'_, '_>) -> Result<Self, XmlError> {
+        Ok(Self {
+            bit_start: required_number(range, "BitStart")?,
+            bit_len: required_number(range, "BitLength")?,
+        })
+    }
+
+    fn from_segment(segment: &PdiSegment) -> Option<Self> {
+        (segment.bit_len > 0).then_some(Self {
+            bit_start: (segment.bytes.start * 8) as u32,
+            bit_len: segment.bit_len as u32,
+        })
+    }
+
+    fn relative_to(self, base: u32) -> Self {
+        Self {
+            bit_start: self.bit_start - base,
+            ..self
+        }
+    }
+}
+
+impl core::fmt::Display for EniPdiRange {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "bits {}..{}",
+            self.bit_start,
+            self.bit_start + self.bit_len
+        )
+    }
+}
+
+/// An EtherCAT register command sent during a state transition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EniRegisterCmd {
+    /// The transitions this command should be sent in.
+    pub transitions: Vec<Transition>,
+    /// Whether the command must be sent before the slave is requested to change state.
+    pub before_slave: bool,
+    /// EtherCAT command type, e.g. `5` for `FPWR`.
+    pub command: u8,
+    /// Slave address, either a position or configured station address depending on
+    /// [`command`](EniRegisterCmd::command).
+    pub adp: u16,
+    /// Register address.
+    pub ado: u16,
+    /// Data to write. Empty for read commands.
+    pub data: Vec<u8>,
+    /// Expected working counter.
+    pub expected_wkc: Option<u16>,
+    /// Number of times to retry the command.
+    pub retries: u16,
+    /// Data a read command must return.
+    pub validate: Option<EniValidate>,
+    /// Description of the command.
+    pub comment: String,
+}
+
+impl EniRegisterCmd {
+    fn parse(cmd: Node<'_, '_>) -> Result<Self, XmlError> {
+        Ok(Self {
+            transitions: transitions(cmd)?,
+            before_slave: child_bool(cmd, "BeforeSlave")?,
+            command: required_number(cmd, "Cmd")?,
+            adp: optional_number(cmd, "Adp")?.unwrap_or(0),
+            ado: required_number(cmd, "Ado")?,
+            data: parse_hex_bytes(child_text(cmd, "Data").unwrap_or_default(), "Data")?,
+            expected_wkc: optional_number(cmd, "Cnt")?,
+            retries: optional_number(cmd, "Retries")?.unwrap_or(0),
+            validate: child(cmd, "Validate").map(EniValidate::parse).transpose()?,
+            comment: child_text(cmd, "Comment").unwrap_or_default().to_string(),
+        })
+    }
+
+    /// Whether this is an auto increment or configured address command to a single slave.
+    fn is_slave_addressed(&self) -> bool {
+        // APRD, APWR, APRW, FPRD, FPWR, FPRW
+        (1..=6).contains(&self.command)
+    }
+
+    fn is_read(&self) -> bool {
+        // APRD, FPRD
+        matches!(self.command, 1 | 4)
+    }
+
+    /// Whether this command accesses a register EtherCrab configures itself.
+    fn is_managed(&self) -> bool {
+        let len = self
+            .validate
+            .as_ref()
+            .map_or(self.data.len(), |validate| validate.data.len())
+            .max(1);
+
+        let start = self.ado;
+        let end = start.saturating_add(len as u16);
+
+        MANAGED_REGISTERS
+            .iter()
+            .any(|range| start < range.end && range.start < end)
+    }
+
+    async fn send<S>(&self, slave: &SlaveRef<'_, S>) -> Result<(), Error> {
+        if !self.is_read() {
+            return slave.write(self.ado).send(self.data.as_slice()).await;
+        }
+
+        let Some(validate) = &self.validate else {
+            slave
+                .read(self.ado)
+                .receive_slice(self.data.len() as u16)
+                .await?;
+
+            return Ok(());
+        };
+
+        crate::timer_factory::timeout(validate.timeout, async {
+            loop {
+                let data = slave
+                    .read(self.ado)
+                    .receive_slice(validate.data.len() as u16)
+                    .await?;
+
+                if validate.matches(&data) {
+                    break Ok(());
+                }
+
+                slave.client.timeouts.loop_tick().await;
+            }
+        })
+        .await
+    }
+
+    /// The DC activation value if this command writes to register `0x0980`.
+    fn assign_activate(&self) -> Option<u16> {
+        if self.ado != u16::from(RegisterAddress::DcCyclicUnitControl) {
+            return None;
+        }
+
+        match self.data.as_slice() {
+            [control, activate, ..] => Some(u16::from_le_bytes([*control, *activate])),
+            _ => None,
+        }
+    }
+}
+
+/// The value a register read command must return.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EniValidate {
+    /// Expected data.
+    pub data: Vec<u8>,
+    /// Mask applied to the read data before it is compared. All bits are compared if empty.
+    pub mask: Vec<u8>,
+    /// How long to wait for the slave to return the expected data.
+    pub timeout: Duration,
+}
+
+impl EniValidate {
+    fn parse(validate: Node<'_, '_>) -> Result<Self, XmlError> {
+        Ok(Self {
+            data: parse_hex_bytes(child_text(validate, "Data").unwrap_or_default(), "Data")?,
+            mask: parse_hex_bytes(
+                child_text(validate, "DataMask").unwrap_or_default(),
+                "DataMask",
+            )?,
+            // Timeouts are given in milliseconds.
+            timeout: Duration::from_millis(optional_number(validate, "Timeout")?.unwrap_or(0)),
+        })
+    }
+
+    fn matches(&self, data: &[u8]) -> bool {
+        data.iter()
+            .zip(&self.data)
+            .enumerate()
+            .all(|(i, (read, expected))| {
+                let mask = self.mask.get(i).copied().unwrap_or(0xff);
+
+                read & mask == expected & mask
+            })
+    }
+}
+
+/// A CoE mailbox command sent during a state transition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EniMailboxCmd {
+    /// The transitions this command should be sent in.
+    pub transitions: Vec<Transition>,
+    /// `true` for an SDO download (write), `false` for an upload (read).
+    pub download: bool,
+    /// Object index.
+    pub index: u16,
+    /// Object sub-index.
+    pub sub_index: u8,
+    /// Data to write.
+    pub data: Vec<u8>,
+    /// Whether the object should be written with complete access.
+    pub complete_access: bool,
+    /// Description of the command.
+    pub comment: String,
+}
+
+impl EniMailboxCmd {
+    fn parse(cmd: Node<'_, '_>) -> Result<Self, XmlError> {
+        // Client command specifier: 1 is an SDO download, 2 is an SDO upload.
+        let ccs: u8 = optional_number(cmd, "Ccs")?.unwrap_or(1);
+
+        Ok(Self {
+            transitions: transitions(cmd)?,
+            download: ccs == 1,
+            index: required_number(cmd, "Index")?,
+            sub_index: optional_number(cmd, "SubIndex")?.unwrap_or(0),
+            data: parse_hex_bytes(child_text(cmd, "Data").unwrap_or_default(), "Data")?,
+            complete_access: attribute_bool(cmd, "CompleteAccess")?
+                || child_bool(cmd, "CompleteAccess")?,
+            comment: child_text(cmd, "Comment").unwrap_or_default().to_string(),
+        })
+    }
+}
+
+/// A slave's Distributed Clocks configuration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EniDc {
+    /// Whether this slave is the DC reference clock.
+    pub reference_clock: bool,
+    /// SYNC0 cycle time in nanoseconds.
+    pub cycle_time_0: u32,
+    /// SYNC1 cycle time in nanoseconds.
+    pub cycle_time_1: u32,
+    /// SYNC0 shift time in nanoseconds.
+    pub shift_time: u32,
+}
+
+impl EniDc {
+    fn parse(dc: Node<'_, '_>) -> Result<Self, XmlError> {
+        Ok(Self {
+            reference_clock: child_bool(dc, "ReferenceClock")?,
+            cycle_time_0: optional_number(dc, "CycleTime0")?.unwrap_or(0),
+            cycle_time_1: optional_number(dc, "CycleTime1")?.unwrap_or(0),
+            shift_time: optional_number(dc, "ShiftTime")?.unwrap_or(0),
+        })
+    }
+}
+
+fn transitions(cmd: Node<'_, '_>) -> Result<Vec<Transition>, XmlError> {
+    children(cmd, "Transition")
+        .map(|transition| {
+            let code = transition.text().unwrap_or_default();
+
+            Transition::from_code(code).ok_or_else(|| XmlError::InvalidValue {
+                name: "Transition",
+                value: code.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENI: &str = r##"<?xml version="1.0"?>
+<EtherCATConfig Version="1.3">
+  <Config>
+    <Master>
+      <Info><Name>Device 1 (EtherCAT)</Name></Info>
+    </Master>
+    <Slave>
+      <Info>
+        <Name>Term 1 (EK1100)</Name>
+        <PhysAddr>1001</PhysAddr>
+        <AutoIncAddr>0</AutoIncAddr>
+        <VendorId>2</VendorId>
+        <ProductCode>72100946</ProductCode>
+        <RevisionNo>1114112</RevisionNo>
+      </Info>
+      <InitCmds>
+        <InitCmd>
+          <Transition>IP</Transition>
+          <Comment>set slave address</Comment>
+          <Cmd>2</Cmd>
+          <Adp>0</Adp>
+          <Ado>16</Ado>
+          <Data>e903</Data>
+          <Cnt>1</Cnt>
+          <Retries>3</Retries>
+        </InitCmd>
+        <InitCmd>
+          <Transition>SO</Transition>
+          <Comment>check power</Comment>
+          <Cmd>4</Cmd>
+          <Adp>1001</Adp>
+          <Ado>3584</Ado>
+          <Data>0000</Data>
+          <Cnt>1</Cnt>
+          <Validate>
+            <Data>0100</Data>
+            <DataMask>ff00</DataMask>
+            <Timeout>100</Timeout>
+          </Validate>
+        </InitCmd>
+      </InitCmds>
+    </Slave>
+    <Slave>
+      <Info>
+        <Name>Drive 2 (AKD)</Name>
+        <PhysAddr>1002</PhysAddr>
+        <AutoIncAddr>65535</AutoIncAddr>
+        <VendorId>#x0000006a</VendorId>
+        <ProductCode>#x00414b44</ProductCode>
+        <RevisionNo>#x00000002</RevisionNo>
+        <SerialNo>1234</SerialNo>
+      </Info>
+      <ProcessData>
+        <Send><BitStart>0</BitStart><BitLength>48</BitLength></Send>
+        <Recv><BitStart>0</BitStart><BitLength>32</BitLength></Recv>
+        <Sm2>
+          <Type>Outputs</Type>
+          <DefaultSize>6</DefaultSize>
+          <StartAddress>#x1100</StartAddress>
+          <ControlByte>#x64</ControlByte>
+          <Enable>true</Enable>
+          <Pdo>#x1600</Pdo>
+        </Sm2>
+        <Sm3>
+          <Type>Inputs</Type>
+          <StartAddress>#x1140</StartAddress>
+          <ControlByte>#x20</ControlByte>
+          <Enable>1</Enable>
+          <Pdo>#x1a00</Pdo>
+        </Sm3>
+        <RxPdo Sm="2">
+          <Index>#x1600</Index>
+          <Name>Outputs</Name>
+          <Entry><Index>#x6040</Index><SubIndex>0</SubIndex><BitLen>16</BitLen><Name>Controlword</Name><DataType>UINT</DataType></Entry>
+          <Entry><Index>#x60ff</Index><SubIndex>0</SubIndex><BitLen>32</BitLen><Name>Target velocity</Name><DataType>DINT</DataType></Entry>
+        </RxPdo>
+        <TxPdo Sm="3">
+          <Index>#x1a00</Index>
+          <Name>Inputs</Name>
+          <Entry><Index>#x6041</Index><SubIndex>0</SubIndex><BitLen>16</BitLen><Name>Statusword</Name><DataType>UINT</DataType></Entry>
+          <Entry><Index>#x0</Index><BitLen>16</BitLen></Entry>
+        </TxPdo>
+      </ProcessData>
+      <Mailbox>
+        <CoE>
+          <InitCmds>
+            <InitCmd>
+              <Transition>PS</Transition>
+              <Comment>clear sm pdos (0x1C12)</Comment>
+              <Ccs>1</Ccs>
+              <Index>#x1c12</Index>
+              <SubIndex>0</SubIndex>
+              <Data>00</Data>
+            </InitCmd>
+            <InitCmd>
+              <Transition>PS</Transition>
+              <Ccs>2</Ccs>
+              <Index>#x1018</Index>
+              <SubIndex>1</SubIndex>
+            </InitCmd>
+            <InitCmd>
+              <Transition>IP</Transition>
+              <Transition>PS</Transition>
+              <Ccs>1</Ccs>
+              <Index>#x1c12</Index>
+              <SubIndex>1</SubIndex>
+              <Data>0016</Data>
+            </InitCmd>
+          </InitCmds>
+        </CoE>
+      </Mailbox>
+      <InitCmds>
+        <InitCmd>
+          <Transition>PS</Transition>
+          <BeforeSlave />
+          <Comment>activate dc</Comment>
+          <Cmd>5</Cmd>
+          <Adp>1002</Adp>
+          <Ado>2432</Ado>
+          <Data>0003</Data>
+          <Cnt>1</Cnt>
+        </InitCmd>
+      </InitCmds>
+      <PreviousPort Selected="1">
+        <Port>B</Port>
+        <PhysAddr>1001</PhysAddr>
+      </PreviousPort>
+      <DC>
+        <ReferenceClock>true</ReferenceClock>
+        <CycleTime0>2000000</CycleTime0>
+        <CycleTime1>0</CycleTime1>
+        <ShiftTime>50000</ShiftTime>
+      </DC>
+    </Slave>
+    <Cyclic>
+      <CycleTime>2000</CycleTime>
+    </Cyclic>
+  </Config>
+</EtherCATConfig>
+"##;
+
+    const EK1100: SlaveIdentity = SlaveIdentity {
+        vendor_id: 2,
+        product_id: 72100946,
+        revision: 1114112,
+        serial: 0,
+    };
+
+    const AKD: SlaveIdentity = SlaveIdentity {
+        vendor_id: 0x6a,
+        product_id: 0x00414b44,
+        revision: 2,
+        serial: 1234,
+    };
+
+    #[test]
+    fn master_and_topology() {
+        let eni = EniFile::parse(ENI).expect("Parse");
+
+        assert_eq!(eni.master_name(), "Device 1 (EtherCAT)");
+        assert_eq!(eni.cycle_time(), Some(Duration::from_millis(2)));
+        assert_eq!(eni.slaves().len(), 2);
+
+        let coupler = eni.slave(0).expect("Coupler");
+        let drive = eni.slave(1).expect("Drive");
+
+        assert_eq!(coupler.name, "Term 1 (EK1100)");
+        assert_eq!(coupler.configured_address, 1001);
+        assert_eq!(coupler.identity, EK1100);
+        assert_eq!(coupler.previous_port, None);
+
+        assert_eq!(drive.position, 1);
+        assert_eq!(drive.identity, AKD);
+        assert_eq!(
+            drive.previous_port,
+            Some(EniPreviousPort {
+                configured_address: 1001,
+                port: 1
+            })
+        );
+    }
+
+    #[test]
+    fn process_data() {
+        let eni = EniFile::parse(ENI).expect("Parse");
+        let drive = eni.slave(1).expect("Drive");
+
+        assert_eq!(
+            drive.outputs,
+            Some(EniPdiRange {
+                bit_start: 0,
+                bit_len: 48
+            })
+        );
+        assert_eq!(
+            drive.inputs,
+            Some(EniPdiRange {
+                bit_start: 0,
+                bit_len: 32
+            })
+        );
+
+        assert_eq!(
+            drive.sync_managers,
+            [
+                EniSyncManager {
+                    index: 2,
+                    kind: EsiSyncManagerType::Outputs,
+                    start_address: Some(0x1100),
+                    default_size: Some(6),
+                    control_byte: Some(0x64),
+                    enable: true,
+                    pdos: vec![0x1600],
+                },
+                EniSyncManager {
+                    index: 3,
+                    kind: EsiSyncManagerType::Inputs,
+                    start_address: Some(0x1140),
+                    default_size: None,
+                    control_byte: Some(0x20),
+                    enable: true,
+                    pdos: vec![0x1a00],
+                }
+            ]
+        );
+
+        assert_eq!(drive.rx_pdos.len(), 1);
+        assert_eq!(drive.rx_pdos[0].bit_len(), 48);
+        assert_eq!(drive.tx_pdos[0].bit_len(), 32);
+    }
+
+    #[test]
+    fn init_cmds() {
+        let eni = EniFile::parse(ENI).expect("Parse");
+        let coupler = eni.slave(0).expect("Coupler");
+        let drive = eni.slave(1).expect("Drive");
+
+        let ip = coupler
+            .register_cmds(Transition::INIT_TO_PRE_OP)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            ip,
+            [&EniRegisterCmd {
+                transitions: vec![Transition::INIT_TO_PRE_OP],
+                before_slave: false,
+                command: 2,
+                adp: 0,
+                ado: 0x0010,
+                data: vec![0xe9, 0x03],
+                expected_wkc: Some(1),
+                retries: 3,
+                validate: None,
+                comment: "set slave address".to_string(),
+            }]
+        );
+
+        let ps = drive
+            .mailbox_cmds(Transition::PRE_OP_TO_SAFE_OP)
+            .collect::<Vec<_>>();
+
+        assert_eq!(ps.len(), 3);
+        assert!(ps[0].download);
+        assert_eq!(ps[0].data, [0x00]);
+        assert!(!ps[1].download);
+        assert_eq!((ps[2].index, ps[2].sub_index), (0x1c12, 1));
+        assert_eq!(ps[2].data, [0x00, 0x16]);
+
+        assert_eq!(drive.mailbox_cmds(Transition::INIT_TO_PRE_OP).count(), 1);
+
+        assert!(
+            drive
+                .register_cmds(Transition::PRE_OP_TO_SAFE_OP)
+                .next()
+                .expect("DC activation")
+                .before_slave
+        );
+    }
+
+    #[test]
+    fn register_cmd_filtering() {
+        let eni = EniFile::parse(ENI).expect("Parse");
+        let coupler = eni.slave(0).expect("Coupler");
+        let drive = eni.slave(1).expect("Drive");
+
+        let set_address = coupler
+            .register_cmds(Transition::INIT_TO_PRE_OP)
+            .next()
+            .expect("Set address");
+
+        assert!(set_address.is_slave_addressed());
+        assert!(set_address.is_managed());
+
+        let check_power = coupler
+            .register_cmds(Transition::SAFE_OP_TO_OP)
+            .next()
+            .expect("Check power");
+
+        assert!(check_power.is_read());
+        assert!(!check_power.is_managed());
+
+        let validate = check_power.validate.as_ref().expect("Validate");
+
+        assert_eq!(validate.timeout, Duration::from_millis(100));
+        assert!(validate.matches(&[0x01, 0x00]));
+        assert!(validate.matches(&[0x01, 0xaa]));
+        assert!(!validate.matches(&[0x02, 0x00]));
+
+        let dc_activation = drive
+            .register_cmds(Transition::PRE_OP_TO_SAFE_OP)
+            .next()
+            .expect("DC activation");
+
+        assert!(dc_activation.is_managed());
+    }
+
+    #[test]
+    fn process_image() {
+        let eni = EniFile::parse(ENI).expect("Parse");
+
+        let range = |bit_start, bit_len| Some(EniPdiRange { bit_start, bit_len });
+
+        // Group PDI starts at a different logical address to the ENI process image
+        assert_eq!(
+            eni.process_image_mismatches(&[(0, None, None), (1, range(64, 32), range(128, 48))]),
+            []
+        );
+
+        let mismatches = eni
+            .process_image_mismatches(&[(0, None, range(0, 8)), (1, range(0, 16), range(8, 48))]);
+
+        assert_eq!(
+            mismatches,
+            [
+                EniProcessImageMismatch {
+                    position: 0,
+                    direction: SignalDirection::Output,
+                    expected: None,
+                    actual: range(0, 8),
+                },
+                EniProcessImageMismatch {
+                    position: 1,
+                    direction: SignalDirection::Input,
+                    expected: range(0, 32),
+                    actual: range(0, 16),
+                },
+                EniProcessImageMismatch {
+                    position: 1,
+                    direction: SignalDirection::Output,
+                    expected: range(0, 48),
+                    actual: range(8, 48),
+                }
+            ]
+        );
+
+        assert_eq!(
+            EniError::ProcessImage(mismatches).to_string(),
+            "process image does not match ENI: position 0: expected no outputs, found bits 0..8; position 1: expected inputs at bits 0..32, found bits 0..16; position 1: expected outputs at bits 0..48, found bits 8..56"
+        );
+    }
+
+    #[test]
+    fn dc() {
+        let eni = EniFile::parse(ENI).expect("Parse");
+
+        assert_eq!(eni.slave(0).expect("Coupler").dc_sync(), None);
+
+        let drive = eni.slave(1).expect("Drive");
+
+        assert!(drive.dc.expect("DC").reference_clock);
+        assert_eq!(
+            drive.dc_sync(),
+            Some(DcSync {
+                assign_activate: 0x0300,
+                sync0_cycle_time: 2_000_000,
+                sync0_shift_time: 50_000,
+                sync1_cycle_time: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn verify_matching() {
+        let eni = EniFile::parse(ENI).expect("Parse");
+
+        // Serial numbers are not compared
+        assert_eq!(
+            eni.verify([EK1100, SlaveIdentity { serial: 99, ..AKD }]),
+            []
+        );
+    }
+
+    #[test]
+    fn verify_mismatches() {
+        let eni = EniFile::parse(ENI).expect("Parse");
+
+        let wrong_revision = SlaveIdentity { revision: 3, ..AKD };
+
+        assert_eq!(
+            eni.verify([EK1100, wrong_revision, EK1100]),
+            [
+                TopologyMismatch {
+                    position: 1,
+                    kind: TopologyMismatchKind::Revision {
+                        expected: 2,
+                        found: wrong_revision
+                    }
+                },
+                TopologyMismatch {
+                    position: 2,
+                    kind: TopologyMismatchKind::Extra { found: EK1100 }
+                }
+            ]
+        );
+
+        let mismatches = eni.verify([EK1100]);
+
+        assert_eq!(
+            mismatches,
+            [TopologyMismatch {
+                position: 1,
+                kind: TopologyMismatchKind::Missing {
+                    expected: ExpectedSlave::from(AKD)
+                }
+            }]
+        );
+
+        assert_eq!(
+            EniError::Mismatch(mismatches).to_string(),
+            "network does not match ENI: position 1: missing slave (vendor 0x0000006a, product 0x00414b44)"
+        );
+    }
+
+    #[test]
+    fn expected_slaves() {
+        let eni = EniFile::parse(ENI).expect("Parse");
+
+        assert_eq!(
+            eni.expected_slaves(),
+            [
+                ExpectedSlave::new(2, 72100946).with_revision(1114112),
+                ExpectedSlave::new(0x6a, 0x00414b44)
+                    .with_revision(2)
+                    .with_parent(0, 1)
+            ]
+        );
+    }
+}
@@ -0,0 +1,1293 @@
+//! Load EtherCAT Slave Information (ESI) XML device descriptions.
+//!
+//! ESI files are provided by device vendors and describe, amongst other things, the PDO mappings,
+//! startup SDOs, object dictionary and DC operation modes supported by a device. An [`EsiDevice`]
+//! can be matched to a discovered slave using its [`SlaveIdentity`], and the information it
+//! contains used to configure the slave during the PRE-OP -> SAFE-OP transition.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ethercrab::{std::esi::EsiFile, Transition};
+//! # async fn configure<'a>(
+//! #     mut slave: ethercrab::SlaveRef<'a, &'a mut ethercrab::Slave>,
+//! # ) -> Result<(), Box<dyn std::error::Error>> {
+//!
+//! let esi = EsiFile::load("./esi/Beckhoff EL31xx.xml")?;
+//!
+//! if let Some(device) = esi.find(&slave.identity()) {
+//!     device.configure_pdos(&mut slave).await?;
+//!     device.configure_dc_sync(&mut slave, "DcSync", 1_000_000)?;
+//!
+//!     device
+//!         .write_init_cmds(&slave, Transition::PRE_OP_TO_SAFE_OP)
+//!         .await?;
+//! }
+//! # Ok(()) }
+//! ```
+
+use super::xml::{
+    attribute_bool, attribute_number, child, child_text, children, decode, optional_number,
+    parse_hex_bytes, parse_number, required_number, required_text,
+};
+use crate::{
+    error::{Error, Item, MailboxError, XmlError},
+    fmt, DcSync, Pdo, PdoAssignment, PdoEntry, Slave, SlaveIdentity, SlaveRef, StateTimeouts,
+    Transition,
+};
+use core::{
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+use roxmltree::{Document, Node};
+use std::path::Path;
+
+/// A parsed ESI file containing one or more device descriptions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiFile {
+    vendor_id: u32,
+    vendor_name: String,
+    devices: Vec<EsiDevice>,
+}
+
+impl EsiFile {
+    /// Parse an ESI document from a string.
+    pub fn parse(xml: &str) -> Result<Self, XmlError> {
+        let doc = Document::parse(xml)?;
+
+        let root = doc.root_element();
+
+        if !root.has_tag_name("EtherCATInfo") {
+            return Err(XmlError::InvalidRoot {
+                expected: "EtherCATInfo",
+                actual: root.tag_name().name().to_string(),
+            });
+        }
+
+        let vendor = child(root, "Vendor").ok_or_else(|| XmlError::Missing {
+            parent: "EtherCATInfo".to_string(),
+            name: "Vendor",
+        })?;
+
+        let vendor_id = required_number(vendor, "Id")?;
+        let vendor_name = child_text(vendor, "Name").unwrap_or_default().to_string();
+
+        let devices = child(root, "Descriptions")
+            .and_then(|descriptions| child(descriptions, "Devices"))
+            .map(|devices| {
+                children(devices, "Device")
+                    .map(|device| EsiDevice::parse(vendor_id, device, root))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(Self {
+            vendor_id,
+            vendor_name,
+            devices,
+        })
+    }
+
+    /// Load and parse an ESI file from disk.
+    ///
+    /// Files encoded as ISO-8859-1, as is common for vendor ESI files, are transcoded to UTF-8.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, XmlError> {
+        let xml = decode(std::fs::read(path)?)?;
+
+        Self::parse(&xml)
+    }
+
+    /// The vendor ID of all devices in this file.
+    pub fn vendor_id(&self) -> u32 {
+        self.vendor_id
+    }
+
+    /// The vendor name, e.g. `Beckhoff Automation GmbH & Co. KG`.
+    pub fn vendor_name(&self) -> &str {
+        &self.vendor_name
+    }
+
+    /// All devices described in this file.
+    pub fn devices(&self) -> &[EsiDevice] {
+        &self.devices
+    }
+
+    /// Find the device description matching the given slave identity.
+    ///
+    /// A description with a matching revision is preferred. If none is found, a description that
+    /// does not specify a revision number is returned, if present.
+    pub fn find(&self, identity: &SlaveIdentity) -> Option<&EsiDevice> {
+        let mut candidates = self
+            .devices
+            .iter()
+            .filter(|device| device.matches(identity));
+
+        let first = candidates.next()?;
+
+        if first.revision.is_some() {
+            return Some(first);
+        }
+
+        candidates
+            .find(|device| device.revision.is_some())
+            .or(Some(first))
+    }
+}
+
+/// A single device description from an ESI file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiDevice {
+    /// Vendor ID.
+    pub vendor_id: u32,
+    /// Product code.
+    pub product_code: u32,
+    /// Revision number. Some descriptions apply to all revisions, in which case this is `None`.
+    pub revision: Option<u32>,
+    /// Device type, e.g. `EL3104`.
+    pub type_name: String,
+    /// Human readable device name.
+    pub name: String,
+    /// Sync manager configuration.
+    pub sync_managers: Vec<EsiSyncManager>,
+    /// Outputs (master to slave) PDOs.
+    pub rx_pdos: Vec<EsiPdo>,
+    /// Inputs (slave to master) PDOs.
+    pub tx_pdos: Vec<EsiPdo>,
+    /// CoE startup commands.
+    pub init_cmds: Vec<EsiInitCmd>,
+    /// CoE object dictionary.
+    pub objects: Vec<EsiObject>,
+    /// Distributed Clocks operation modes.
+    pub dc_op_modes: Vec<EsiDcOpMode>,
+    /// Device-specific state transition timeouts from the `Info/StateMachine/Timeout` element.
+    /// Transitions without a timeout in the ESI file use the default.
+    ///
+    /// These can be applied with [`SlaveRef::set_state_timeouts`].
+    pub state_timeouts: Option<StateTimeouts>,
+}
+
+impl EsiDevice {
+    fn parse(vendor_id: u32, device: Node<'_, '_>, root: Node<'_, '_>) -> Result<Self, XmlError> {
+        let ty = child(device, "Type").ok_or_else(|| XmlError::Missing {
+            parent: "Device".to_string(),
+            name: "Type",
+        })?;
+
+        let product_code =
+            attribute_number(ty, "ProductCode")?.ok_or_else(|| XmlError::Missing {
+                parent: "Type".to_string(),
+                name: "ProductCode",
+            })?;
+        let revision = attribute_number(ty, "RevisionNo")?;
+        let type_name = ty.text().unwrap_or_default().trim().to_string();
+        let name = child_text(device, "Name").unwrap_or_default().to_string();
+
+        let sync_managers = children(device, "Sm")
+            .map(EsiSyncManager::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let rx_pdos = children(device, "RxPdo")
+            .map(EsiPdo::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tx_pdos = children(device, "TxPdo")
+            .map(EsiPdo::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let init_cmds = child(device, "Mailbox")
+            .and_then(|mailbox| child(mailbox, "CoE"))
+            .map(|coe| {
+                children(coe, "InitCmd")
+                    .map(EsiInitCmd::parse)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let dictionary = child(device, "Profile").and_then(|profile| child(profile, "Dictionary"));
+
+        // Data types can be defined per device or, in older files, once for the whole file.
+        let data_types = dictionary
+            .and_then(|dictionary| child(dictionary, "DataTypes"))
+            .or_else(|| {
+                child(root, "Descriptions")
+                    .and_then(|descriptions| child(descriptions, "DataTypes"))
+            });
+
+        let objects = dictionary
+            .and_then(|dictionary| child(dictionary, "Objects"))
+            .map(|objects| {
+                children(objects, "Object")
+                    .map(|object| EsiObject::parse(object, data_types))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let dc_op_modes = child(device, "Dc")
+            .map(|dc| {
+                children(dc, "OpMode")
+                    .map(EsiDcOpMode::parse)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        let state_timeouts = child(device, "Info")
+            .and_then(|info| child(info, "StateMachine"))
+            .and_then(|state_machine| child(state_machine, "Timeout"))
+            .map(parse_state_timeouts)
+            .transpose()?;
+
+        Ok(Self {
+            vendor_id,
+            product_code,
+            revision,
+            type_name,
+            name,
+            sync_managers,
+            rx_pdos,
+            tx_pdos,
+            init_cmds,
+            objects,
+            dc_op_modes,
+            state_timeouts,
+        })
+    }
+
+    /// Whether this description applies to a slave with the given identity.
+    pub fn matches(&self, identity: &SlaveIdentity) -> bool {
+        self.vendor_id == identity.vendor_id
+            && self.product_code == identity.product_id
+            && self
+                .revision
+                .map_or(true, |revision| revision == identity.revision)
+    }
+
+    /// Startup SDOs that should be written during the given state transition, in the order they
+    /// are defined in the ESI file.
+    pub fn init_cmds(&self, transition: Transition) -> impl Iterator<Item = &EsiInitCmd> {
+        self.init_cmds
+            .iter()
+            .filter(move |cmd| cmd.transitions.contains(&transition))
+    }
+
+    /// Outputs PDOs assigned to a sync manager by default.
+    pub fn default_rx_pdos(&self) -> impl Iterator<Item = &EsiPdo> {
+        self.rx_pdos.iter().filter(|pdo| pdo.sync_manager.is_some())
+    }
+
+    /// Inputs PDOs assigned to a sync manager by default.
+    pub fn default_tx_pdos(&self) -> impl Iterator<Item = &EsiPdo> {
+        self.tx_pdos.iter().filter(|pdo| pdo.sync_manager.is_some())
+    }
+
+    /// Find an object in the object dictionary by index.
+    pub fn object(&self, index: u16) -> Option<&EsiObject> {
+        self.objects.iter().find(|object| object.index == index)
+    }
+
+    /// Find a DC operation mode by name, e.g. `DcSync`.
+    pub fn dc_op_mode(&self, name: &str) -> Option<&EsiDcOpMode> {
+        self.dc_op_modes.iter().find(|mode| mode.name == name)
+    }
+
+    /// Use the named DC operation mode for a slave, with the given bus cycle time in nanoseconds.
+    ///
+    /// The configuration is written to the slave when its group transitions from PRE-OP to
+    /// SAFE-OP.
+    pub fn configure_dc_sync<S>(
+        &self,
+        slave: &mut SlaveRef<'_, S>,
+        op_mode: &str,
+        cycle_time: u32,
+    ) -> Result<(), Error>
+    where
+        S: DerefMut<Target = Slave>,
+    {
+        let mode = self.dc_op_mode(op_mode).ok_or(Error::NotFound {
+            item: Item::DcOpMode,
+            index: None,
+        })?;
+
+        slave.set_dc_sync(mode.dc_sync(cycle_time));
+
+        Ok(())
+    }
+
+    /// Assign and map the default PDOs of this device, i.e. all PDOs with a sync manager in the
+    /// ESI file, using [`SlaveRef::configure_pdos`].
+    ///
+    /// The mapping of PDOs marked as fixed is not written. Sync managers without any default PDOs
+    /// are left unchanged.
+    ///
+    /// This method must be called in PRE-OP, and requires the slave to support CoE.
+    pub async fn configure_pdos<S>(&self, slave: &mut SlaveRef<'_, S>) -> Result<(), Error>
+    where
+        S: DerefMut<Target = Slave>,
+    {
+        let pdos = self
+            .default_rx_pdos()
+            .chain(self.default_tx_pdos())
+            .map(|pdo| pdo.index)
+            .collect::<Vec<_>>();
+
+        self.configure_selected_pdos(slave, &pdos).await
+    }
+
+    /// Assign and map the given PDOs of this device, e.g. `&[0x1600, 0x1a01]`, using
+    /// [`SlaveRef::configure_pdos`].
+    ///
+    /// PDOs are assigned to their default sync manager from the ESI file. PDOs without one are
+    /// assigned to the device's first outputs or inputs sync manager. The mapping of PDOs marked as
+    /// fixed is not written. Sync managers without any selected PDOs are left unchanged. PDO and
+    /// entry names are kept for [`SlaveRef::signal_by_name`].
+    ///
+    /// This method must be called in PRE-OP, and requires the slave to support CoE.
+    pub async fn configure_selected_pdos<S>(
+        &self,
+        slave: &mut SlaveRef<'_, S>,
+        pdos: &[u16],
+    ) -> Result<(), Error>
+    where
+        S: DerefMut<Target = Slave>,
+    {
+        let selected = self.select_pdos(pdos)?;
+
+        let entries = selected
+            .iter()
+            .map(|(_, pdo)| {
+                if pdo.fixed {
+                    return Ok(None);
+                }
+
+                pdo.entries
+                    .iter()
+                    .map(PdoEntry::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Some)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut sync_managers: Vec<(u8, Vec<Pdo<'_>>)> = Vec::new();
+
+        for ((sync_manager, pdo), entries) in selected.iter().zip(&entries) {
+            let pdo = match (entries, pdo.name.as_str()) {
+                (Some(entries), "") => Pdo::new(pdo.index, entries),
+                (Some(entries), name) => Pdo::new(pdo.index, entries).with_name(name),
+                (None, _) => Pdo::fixed(pdo.index),
+            };
+
+            match sync_managers.iter_mut().find(|(sm, _)| sm == sync_manager) {
+                Some((_, pdos)) => pdos.push(pdo),
+                None => sync_managers.push((*sync_manager, vec![pdo])),
+            }
+        }
+
+        let assignment = sync_managers
+            .iter()
+            .try_fold(PdoAssignment::new(), |assignment, (sync_manager, pdos)| {
+                assignment.sync_manager(*sync_manager, pdos)
+            })?;
+
+        slave.configure_pdos(&assignment).await
+    }
+
+    /// Find the given PDOs and the sync manager each one is assigned to.
+    fn select_pdos(&self, pdos: &[u16]) -> Result<Vec<(u8, &EsiPdo)>, Error> {
+        let first_sync_manager = |kind| {
+            self.sync_managers
+                .iter()
+                .position(|sm| sm.kind == kind)
+                .and_then(|position| u8::try_from(position).ok())
+        };
+
+        pdos.iter()
+            .enumerate()
+            .map(|(position, index)| {
+                let (pdo, kind) = self
+                    .rx_pdos
+                    .iter()
+                    .map(|pdo| (pdo, EsiSyncManagerType::Outputs))
+                    .chain(
+                        self.tx_pdos
+                            .iter()
+                            .map(|pdo| (pdo, EsiSyncManagerType::Inputs)),
+                    )
+                    .find(|(pdo, _)| pdo.index == *index)
+                    .ok_or(Error::NotFound {
+                        item: Item::Pdo,
+                        index: Some(position),
+                    })?;
+
+                let sync_manager = pdo
+                    .sync_manager
+                    .or_else(|| first_sync_manager(kind))
+                    .ok_or(Error::NotFound {
+                        item: Item::SyncManager,
+                        index: None,
+                    })?;
+
+                Ok((sync_manager, pdo))
+            })
+            .collect()
+    }
+
+    /// Write all startup SDOs for the given transition to a slave in PRE-OP.
+    ///
+    /// Note that only expedited SDO downloads (4 bytes maximum) are currently supported. Complete
+    /// access commands or longer values return [`MailboxError::Unsupported`].
+    pub async fn write_init_cmds<S>(
+        &self,
+        slave: &SlaveRef<'_, S>,
+        transition: Transition,
+    ) -> Result<(), Error>
+    where
+        S: Deref<Target = Slave>,
+    {
+        for cmd in self.init_cmds(transition) {
+            fmt::debug!(
+                "Slave {:#06x} ESI init command {:#06x}:{} ({})",
+                slave.configured_address(),
+                cmd.index,
+                cmd.sub_index,
+                cmd.comment.as_str()
+            );
+
+            if cmd.complete_access || cmd.data.len() > 4 {
+                fmt::error!("Complete access and SDO writes longer than 4 bytes are not supported");
+
+                return Err(Error::Mailbox(MailboxError::Unsupported {
+                    address: cmd.index,
+                    sub_index: cmd.sub_index,
+                }));
+            }
+
+            slave
+                .sdo_write(cmd.index, cmd.sub_index, cmd.data.as_slice())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The function of a sync manager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EsiSyncManagerType {
+    /// Mailbox, master to slave.
+    MailboxOut,
+    /// Mailbox, slave to master.
+    MailboxIn,
+    /// Process data outputs.
+    Outputs,
+    /// Process data inputs.
+    Inputs,
+    /// Unused or unknown.
+    Other(String),
+}
+
+/// Sync manager configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiSyncManager {
+    /// Sync manager function.
+    pub kind: EsiSyncManagerType,
+    /// Physical start address.
+    pub start_address: Option<u16>,
+    /// Default length in bytes.
+    pub default_size: Option<u16>,
+    /// Control register value.
+    pub control_byte: Option<u8>,
+    /// Whether the sync manager is enabled by default.
+    pub enable: bool,
+}
+
+impl EsiSyncManager {
+    pub(crate) fn parse_kind(kind: &str) -> EsiSyncManagerType {
+        match kind.trim() {
+            "MBoxOut" => EsiSyncManagerType::MailboxOut,
+            "MBoxIn" => EsiSyncManagerType::MailboxIn,
+            "Outputs" => EsiSyncManagerType::Outputs,
+            "Inputs" => EsiSyncManagerType::Inputs,
+            other => EsiSyncManagerType::Other(other.to_string()),
+        }
+    }
+
+    fn parse(sm: Node<'_, '_>) -> Result<Self, XmlError> {
+        Ok(Self {
+            kind: Self::parse_kind(sm.text().unwrap_or_default()),
+            start_address: attribute_number(sm, "StartAddress")?,
+            default_size: attribute_number(sm, "DefaultSize")?,
+            control_byte: attribute_number(sm, "ControlByte")?,
+            enable: attribute_bool(sm, "Enable")?,
+        })
+    }
+}
+
+/// A PDO and its mapped entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiPdo {
+    /// PDO index, e.g. `0x1a00`.
+    pub index: u16,
+    /// PDO name.
+    pub name: String,
+    /// The sync manager this PDO is assigned to by default.
+    pub sync_manager: Option<u8>,
+    /// The PDO mapping cannot be changed.
+    pub fixed: bool,
+    /// The PDO must always be assigned.
+    pub mandatory: bool,
+    /// PDOs that cannot be assigned at the same time as this one.
+    pub exclude: Vec<u16>,
+    /// Mapped entries.
+    pub entries: Vec<EsiPdoEntry>,
+}
+
+impl EsiPdo {
+    pub(crate) fn parse(pdo: Node<'_, '_>) -> Result<Self, XmlError> {
+        Ok(Self {
+            index: required_number(pdo, "Index")?,
+            name: child_text(pdo, "Name").unwrap_or_default().to_string(),
+            sync_manager: attribute_number(pdo, "Sm")?,
+            fixed: attribute_bool(pdo, "Fixed")?,
+            mandatory: attribute_bool(pdo, "Mandatory")?,
+            exclude: children(pdo, "Exclude")
+                .map(|exclude| parse_number(exclude.text().unwrap_or_default(), "Exclude"))
+                .collect::<Result<Vec<_>, _>>()?,
+            entries: children(pdo, "Entry")
+                .map(EsiPdoEntry::parse)
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+
+    /// Total length of all mapped entries in bits.
+    pub fn bit_len(&self) -> u32 {
+        self.entries
+            .iter()
+            .map(|entry| u32::from(entry.bit_len))
+            .sum()
+    }
+}
+
+/// An entry mapped into a PDO.
+///
+/// Entries with an index of `0` are padding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiPdoEntry {
+    /// Object index.
+    pub index: u16,
+    /// Object sub-index.
+    pub sub_index: u8,
+    /// Length in bits.
+    pub bit_len: u16,
+    /// Entry name.
+    pub name: String,
+    /// Data type, e.g. `UINT`.
+    pub data_type: Option<String>,
+}
+
+impl EsiPdoEntry {
+    fn parse(entry: Node<'_, '_>) -> Result<Self, XmlError> {
+        Ok(Self {
+            index: required_number(entry, "Index")?,
+            sub_index: optional_number(entry, "SubIndex")?.unwrap_or(0),
+            bit_len: required_number(entry, "BitLen")?,
+            name: child_text(entry, "Name").unwrap_or_default().to_string(),
+            data_type: child_text(entry, "DataType").map(str::to_string),
+        })
+    }
+
+    /// Whether this entry is padding rather than a mapped object.
+    pub fn is_padding(&self) -> bool {
+        self.index == 0
+    }
+
+    /// The value written to a PDO mapping object (e.g. `0x1600:01`) to map this entry.
+    ///
+    /// Returns [`Error::IntegerTypeConversion`] if the entry is longer than the 255 bits a mapping
+    /// can hold.
+    pub fn mapping_value(&self) -> Result<u32, Error> {
+        PdoEntry::try_from(self).map(|entry| entry.mapping())
+    }
+}
+
+impl<'a> TryFrom<&'a EsiPdoEntry> for PdoEntry<'a> {
+    type Error = Error;
+
+    fn try_from(entry: &'a EsiPdoEntry) -> Result<Self, Self::Error> {
+        let bit_len = u8::try_from(entry.bit_len).map_err(|_| Error::IntegerTypeConversion)?;
+
+        let mapped = PdoEntry::new(entry.index, entry.sub_index, bit_len);
+
+        Ok(match entry.name.as_str() {
+            "" => mapped,
+            name => mapped.with_name(name),
+        })
+    }
+}
+
+/// A CoE startup SDO write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiInitCmd {
+    /// The transitions this command should be sent in.
+    pub transitions: Vec<Transition>,
+    /// Object index.
+    pub index: u16,
+    /// Object sub-index.
+    pub sub_index: u8,
+    /// Raw data to write.
+    pub data: Vec<u8>,
+    /// Whether the object should be written with complete access.
+    pub complete_access: bool,
+    /// Description of the command.
+    pub comment: String,
+}
+
+impl EsiInitCmd {
+    fn parse(cmd: Node<'_, '_>) -> Result<Self, XmlError> {
+        let transitions = children(cmd, "Transition")
+            .map(|transition| {
+                let code = transition.text().unwrap_or_default();
+
+                Transition::from_code(code).ok_or_else(|| XmlError::InvalidValue {
+                    name: "Transition",
+                    value: code.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            transitions,
+            index: required_number(cmd, "Index")?,
+            sub_index: optional_number(cmd, "SubIndex")?.unwrap_or(0),
+            data: parse_hex_bytes(child_text(cmd, "Data").unwrap_or_default(), "Data")?,
+            complete_access: attribute_bool(cmd, "CompleteAccess")?,
+            comment: child_text(cmd, "Comment").unwrap_or_default().to_string(),
+        })
+    }
+}
+
+/// An object in a device's CoE object dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiObject {
+    /// Object index.
+    pub index: u16,
+    /// Object name.
+    pub name: String,
+    /// Data type name, e.g. `UDINT` or `DT1018`.
+    pub data_type: String,
+    /// Size of the whole object in bits.
+    pub bit_size: u32,
+    /// Access rights, e.g. `ro` or `rw`.
+    pub access: Option<String>,
+    /// Default value.
+    pub default_data: Option<Vec<u8>>,
+    /// Sub-items for record and array objects. Empty for simple objects.
+    pub sub_items: Vec<EsiSubItem>,
+}
+
+impl EsiObject {
+    fn parse(object: Node<'_, '_>, data_types: Option<Node<'_, '_>>) -> Result<Self, XmlError> {
+        let data_type = required_text(object, "Type")?.to_string();
+        let info = child(object, "Info");
+
+        let mut sub_items = Vec::new();
+
+        if let Some(ty) = data_types.and_then(|types| find_data_type(types, &data_type)) {
+            resolve_sub_items(ty, data_types, &mut sub_items)?;
+        }
+
+        // Per sub-item defaults are listed in order in the object's `Info` element.
+        if let Some(info) = info {
+            for (sub_item, sub_info) in sub_items.iter_mut().zip(children(info, "SubItem")) {
+                sub_item.default_data = default_data(sub_info)?;
+            }
+        }
+
+        Ok(Self {
+            index: required_number(object, "Index")?,
+            name: child_text(object, "Name").unwrap_or_default().to_string(),
+            data_type,
+            bit_size: optional_number(object, "BitSize")?.unwrap_or(0),
+            access: access(object),
+            default_data: info.map(default_data).transpose()?.flatten(),
+            sub_items,
+        })
+    }
+
+    /// Find a sub-item by sub-index.
+    pub fn sub_item(&self, sub_index: u8) -> Option<&EsiSubItem> {
+        self.sub_items
+            .iter()
+            .find(|item| item.sub_index == sub_index)
+    }
+}
+
+/// A sub-item of a record or array object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiSubItem {
+    /// Sub-index.
+    pub sub_index: u8,
+    /// Sub-item name.
+    pub name: String,
+    /// Data type name.
+    pub data_type: String,
+    /// Size in bits.
+    pub bit_size: u32,
+    /// Offset from the start of the object in bits.
+    pub bit_offset: u32,
+    /// Access rights, e.g. `ro` or `rw`.
+    pub access: Option<String>,
+    /// Default value.
+    pub default_data: Option<Vec<u8>>,
+}
+
+/// Parse a `StateMachine/Timeout` element. All values are in milliseconds.
+fn parse_state_timeouts(timeout: Node<'_, '_>) -> Result<StateTimeouts, XmlError> {
+    let millis = |name| optional_number(timeout, name).map(|ms| ms.map(Duration::from_millis));
+
+    let mut timeouts = StateTimeouts::default();
+
+    if let Some(t) = millis("PreopTimeout")? {
+        timeouts.init_to_pre_op = t;
+    }
+
+    if let Some(t) = millis("SafeopOpTimeout")? {
+        timeouts.pre_op_to_safe_op = t;
+        timeouts.safe_op_to_op = t;
+    }
+
+    if let Some(t) = millis("BackToSafeopTimeout")? {
+        timeouts.op_to_safe_op = t;
+    }
+
+    if let Some(t) = millis("BackToInitTimeout")? {
+        timeouts.back_to_init = t;
+    }
+
+    Ok(timeouts)
+}
+
+fn find_data_type<'a, 'input>(types: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    children(types, "DataType").find(|ty| child_text(*ty, "Name") == Some(name))
+}
+
+fn access(node: Node<'_, '_>) -> Option<String> {
+    child(node, "Flags")
+        .and_then(|flags| child_text(flags, "Access"))
+        .map(str::to_string)
+}
+
+fn default_data(info: Node<'_, '_>) -> Result<Option<Vec<u8>>, XmlError> {
+    child(info, "Info")
+        .or(Some(info))
+        .and_then(|info| child_text(info, "DefaultData"))
+        .map(|data| parse_hex_bytes(data, "DefaultData"))
+        .transpose()
+}
+
+/// Flatten the sub-items of a record data type, expanding arrays into one item per element.
+fn resolve_sub_items(
+    ty: Node<'_, '_>,
+    data_types: Option<Node<'_, '_>>,
+    sub_items: &mut Vec<EsiSubItem>,
+) -> Result<(), XmlError> {
+    for item in children(ty, "SubItem") {
+        let name = child_text(item, "Name").unwrap_or_default();
+        let item_type = required_text(item, "Type")?;
+        let bit_size = optional_number(item, "BitSize")?.unwrap_or(0);
+        let bit_offset: u32 = optional_number(item, "BitOffs")?.unwrap_or(0);
+
+        match optional_number(item, "SubIdx")? {
+            Some(sub_index) => sub_items.push(EsiSubItem {
+                sub_index,
+                name: name.to_string(),
+                data_type: item_type.to_string(),
+                bit_size,
+                bit_offset,
+                access: access(item),
+                default_data: None,
+            }),
+            // Array elements don't have a sub-index of their own.
+            None => {
+                let Some(array) = data_types.and_then(|types| find_data_type(types, item_type))
+                else {
+                    continue;
+                };
+
+                let Some(array_info) = child(array, "ArrayInfo") else {
+                    continue;
+                };
+
+                let base_type = required_text(array, "BaseType")?;
+                let lower: u8 = optional_number(array_info, "LBound")?.unwrap_or(1);
+                let elements: u8 = required_number(array_info, "Elements")?;
+
+                let element_bits = data_types
+                    .and_then(|types| find_data_type(types, base_type))
+                    .map(|base| optional_number::<u32>(base, "BitSize"))
+                    .transpose()?
+                    .flatten()
+                    .unwrap_or_else(|| bit_size / u32::from(elements.max(1)));
+
+                for i in 0..elements {
+                    sub_items.push(EsiSubItem {
+                        sub_index: lower.saturating_add(i),
+                        name: format!("{} {}", name, u32::from(lower) + u32::from(i)),
+                        data_type: base_type.to_string(),
+                        bit_size: element_bits,
+                        bit_offset: bit_offset + element_bits * u32::from(i),
+                        access: access(item),
+                        default_data: None,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A Distributed Clocks operation mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiDcOpMode {
+    /// Operation mode name, e.g. `DcSync`.
+    pub name: String,
+    /// Human readable description.
+    pub description: String,
+    /// Value written to the DC activation registers `0x0980:0x0981`.
+    pub assign_activate: u16,
+    /// Fixed SYNC0 cycle time in nanoseconds, or `0` to derive it from the bus cycle time.
+    pub cycle_time_sync0: u32,
+    /// SYNC0 cycle time factor relative to the bus cycle time.
+    pub cycle_time_sync0_factor: i32,
+    /// SYNC0 shift time in nanoseconds.
+    pub shift_time_sync0: u32,
+    /// Fixed SYNC1 cycle time in nanoseconds, or `0` to derive it from the SYNC0 cycle time.
+    pub cycle_time_sync1: u32,
+    /// SYNC1 cycle time factor relative to the SYNC0 cycle time.
+    pub cycle_time_sync1_factor: i32,
+    /// SYNC1 shift time in nanoseconds.
+    pub shift_time_sync1: u32,
+}
+
+impl EsiDcOpMode {
+    fn parse(mode: Node<'_, '_>) -> Result<Self, XmlError> {
+        let factor = |name: &'static str| -> Result<i32, XmlError> {
+            Ok(child(mode, name)
+                .map(|node| attribute_number(node, "Factor"))
+                .transpose()?
+                .flatten()
+                .unwrap_or(0))
+        };
+
+        Ok(Self {
+            name: child_text(mode, "Name").unwrap_or_default().to_string(),
+            description: child_text(mode, "Desc").unwrap_or_default().to_string(),
+            assign_activate: optional_number(mode, "AssignActivate")?.unwrap_or(0),
+            cycle_time_sync0: optional_number(mode, "CycleTimeSync0")?.unwrap_or(0),
+            cycle_time_sync0_factor: factor("CycleTimeSync0")?,
+            shift_time_sync0: optional_number(mode, "ShiftTimeSync0")?.unwrap_or(0),
+            cycle_time_sync1: optional_number(mode, "CycleTimeSync1")?.unwrap_or(0),
+            cycle_time_sync1_factor: factor("CycleTimeSync1")?,
+            shift_time_sync1: optional_number(mode, "ShiftTimeSync1")?.unwrap_or(0),
+        })
+    }
+
+    /// Compute the DC sync configuration for this operation mode with the given bus cycle time in
+    /// nanoseconds.
+    pub fn dc_sync(&self, cycle_time: u32) -> DcSync {
+        fn scale(fixed: u32, factor: i32, base: u32) -> u32 {
+            if fixed != 0 {
+                return fixed;
+            }
+
+            match factor {
+                0 => 0,
+                factor if factor > 0 => base.saturating_mul(factor.unsigned_abs()),
+                factor => base / factor.unsigned_abs(),
+            }
+        }
+
+        let sync0_cycle_time = scale(
+            self.cycle_time_sync0,
+            self.cycle_time_sync0_factor,
+            cycle_time,
+        );
+
+        DcSync {
+            assign_activate: self.assign_activate,
+            sync0_cycle_time,
+            sync0_shift_time: self.shift_time_sync0,
+            sync1_cycle_time: scale(
+                self.cycle_time_sync1,
+                self.cycle_time_sync1_factor,
+                sync0_cycle_time,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ESI: &str = r##"<?xml version="1.0" encoding="ISO-8859-1"?>
+<EtherCATInfo Version="1.6">
+  <Vendor>
+    <Id>#x00000002</Id>
+    <Name>Beckhoff Automation GmbH &amp; Co. KG</Name>
+  </Vendor>
+  <Descriptions>
+    <Devices>
+      <Device Physics="YY">
+        <Type ProductCode="#x0c1e3052" RevisionNo="#x00140000">EL3102</Type>
+        <Name LcId="1033">EL3102 2Ch. Ana. Input +/-10V, Diff.</Name>
+        <Info>
+          <StateMachine>
+            <Timeout>
+              <PreopTimeout>2000</PreopTimeout>
+              <SafeopOpTimeout>9000</SafeopOpTimeout>
+              <BackToInitTimeout>5000</BackToInitTimeout>
+              <BackToSafeopTimeout>200</BackToSafeopTimeout>
+            </Timeout>
+          </StateMachine>
+        </Info>
+        <Profile>
+          <Dictionary>
+            <DataTypes>
+              <DataType><Name>UDINT</Name><BitSize>32</BitSize></DataType>
+              <DataType><Name>USINT</Name><BitSize>8</BitSize></DataType>
+              <DataType>
+                <Name>DT1018</Name>
+                <BitSize>144</BitSize>
+                <SubItem>
+                  <SubIdx>0</SubIdx><Name>SubIndex 000</Name><Type>USINT</Type>
+                  <BitSize>8</BitSize><BitOffs>0</BitOffs><Flags><Access>ro</Access></Flags>
+                </SubItem>
+                <SubItem>
+                  <SubIdx>1</SubIdx><Name>Vendor ID</Name><Type>UDINT</Type>
+                  <BitSize>32</BitSize><BitOffs>16</BitOffs><Flags><Access>ro</Access></Flags>
+                </SubItem>
+              </DataType>
+              <DataType>
+                <Name>DT1C13ARR</Name>
+                <BaseType>UINT</BaseType>
+                <BitSize>32</BitSize>
+                <ArrayInfo><LBound>1</LBound><Elements>2</Elements></ArrayInfo>
+              </DataType>
+              <DataType>
+                <Name>DT1C13</Name>
+                <BitSize>48</BitSize>
+                <SubItem>
+                  <SubIdx>0</SubIdx><Name>SubIndex 000</Name><Type>USINT</Type>
+                  <BitSize>8</BitSize><BitOffs>0</BitOffs><Flags><Access>rw</Access></Flags>
+                </SubItem>
+                <SubItem>
+                  <Name>Elements</Name><Type>DT1C13ARR</Type>
+                  <BitSize>32</BitSize><BitOffs>16</BitOffs><Flags><Access>rw</Access></Flags>
+                </SubItem>
+              </DataType>
+            </DataTypes>
+            <Objects>
+              <Object>
+                <Index>#x1018</Index><Name>Identity</Name><Type>DT1018</Type><BitSize>144</BitSize>
+                <Info>
+                  <SubItem><Name>SubIndex 000</Name><Info><DefaultData>04</DefaultData></Info></SubItem>
+                  <SubItem><Name>Vendor ID</Name><Info><DefaultData>02000000</DefaultData></Info></SubItem>
+                </Info>
+              </Object>
+              <Object>
+                <Index>#x1c13</Index><Name>TxPDO assign</Name><Type>DT1C13</Type><BitSize>48</BitSize>
+              </Object>
+              <Object>
+                <Index>#x8000</Index><Name>Filter</Name><Type>UDINT</Type><BitSize>32</BitSize>
+                <Info><DefaultData>01000000</DefaultData></Info>
+                <Flags><Access>rw</Access></Flags>
+              </Object>
+            </Objects>
+          </Dictionary>
+        </Profile>
+        <Sm DefaultSize="128" StartAddress="#x1000" ControlByte="#x26" Enable="1">MBoxOut</Sm>
+        <Sm DefaultSize="128" StartAddress="#x1080" ControlByte="#x22" Enable="1">MBoxIn</Sm>
+        <Sm StartAddress="#x1100" ControlByte="#x24" Enable="0">Outputs</Sm>
+        <Sm DefaultSize="4" StartAddress="#x1180" ControlByte="#x20" Enable="1">Inputs</Sm>
+        <TxPdo Fixed="1" Mandatory="1" Sm="3">
+          <Index>#x1a00</Index>
+          <Name>AI Standard Channel 1</Name>
+          <Exclude>#x1a01</Exclude>
+          <Entry><Index>#x6000</Index><SubIndex>1</SubIndex><BitLen>1</BitLen><Name>Underrange</Name><DataType>BOOL</DataType></Entry>
+          <Entry><Index>#x0</Index><BitLen>15</BitLen></Entry>
+          <Entry><Index>#x6000</Index><SubIndex>17</SubIndex><BitLen>16</BitLen><Name>Value</Name><DataType>INT</DataType></Entry>
+        </TxPdo>
+        <TxPdo Fixed="1">
+          <Index>#x1a01</Index>
+          <Name>AI Compact Channel 1</Name>
+          <Exclude>#x1a00</Exclude>
+          <Entry><Index>#x6000</Index><SubIndex>17</SubIndex><BitLen>16</BitLen><Name>Value</Name><DataType>INT</DataType></Entry>
+        </TxPdo>
+        <Mailbox DataLinkLayer="1">
+          <CoE SdoInfo="1" PdoAssign="1" PdoConfig="1">
+            <InitCmd>
+              <Transition>PS</Transition>
+              <Index>#x8000</Index>
+              <SubIndex>6</SubIndex>
+              <Data>01</Data>
+              <Comment>Enable filter</Comment>
+            </InitCmd>
+            <InitCmd CompleteAccess="1">
+              <Transition>PS</Transition>
+              <Transition>SO</Transition>
+              <Index>#x1c13</Index>
+              <Data>0100001a</Data>
+            </InitCmd>
+          </CoE>
+        </Mailbox>
+        <Dc>
+          <OpMode>
+            <Name>Synchron</Name>
+            <Desc>SM-Synchron</Desc>
+            <AssignActivate>#x0</AssignActivate>
+          </OpMode>
+          <OpMode>
+            <Name>DcSync</Name>
+            <Desc>DC-Synchron</Desc>
+            <AssignActivate>#x700</AssignActivate>
+            <CycleTimeSync0 Factor="1">0</CycleTimeSync0>
+            <ShiftTimeSync0>20000</ShiftTimeSync0>
+            <CycleTimeSync1 Factor="2">0</CycleTimeSync1>
+          </OpMode>
+        </Dc>
+      </Device>
+      <Device>
+        <Type ProductCode="#x0c1e3052">EL3102</Type>
+        <Name>EL3102 any revision</Name>
+      </Device>
+    </Devices>
+  </Descriptions>
+</EtherCATInfo>
+"##;
+
+    fn identity(revision: u32) -> SlaveIdentity {
+        SlaveIdentity {
+            vendor_id: 0x2,
+            product_id: 0x0c1e3052,
+            revision,
+            serial: 0,
+        }
+    }
+
+    #[test]
+    fn find_device() {
+        let esi = EsiFile::parse(ESI).expect("Parse");
+
+        assert_eq!(esi.vendor_id(), 2);
+        assert_eq!(esi.vendor_name(), "Beckhoff Automation GmbH & Co. KG");
+        assert_eq!(esi.devices().len(), 2);
+
+        let exact = esi.find(&identity(0x00140000)).expect("Exact");
+
+        assert_eq!(exact.type_name, "EL3102");
+        assert_eq!(exact.revision, Some(0x00140000));
+
+        let fallback = esi.find(&identity(0x00150000)).expect("Fallback");
+
+        assert_eq!(fallback.name, "EL3102 any revision");
+
+        assert_eq!(
+            esi.find(&SlaveIdentity {
+                vendor_id: 0x3,
+                ..identity(0x00140000)
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn pdos_and_sync_managers() {
+        let esi = EsiFile::parse(ESI).expect("Parse");
+        let device = &esi.devices()[0];
+
+        assert_eq!(device.sync_managers.len(), 4);
+        assert_eq!(
+            device.sync_managers[3],
+            EsiSyncManager {
+                kind: EsiSyncManagerType::Inputs,
+                start_address: Some(0x1180),
+                default_size: Some(4),
+                control_byte: Some(0x20),
+                enable: true,
+            }
+        );
+        assert!(!device.sync_managers[2].enable);
+
+        assert!(device.rx_pdos.is_empty());
+        assert_eq!(device.tx_pdos.len(), 2);
+
+        let defaults = device.default_tx_pdos().collect::<Vec<_>>();
+
+        assert_eq!(defaults.len(), 1);
+
+        let pdo = defaults[0];
+
+        assert_eq!(pdo.index, 0x1a00);
+        assert_eq!(pdo.sync_manager, Some(3));
+        assert!(pdo.fixed);
+        assert!(pdo.mandatory);
+        assert_eq!(pdo.exclude, [0x1a01]);
+        assert_eq!(pdo.bit_len(), 32);
+        assert!(pdo.entries[1].is_padding());
+        assert_eq!(pdo.entries[2].mapping_value(), Ok(0x6000_1110));
+
+        let long = EsiPdoEntry {
+            bit_len: 256,
+            ..pdo.entries[2].clone()
+        };
+
+        assert_eq!(long.mapping_value(), Err(Error::IntegerTypeConversion));
+        assert_eq!(pdo.entries[2].data_type.as_deref(), Some("INT"));
+    }
+
+    #[test]
+    fn select_pdos() {
+        let esi = EsiFile::parse(ESI).expect("Parse");
+        let device = &esi.devices()[0];
+
+        let selected = device.select_pdos(&[0x1a00, 0x1a01]).expect("Select");
+
+        // `0x1a01` has no default sync manager so is assigned to the inputs SM.
+        assert_eq!(
+            selected
+                .iter()
+                .map(|(sm, pdo)| (*sm, pdo.index))
+                .collect::<Vec<_>>(),
+            [(3, 0x1a00), (3, 0x1a01)]
+        );
+
+        assert_eq!(
+            device.select_pdos(&[0x1a00, 0x1600]),
+            Err(Error::NotFound {
+                item: Item::Pdo,
+                index: Some(1)
+            })
+        );
+    }
+
+    #[test]
+    fn init_cmds() {
+        let esi = EsiFile::parse(ESI).expect("Parse");
+        let device = &esi.devices()[0];
+
+        let ps = device
+            .init_cmds(Transition::PRE_OP_TO_SAFE_OP)
+            .collect::<Vec<_>>();
+
+        assert_eq!(ps.len(), 2);
+        assert_eq!(ps[0].index, 0x8000);
+        assert_eq!(ps[0].sub_index, 6);
+        assert_eq!(ps[0].data, [0x01]);
+        assert_eq!(ps[0].comment, "Enable filter");
+        assert!(ps[1].complete_access);
+        assert_eq!(ps[1].data, [0x01, 0x00, 0x00, 0x1a]);
+
+        assert_eq!(device.init_cmds(Transition::SAFE_OP_TO_OP).count(), 1);
+        assert_eq!(device.init_cmds(Transition::INIT_TO_PRE_OP).count(), 0);
+    }
+
+    #[test]
+    fn object_dictionary() {
+        let esi = EsiFile::parse(ESI).expect("Parse");
+        let device = &esi.devices()[0];
+
+        let identity = device.object(0x1018).expect("Identity");
+
+        assert_eq!(identity.sub_items.len(), 2);
+        assert_eq!(
+            identity.sub_item(1),
+            Some(&EsiSubItem {
+                sub_index: 1,
+                name: "Vendor ID".to_string(),
+                data_type: "UDINT".to_string(),
+                bit_size: 32,
+                bit_offset: 16,
+                access: Some("ro".to_string()),
+                default_data: Some(vec![0x02, 0x00, 0x00, 0x00]),
+            })
+        );
+
+        let assign = device.object(0x1c13).expect("Assign");
+
+        assert_eq!(
+            assign
+                .sub_items
+                .iter()
+                .map(|item| (item.sub_index, item.bit_offset))
+                .collect::<Vec<_>>(),
+            [(0, 0), (1, 16), (2, 32)]
+        );
+        assert_eq!(assign.sub_items[2].name, "Elements 2");
+
+        let filter = device.object(0x8000).expect("Filter");
+
+        assert!(filter.sub_items.is_empty());
+        assert_eq!(filter.access.as_deref(), Some("rw"));
+        assert_eq!(filter.default_data, Some(vec![0x01, 0x00, 0x00, 0x00]));
+    }
+
+    #[test]
+    fn dc_op_modes() {
+        let esi = EsiFile::parse(ESI).expect("Parse");
+        let device = &esi.devices()[0];
+
+        assert_eq!(device.dc_op_modes.len(), 2);
+
+        let mode = device.dc_op_mode("DcSync").expect("DcSync");
+
+        assert_eq!(
+            mode.dc_sync(1_000_000),
+            DcSync {
+                assign_activate: 0x0700,
+                sync0_cycle_time: 1_000_000,
+                sync0_shift_time: 20_000,
+                sync1_cycle_time: 2_000_000,
+            }
+        );
+
+        assert!(!device
+            .dc_op_mode("Synchron")
+            .expect("Synchron")
+            .dc_sync(1_000_000)
+            .is_enabled());
+    }
+
+    #[test]
+    fn state_timeouts() {
+        let esi = EsiFile::parse(ESI).expect("Parse");
+
+        let timeouts = esi.devices()[0].state_timeouts.expect("Timeouts");
+
+        assert_eq!(timeouts.init_to_pre_op, Duration::from_millis(2000));
+        assert_eq!(timeouts.pre_op_to_safe_op, Duration::from_millis(9000));
+        assert_eq!(timeouts.safe_op_to_op, Duration::from_millis(9000));
+        assert_eq!(timeouts.op_to_safe_op, Duration::from_millis(200));
+        assert_eq!(timeouts.back_to_init, Duration::from_millis(5000));
+        assert_eq!(
+            timeouts.back_to_pre_op,
+            StateTimeouts::default().back_to_pre_op
+        );
+
+        assert_eq!(esi.devices()[1].state_timeouts, None);
+    }
+
+    #[test]
+    fn invalid_root() {
+        assert_eq!(
+            EsiFile::parse("<EtherCATConfig />"),
+            Err(XmlError::InvalidRoot {
+                expected: "EtherCATInfo",
+                actual: "EtherCATConfig".to_string()
+            })
+        );
+    }
+}
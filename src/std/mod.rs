@@ -1,11 +1,17 @@
 //! Items required for running in `std` environments.
 
+mod eeprom_cache;
+pub mod eni;
+pub mod esi;
 #[cfg(unix)]
 mod unix;
 #[cfg(target_os = "windows")]
 mod windows;
+mod xml;
 
 #[cfg(target_os = "windows")]
 pub use self::windows::tx_rx_task;
 #[cfg(unix)]
 pub use unix::tx_rx_task;
+
+pub use self::eeprom_cache::EepromImageCache;
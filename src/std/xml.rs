@@ -0,0 +1,252 @@
+//! Helpers for reading values out of EtherCAT XML files (ESI, ENI).
+
+use crate::error::XmlError;
+use roxmltree::Node;
+
+/// Decode the raw contents of an XML file.
+///
+/// Vendor files are commonly encoded as ISO-8859-1 (Latin-1), as given by the XML declaration, and
+/// are transcoded to UTF-8. All other files must be UTF-8, optionally starting with a byte order
+/// mark.
+pub(crate) fn decode(mut bytes: Vec<u8>) -> Result<String, XmlError> {
+    if bytes.starts_with(UTF8_BOM) {
+        bytes.drain(..UTF8_BOM.len());
+    }
+
+    let latin1 = declared_encoding(&bytes).map_or(false, |encoding| {
+        ["ISO-8859-1", "ISO_8859-1", "latin1", "latin-1"]
+            .iter()
+            .any(|name| encoding.eq_ignore_ascii_case(name))
+    });
+
+    if latin1 {
+        // Latin-1 code points map one to one onto the first 256 Unicode characters.
+        return Ok(bytes.into_iter().map(char::from).collect());
+    }
+
+    String::from_utf8(bytes).map_err(|_| XmlError::Io(std::io::ErrorKind::InvalidData))
+}
+
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// The `encoding` value of an XML declaration, e.g. `<?xml version="1.0" encoding="ISO-8859-1"?>`.
+fn declared_encoding(bytes: &[u8]) -> Option<&str> {
+    if !bytes.starts_with(b"<?xml") {
+        return None;
+    }
+
+    let end = bytes.windows(2).position(|window| window == b"?>")?;
+
+    // The declaration itself is always ASCII.
+    let declaration = core::str::from_utf8(&bytes[..end]).ok()?;
+
+    let (_, rest) = declaration.split_once("encoding")?;
+    let rest = rest.trim_start().strip_prefix('=')?.trim_start();
+
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+
+    rest[1..].split(quote).next()
+}
+
+/// Find the first child element with the given tag name.
+pub(crate) fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+/// Iterate over all child elements with the given tag name.
+pub(crate) fn children<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |n| n.has_tag_name(name))
+}
+
+/// Get the trimmed text of the first child element with the given tag name.
+pub(crate) fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name).and_then(|n| n.text()).map(str::trim)
+}
+
+/// Get the trimmed text of a required child element.
+pub(crate) fn required_text<'a>(
+    node: Node<'a, '_>,
+    name: &'static str,
+) -> Result<&'a str, XmlError> {
+    child_text(node, name).ok_or_else(|| XmlError::Missing {
+        parent: node.tag_name().name().to_string(),
+        name,
+    })
+}
+
+/// Parse a required child element as a number.
+pub(crate) fn required_number<T>(node: Node<'_, '_>, name: &'static str) -> Result<T, XmlError>
+where
+    T: TryFrom<i64>,
+{
+    parse_number(required_text(node, name)?, name)
+}
+
+/// Parse an optional child element as a number.
+pub(crate) fn optional_number<T>(
+    node: Node<'_, '_>,
+    name: &'static str,
+) -> Result<Option<T>, XmlError>
+where
+    T: TryFrom<i64>,
+{
+    child_text(node, name)
+        .map(|value| parse_number(value, name))
+        .transpose()
+}
+
+/// Parse an optional attribute as a number.
+pub(crate) fn attribute_number<T>(
+    node: Node<'_, '_>,
+    name: &'static str,
+) -> Result<Option<T>, XmlError>
+where
+    T: TryFrom<i64>,
+{
+    node.attribute(name)
+        .map(|value| parse_number(value, name))
+        .transpose()
+}
+
+/// Parse an optional boolean attribute, defaulting to `false`.
+pub(crate) fn attribute_bool(node: Node<'_, '_>, name: &'static str) -> Result<bool, XmlError> {
+    node.attribute(name)
+        .map_or(Ok(false), |value| parse_bool(value, name))
+}
+
+/// Parse an optional boolean child element, defaulting to `false`.
+///
+/// An empty element, e.g. `<ReferenceClock />`, is treated as `true`.
+pub(crate) fn child_bool(node: Node<'_, '_>, name: &'static str) -> Result<bool, XmlError> {
+    match child(node, name) {
+        Some(node) => match node.text().map(str::trim) {
+            None | Some("") => Ok(true),
+            Some(value) => parse_bool(value, name),
+        },
+        None => Ok(false),
+    }
+}
+
+fn parse_bool(value: &str, name: &'static str) -> Result<bool, XmlError> {
+    match value.trim() {
+        "0" | "false" => Ok(false),
+        "1" | "true" => Ok(true),
+        other => Err(XmlError::InvalidValue {
+            name,
+            value: other.to_string(),
+        }),
+    }
+}
+
+/// Parse a number in one of the formats used by EtherCAT XML files.
+///
+/// Hexadecimal values are written as `#x1a00`, but `0x1a00` is also accepted. All other values are
+/// parsed as (possibly negative) decimal numbers.
+pub(crate) fn parse_number<T>(value: &str, name: &'static str) -> Result<T, XmlError>
+where
+    T: TryFrom<i64>,
+{
+    let value = value.trim();
+
+    let invalid = || XmlError::InvalidValue {
+        name,
+        value: value.to_string(),
+    };
+
+    let hex = value
+        .strip_prefix("#x")
+        .or_else(|| value.strip_prefix("0x"))
+        .or_else(|| value.strip_prefix("#X"))
+        .or_else(|| value.strip_prefix("0X"));
+
+    let raw = match hex {
+        Some(hex) => i64::from_str_radix(hex, 16).map_err(|_| invalid())?,
+        None => value.parse::<i64>().map_err(|_| invalid())?,
+    };
+
+    T::try_from(raw).map_err(|_| invalid())
+}
+
+/// Parse a string of hex digits such as `0a1b2c` into bytes.
+pub(crate) fn parse_hex_bytes(value: &str, name: &'static str) -> Result<Vec<u8>, XmlError> {
+    let value = value.trim();
+
+    let invalid = || XmlError::InvalidValue {
+        name,
+        value: value.to_string(),
+    };
+
+    if value.len() % 2 != 0 {
+        return Err(invalid());
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            value
+                .get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse_number::<u16>("#x1A00", "n"), Ok(0x1a00));
+        assert_eq!(parse_number::<u16>("0x1a00", "n"), Ok(0x1a00));
+        assert_eq!(parse_number::<u32>(" 1000 ", "n"), Ok(1000));
+        assert_eq!(parse_number::<i16>("-1", "n"), Ok(-1));
+        assert_eq!(
+            parse_number::<u8>("#x100", "n"),
+            Err(XmlError::InvalidValue {
+                name: "n",
+                value: "#x100".to_string()
+            })
+        );
+        assert!(parse_number::<u8>("abc", "n").is_err());
+    }
+
+    #[test]
+    fn decode_latin1() {
+        let xml = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><Name>Ger\xe4t</Name>".to_vec();
+
+        assert_eq!(
+            decode(xml),
+            Ok(
+                "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><Name>Ger\u{e4}t</Name>"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn decode_utf8() {
+        let xml = "\u{feff}<?xml version='1.0' encoding='UTF-8'?><Name>Ger\u{e4}t</Name>";
+
+        assert_eq!(
+            decode(xml.as_bytes().to_vec()),
+            Ok("<?xml version='1.0' encoding='UTF-8'?><Name>Ger\u{e4}t</Name>".to_string())
+        );
+
+        assert_eq!(
+            decode(b"<Name>Ger\xe4t</Name>".to_vec()),
+            Err(XmlError::Io(std::io::ErrorKind::InvalidData))
+        );
+    }
+
+    #[test]
+    fn hex_bytes() {
+        assert_eq!(parse_hex_bytes("00021a", "d"), Ok(vec![0x00, 0x02, 0x1a]));
+        assert_eq!(parse_hex_bytes("", "d"), Ok(vec![]));
+        assert!(parse_hex_bytes("001", "d").is_err());
+        assert!(parse_hex_bytes("zz", "d").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::error::Error;
+use crate::{error::Error, SlaveState, Transition};
 use core::time::Duration;
 use embassy_futures::select::{select, Either};
 
@@ -35,10 +35,11 @@ where
 /// Timeout configuration for the EtherCrab master.
 #[derive(Copy, Clone, Debug)]
 pub struct Timeouts {
-    /// How long to wait for a slave state change, e.g. SAFE-OP to OP.
+    /// How long to wait for each slave state change, e.g. SAFE-OP to OP.
     ///
-    /// This timeout is global for all state transitions.
-    pub state_transition: Duration,
+    /// These timeouts can be overridden for individual slave devices with
+    /// [`SlaveRef::set_state_timeouts`](crate::SlaveRef::set_state_timeouts).
+    pub state_transitions: StateTimeouts,
 
     /// How long to wait for a PDU response.
     pub pdu: Duration,
@@ -59,6 +60,10 @@ pub struct Timeouts {
 
     /// How long to wait for a response to be read from the slave's response mailbox.
     pub mailbox_response: Duration,
+
+    /// How long to keep sending an FoE packet again while the slave responds with busy, e.g. while
+    /// it writes a firmware image to flash.
+    pub foe_busy: Duration,
 }
 
 impl Timeouts {
@@ -70,12 +75,116 @@ impl Timeouts {
 impl Default for Timeouts {
     fn default() -> Self {
         Self {
-            state_transition: Duration::from_millis(5000),
+            state_transitions: StateTimeouts::default(),
             pdu: Duration::from_micros(30_000),
             eeprom: Duration::from_millis(10),
             wait_loop_delay: Duration::from_millis(0),
             mailbox_echo: Duration::from_millis(100),
             mailbox_response: Duration::from_millis(1000),
+            foe_busy: Duration::from_millis(10_000),
         }
     }
 }
+
+/// How long to wait for each EtherCAT state machine transition.
+///
+/// Defaults are the same as those used by TwinCAT, and the same values can be specified for a
+/// device in the `StateMachine/Timeout` element of its ESI file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StateTimeouts {
+    /// INIT to PRE-OP. Defaults to 3 seconds.
+    pub init_to_pre_op: Duration,
+
+    /// PRE-OP to SAFE-OP. Defaults to 10 seconds.
+    pub pre_op_to_safe_op: Duration,
+
+    /// SAFE-OP to OP. Defaults to 10 seconds.
+    pub safe_op_to_op: Duration,
+
+    /// OP to SAFE-OP. Defaults to 200 milliseconds.
+    pub op_to_safe_op: Duration,
+
+    /// SAFE-OP or OP back to PRE-OP. Defaults to 5 seconds.
+    pub back_to_pre_op: Duration,
+
+    /// Any state back to INIT. Defaults to 5 seconds.
+    pub back_to_init: Duration,
+
+    /// Any other transition, e.g. INIT to BOOT. Defaults to 5 seconds.
+    pub other: Duration,
+}
+
+impl Default for StateTimeouts {
+    fn default() -> Self {
+        Self {
+            init_to_pre_op: Duration::from_millis(3000),
+            pre_op_to_safe_op: Duration::from_millis(10_000),
+            safe_op_to_op: Duration::from_millis(10_000),
+            op_to_safe_op: Duration::from_millis(200),
+            back_to_pre_op: Duration::from_millis(5000),
+            back_to_init: Duration::from_millis(5000),
+            other: Duration::from_millis(5000),
+        }
+    }
+}
+
+impl StateTimeouts {
+    /// Get the timeout for the given transition.
+    pub fn transition(&self, transition: Transition) -> Duration {
+        match (transition.from, transition.to) {
+            (SlaveState::Init, SlaveState::PreOp) => self.init_to_pre_op,
+            (SlaveState::PreOp, SlaveState::SafeOp) => self.pre_op_to_safe_op,
+            (SlaveState::SafeOp, SlaveState::Op) => self.safe_op_to_op,
+            (SlaveState::Op, SlaveState::SafeOp) => self.op_to_safe_op,
+            (SlaveState::SafeOp | SlaveState::Op, SlaveState::PreOp) => self.back_to_pre_op,
+            (_, SlaveState::Init) => self.back_to_init,
+            // The current state is unknown, e.g. it is a combination of multiple slaves' states.
+            (SlaveState::None | SlaveState::Other(_), to) => self.for_state(to),
+            _ => self.other,
+        }
+    }
+
+    /// Get the longest timeout of any transition into the given state, for when the state being
+    /// transitioned from is not known.
+    pub fn for_state(&self, to: SlaveState) -> Duration {
+        match to {
+            SlaveState::PreOp => self.init_to_pre_op.max(self.back_to_pre_op),
+            SlaveState::SafeOp => self.pre_op_to_safe_op.max(self.op_to_safe_op),
+            SlaveState::Op => self.safe_op_to_op,
+            SlaveState::Init => self.back_to_init,
+            _ => self.other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transition_timeouts() {
+        let timeouts = StateTimeouts::default();
+
+        assert_eq!(
+            timeouts.transition(Transition::INIT_TO_PRE_OP),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            timeouts.transition(Transition::OP_TO_SAFE_OP),
+            Duration::from_millis(200)
+        );
+        assert_eq!(
+            timeouts.transition(Transition::new(SlaveState::Op, SlaveState::PreOp)),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            timeouts.transition(Transition::new(SlaveState::Op, SlaveState::Init)),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            timeouts.transition(Transition::new(SlaveState::Other(0x06), SlaveState::SafeOp)),
+            Duration::from_secs(10)
+        );
+    }
+}
@@ -0,0 +1,706 @@
+//! Physical network topology, e.g. to compare the actual wiring of a network against drawings.
+
+use crate::{
+    command::Command,
+    dl_status::DlStatus,
+    error::{Error, Item},
+    fmt,
+    register::RegisterAddress,
+    slave::Slave,
+    Client, PortLinkStatus, SlaveGroup, SlaveIdentity,
+};
+use core::fmt::{Display, Formatter, Write};
+
+/// A slave device in a [`NetworkTopology`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopologyNode {
+    /// Position of the slave device in the network, starting at `0` for the device closest to the
+    /// master.
+    pub index: usize,
+    /// Configured station address.
+    pub configured_address: u16,
+    /// Configured station alias, or `0` if the slave has none.
+    pub alias: u16,
+    /// Human readable name.
+    pub name: heapless::String<64>,
+    /// Vendor ID, product ID, etc.
+    pub identity: SlaveIdentity,
+    /// The EtherCAT port number the slave receives frames from the master on.
+    pub entry_port: usize,
+    /// Network propagation delay from the first slave to this slave in nanoseconds. This is `0`
+    /// for slaves that do not support distributed clocks.
+    pub propagation_delay: u32,
+    /// Index of the slave this slave is connected to, or `None` if it is connected to the master.
+    parent: Option<usize>,
+    /// The index of the downstream slave connected to each port, by EtherCAT port number.
+    downstream: [Option<usize>; 4],
+}
+
+impl TopologyNode {
+    fn new(slave: &Slave) -> Self {
+        let mut downstream = [None; 4];
+
+        for port in slave.ports.0.iter().filter(|port| port.active) {
+            if let Some(d) = downstream.get_mut(port.number) {
+                *d = port.downstream_to;
+            }
+        }
+
+        let entry_port = slave
+            .ports
+            .0
+            .iter()
+            .filter(|port| port.active)
+            .min_by_key(|port| port.dc_receive_time)
+            .map(|port| port.number)
+            .unwrap_or(0);
+
+        Self {
+            index: slave.index,
+            configured_address: slave.configured_address,
+            alias: slave.alias,
+            name: slave.name.clone(),
+            identity: slave.identity,
+            entry_port,
+            propagation_delay: slave.propagation_delay,
+            parent: slave.parent_index,
+            downstream,
+        }
+    }
+}
+
+/// A cable between two slave devices, or between the master and the first slave, in a
+/// [`NetworkTopology`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TopologyEdge {
+    /// Index of the upstream slave, or `None` for the master.
+    pub parent: Option<usize>,
+    /// EtherCAT port number on the upstream slave, or `None` for the master.
+    pub parent_port: Option<usize>,
+    /// Index of the downstream slave.
+    pub child: usize,
+    /// EtherCAT port number on the downstream slave.
+    pub child_port: usize,
+    /// Propagation delay between the two slaves in nanoseconds, or `0` if either slave does not
+    /// support distributed clocks.
+    pub propagation_delay: u32,
+}
+
+/// The likely location of a broken cable, found by
+/// [`NetworkTopology::locate_cable_breaks`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CableBreak {
+    /// Index of the last reachable slave before the break, or `None` if the master cannot reach
+    /// the first slave.
+    pub parent: Option<usize>,
+    /// EtherCAT port number on the last reachable slave, or `None` for the master.
+    pub parent_port: Option<usize>,
+    /// Index of the first unreachable slave after the break.
+    pub child: usize,
+    /// Current link status of the port on the last reachable slave, or `None` for the master.
+    ///
+    /// A port with no link points to a broken cable or a slave that has lost power. A port that
+    /// still has a link points to a fault in the slave after the break.
+    pub port_status: Option<PortLinkStatus>,
+}
+
+impl Display for CableBreak {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match (self.parent, self.parent_port) {
+            (Some(parent), Some(port)) => write!(f, "slave #{} port {}", parent, port)?,
+            _ => f.write_str("master")?,
+        }
+
+        write!(f, " -> slave #{}", self.child)?;
+
+        if let Some(status) = self.port_status {
+            f.write_str(if status.link {
+                " (link up)"
+            } else {
+                " (no link)"
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The physical layout of an EtherCAT network: its slave devices and the cables between them.
+///
+/// Slave devices are added a group at a time with [`add_group`](NetworkTopology::add_group). Once
+/// every group is added, the topology covers the whole network. Connections are those found
+/// during [`Client::init`](crate::Client::init), so slaves connected or reconnected after init are
+/// shown where they were found at init.
+///
+/// The topology can be rendered as a [Graphviz](https://graphviz.org/) graph with
+/// [`dot`](NetworkTopology::dot), or as JSON with [`json`](NetworkTopology::json). After a
+/// communication failure, [`locate_cable_breaks`](NetworkTopology::locate_cable_breaks) compares
+/// the topology against the slaves that can still be reached.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ethercrab::{Client, ClientConfig, NetworkTopology, PduStorage, Timeouts};
+///
+/// const MAX_SLAVES: usize = 16;
+///
+/// static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+///
+/// # async fn topology() -> Result<(), ethercrab::error::Error> {
+/// let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+///
+/// let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+///
+/// let group = client.init_single_group::<MAX_SLAVES, 64>().await?;
+///
+/// let mut topology = NetworkTopology::<MAX_SLAVES>::new();
+///
+/// topology.add_group(&group)?;
+///
+/// std::fs::write("network.dot", topology.dot().to_string()).expect("write graph");
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkTopology<const MAX_SLAVES: usize> {
+    nodes: heapless::Vec<TopologyNode, MAX_SLAVES>,
+}
+
+impl<const MAX_SLAVES: usize> Default for NetworkTopology<MAX_SLAVES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const MAX_SLAVES: usize> NetworkTopology<MAX_SLAVES> {
+    /// Create an empty topology.
+    pub const fn new() -> Self {
+        Self {
+            nodes: heapless::Vec::new(),
+        }
+    }
+
+    /// Add all slave devices in the given group to the topology.
+    ///
+    /// Slaves of hot connect segments that were not found during init are skipped. Returns
+    /// [`Error::Borrow`] if a slave in the group is already borrowed, or [`Error::Capacity`] if the
+    /// topology cannot hold any more slaves.
+    pub fn add_group<const G: usize, const MAX_PDI: usize, S, const MAX_SIGNALS: usize>(
+        &mut self,
+        group: &SlaveGroup<G, MAX_PDI, S, MAX_SIGNALS>,
+    ) -> Result<(), Error> {
+        group.add_to_topology(self)
+    }
+
+    /// Add a single slave device, keeping nodes in network order.
+    pub(crate) fn push(&mut self, slave: &Slave) -> Result<(), Error> {
+        let node = TopologyNode::new(slave);
+
+        let position = self
+            .nodes
+            .iter()
+            .position(|existing| existing.index > node.index)
+            .unwrap_or(self.nodes.len());
+
+        self.nodes
+            .insert(position, node)
+            .map_err(|_| Error::Capacity(Item::Slave))
+    }
+
+    /// Slave devices in the topology, in network order.
+    pub fn nodes(&self) -> &[TopologyNode] {
+        &self.nodes
+    }
+
+    /// Get the slave device at the given position in the network, if it is in the topology.
+    pub fn node(&self, index: usize) -> Option<&TopologyNode> {
+        self.nodes.iter().find(|node| node.index == index)
+    }
+
+    /// Find where the network has been broken since init, e.g. after working counter errors or
+    /// timeouts in the cyclic process data exchange.
+    ///
+    /// The slave devices that can still be reached are found with auto increment addressing and
+    /// matched against the topology by their configured address. Every cable from a reachable
+    /// slave to an unreachable slave is returned, along with the current link status of the
+    /// reachable slave's port. An empty list means every slave in the topology is reachable.
+    ///
+    /// Slaves behind a break are not reported separately, so each break is only reported once.
+    /// Breaks upstream of slaves that are missing from the topology cannot be found.
+    pub async fn locate_cable_breaks(
+        &self,
+        client: &Client<'_>,
+    ) -> Result<heapless::Vec<CableBreak, MAX_SLAVES>, Error> {
+        let mut statuses = heapless::Vec::<Option<DlStatus>, MAX_SLAVES>::new();
+
+        // Same length as `nodes` so this can't overflow
+        let _ = statuses.resize(self.nodes.len(), None);
+
+        for position in 0..client.count_slaves().await? {
+            let address = Command::aprd(position, RegisterAddress::ConfiguredStationAddress.into())
+                .wrap(client)
+                .receive::<u16>()
+                .await?;
+
+            let Some(node) = self
+                .nodes
+                .iter()
+                .position(|node| node.configured_address == address)
+            else {
+                fmt::debug!(
+                    "Device {:#06x} at position {} is not in the topology",
+                    address,
+                    position
+                );
+
+                continue;
+            };
+
+            statuses[node] = Some(
+                Command::aprd(position, RegisterAddress::DlStatus.into())
+                    .wrap(client)
+                    .receive::<DlStatus>()
+                    .await?,
+            );
+        }
+
+        let breaks = self.find_breaks(&statuses);
+
+        for cable_break in breaks.iter() {
+            let name = |index| {
+                self.node(index)
+                    .map(|node| node.name.as_str())
+                    .unwrap_or("")
+            };
+
+            fmt::warn!(
+                "Cable break {}: {} -> {}",
+                cable_break,
+                cable_break.parent.map(name).unwrap_or("master"),
+                name(cable_break.child)
+            );
+        }
+
+        Ok(breaks)
+    }
+
+    /// Find cables from reachable to unreachable slaves, given the current DL status of each node,
+    /// or `None` if it can't be reached.
+    fn find_breaks(&self, statuses: &[Option<DlStatus>]) -> heapless::Vec<CableBreak, MAX_SLAVES> {
+        let status_of = |index| {
+            self.nodes
+                .iter()
+                .position(|node| node.index == index)
+                .and_then(|node| statuses.get(node).copied().flatten())
+        };
+
+        self.edges()
+            .filter(|edge| status_of(edge.child).is_none())
+            .filter_map(|edge| {
+                let port_status = match (edge.parent, edge.parent_port) {
+                    // An unreachable parent means the break is further upstream
+                    (Some(parent), Some(port)) => Some(status_of(parent)?.port(port)),
+                    _ => None,
+                };
+
+                Some(CableBreak {
+                    parent: edge.parent,
+                    parent_port: edge.parent_port,
+                    child: edge.child,
+                    port_status,
+                })
+            })
+            .collect()
+    }
+
+    /// Cables between slave devices in the topology, in network order of the downstream slave.
+    ///
+    /// Connections to slaves that have not been added to the topology are skipped.
+    pub fn edges(&self) -> impl Iterator<Item = TopologyEdge> + '_ {
+        self.nodes.iter().filter_map(|child| {
+            let (parent, parent_port, propagation_delay) = match child.parent {
+                None => (None, None, child.propagation_delay),
+                Some(parent_index) => {
+                    let parent = self.nodes.iter().find(|node| node.index == parent_index)?;
+
+                    let port = parent
+                        .downstream
+                        .iter()
+                        .position(|downstream| *downstream == Some(child.index))?;
+
+                    (
+                        Some(parent_index),
+                        Some(port),
+                        child
+                            .propagation_delay
+                            .saturating_sub(parent.propagation_delay),
+                    )
+                }
+            };
+
+            Some(TopologyEdge {
+                parent,
+                parent_port,
+                child: child.index,
+                child_port: child.entry_port,
+                propagation_delay,
+            })
+        })
+    }
+
+    /// Render the topology as an undirected [Graphviz](https://graphviz.org/) DOT graph.
+    ///
+    /// Each slave is labelled with its position, name, address, alias and identity. Cables are
+    /// labelled with the port numbers at each end and their propagation delay.
+    pub fn dot(&self) -> impl Display + '_ {
+        Dot(self)
+    }
+
+    /// Render the topology as a JSON object with `nodes` and `edges` arrays.
+    ///
+    /// Field names match those of [`TopologyNode`] and [`TopologyEdge`], with the node's identity
+    /// flattened into `vendor_id`, `product_id`, `revision` and `serial` fields. The master is
+    /// represented by a `null` parent.
+    pub fn json(&self) -> impl Display + '_ {
+        Json(self)
+    }
+}
+
+/// Write a string with `"` and `\` escaped, for use in DOT or JSON strings.
+fn write_escaped(f: &mut Formatter<'_>, s: &str) -> core::fmt::Result {
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => {
+                f.write_char('\\')?;
+                f.write_char(c)?;
+            }
+            c if c.is_control() => write!(f, "\\u{:04x}", u32::from(c))?,
+            c => f.write_char(c)?,
+        }
+    }
+
+    Ok(())
+}
+
+struct Dot<'a, const MAX_SLAVES: usize>(&'a NetworkTopology<MAX_SLAVES>);
+
+impl<'a, const MAX_SLAVES: usize> Display for Dot<'a, MAX_SLAVES> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("graph ethercat {\n")?;
+        f.write_str("    node [shape=box];\n")?;
+        f.write_str("    master [label=\"Master\", shape=ellipse];\n")?;
+
+        for node in self.0.nodes.iter() {
+            write!(f, "    slave_{} [label=\"#{} ", node.index, node.index)?;
+            write_escaped(f, &node.name)?;
+            writeln!(
+                f,
+                "\\n{:#06x}, alias {:#06x}\\nvendor {:#010x}, product {:#010x}, rev {}, serial {}\"];",
+                node.configured_address,
+                node.alias,
+                node.identity.vendor_id,
+                node.identity.product_id,
+                node.identity.revision,
+                node.identity.serial
+            )?;
+        }
+
+        for edge in self.0.edges() {
+            match (edge.parent, edge.parent_port) {
+                (Some(parent), Some(port)) => write!(
+                    f,
+                    "    slave_{} -- slave_{} [taillabel=\"{}\", ",
+                    parent, edge.child, port
+                )?,
+                _ => write!(f, "    master -- slave_{} [", edge.child)?,
+            }
+
+            writeln!(
+                f,
+                "headlabel=\"{}\", label=\"{} ns\"];",
+                edge.child_port, edge.propagation_delay
+            )?;
+        }
+
+        f.write_str("}\n")
+    }
+}
+
+struct Json<'a, const MAX_SLAVES: usize>(&'a NetworkTopology<MAX_SLAVES>);
+
+impl<'a, const MAX_SLAVES: usize> Display for Json<'a, MAX_SLAVES> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        /// `null` or a number.
+        struct Nullable(Option<usize>);
+
+        impl Display for Nullable {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                match self.0 {
+                    Some(value) => write!(f, "{}", value),
+                    None => f.write_str("null"),
+                }
+            }
+        }
+
+        f.write_str("{\"nodes\":[")?;
+
+        for (i, node) in self.0.nodes.iter().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+
+            write!(
+                f,
+                "{{\"index\":{},\"configured_address\":{},\"alias\":{},\"name\":\"",
+                node.index, node.configured_address, node.alias
+            )?;
+            write_escaped(f, &node.name)?;
+            write!(
+                f,
+                "\",\"vendor_id\":{},\"product_id\":{},\"revision\":{},\"serial\":{},\"entry_port\":{},\"propagation_delay\":{}}}",
+                node.identity.vendor_id,
+                node.identity.product_id,
+                node.identity.revision,
+                node.identity.serial,
+                node.entry_port,
+                node.propagation_delay
+            )?;
+        }
+
+        f.write_str("],\"edges\":[")?;
+
+        for (i, edge) in self.0.edges().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+
+            write!(
+                f,
+                "{{\"parent\":{},\"parent_port\":{},\"child\":{},\"child_port\":{},\"propagation_delay\":{}}}",
+                Nullable(edge.parent),
+                Nullable(edge.parent_port),
+                edge.child,
+                edge.child_port,
+                edge.propagation_delay
+            )?;
+        }
+
+        f.write_str("]}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slave::ports::tests::make_ports;
+
+    /// EK1100 coupler with an EL2004 and an EK1122 junction, with a slave on the junction's port
+    /// 3.
+    fn network() -> NetworkTopology<8> {
+        let mut coupler = Slave {
+            index: 0,
+            configured_address: 0x1000,
+            name: "EK1100".try_into().unwrap(),
+            identity: SlaveIdentity {
+                vendor_id: 2,
+                product_id: 0x044c2c52,
+                revision: 0x00110000,
+                serial: 0,
+            },
+            ports: make_ports(true, false, true, false),
+            ..Slave::default()
+        };
+
+        let mut junction = Slave {
+            index: 1,
+            configured_address: 0x1001,
+            alias: 0x10,
+            name: "EK1122 \"junction\"".try_into().unwrap(),
+            ports: make_ports(true, true, true, false),
+            parent_index: Some(0),
+            propagation_delay: 140,
+            ..Slave::default()
+        };
+
+        let branch = Slave {
+            index: 2,
+            configured_address: 0x1002,
+            name: "EL2004".try_into().unwrap(),
+            ports: make_ports(true, false, false, false),
+            parent_index: Some(1),
+            propagation_delay: 290,
+            ..Slave::default()
+        };
+
+        coupler.ports.assign_next_downstream_port(1);
+        junction.ports.assign_next_downstream_port(2);
+
+        let mut topology = NetworkTopology::new();
+
+        // Out of order to check nodes are sorted
+        topology.push(&branch).unwrap();
+        topology.push(&coupler).unwrap();
+        topology.push(&junction).unwrap();
+
+        topology
+    }
+
+    #[test]
+    fn edges() {
+        let topology = network();
+
+        assert_eq!(
+            topology
+                .nodes()
+                .iter()
+                .map(|node| node.index)
+                .collect::<Vec<_>>(),
+            [0, 1, 2]
+        );
+
+        assert_eq!(
+            topology.edges().collect::<Vec<_>>(),
+            [
+                TopologyEdge {
+                    parent: None,
+                    parent_port: None,
+                    child: 0,
+                    child_port: 0,
+                    propagation_delay: 0
+                },
+                TopologyEdge {
+                    parent: Some(0),
+                    parent_port: Some(1),
+                    child: 1,
+                    child_port: 0,
+                    propagation_delay: 140
+                },
+                TopologyEdge {
+                    parent: Some(1),
+                    parent_port: Some(3),
+                    child: 2,
+                    child_port: 0,
+                    propagation_delay: 150
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_parent() {
+        let mut topology = network();
+
+        topology.nodes.remove(1);
+
+        assert_eq!(
+            topology.edges().map(|edge| edge.child).collect::<Vec<_>>(),
+            [0]
+        );
+    }
+
+    #[test]
+    fn cable_break() {
+        let topology = network();
+
+        let reachable = DlStatus {
+            pdi_operational: true,
+            watchdog_ok: true,
+            extended_link_detection: false,
+            link_port0: true,
+            link_port1: true,
+            link_port2: false,
+            link_port3: false,
+            loopback_port0: false,
+            signal_port0: true,
+            loopback_port1: false,
+            signal_port1: true,
+            loopback_port2: true,
+            signal_port2: false,
+            loopback_port3: true,
+            signal_port3: false,
+        };
+
+        assert_eq!(
+            topology.find_breaks(&[Some(reachable), Some(reachable), Some(reachable)]),
+            []
+        );
+
+        let breaks = topology.find_breaks(&[Some(reachable), Some(reachable), None]);
+
+        assert_eq!(
+            breaks,
+            [CableBreak {
+                parent: Some(1),
+                parent_port: Some(3),
+                child: 2,
+                port_status: Some(PortLinkStatus {
+                    link: false,
+                    loop_closed: true,
+                    communication: false
+                })
+            }]
+        );
+        assert_eq!(
+            breaks[0].to_string(),
+            "slave #1 port 3 -> slave #2 (no link)"
+        );
+
+        // Only the first break is reported
+        assert_eq!(
+            topology
+                .find_breaks(&[Some(reachable), None, None])
+                .iter()
+                .map(|cable_break| cable_break.child)
+                .collect::<Vec<_>>(),
+            [1]
+        );
+
+        let breaks = topology.find_breaks(&[None, None, None]);
+
+        assert_eq!(breaks.len(), 1);
+        assert_eq!(breaks[0].to_string(), "master -> slave #0");
+    }
+
+    #[test]
+    fn dot() {
+        let dot = network().dot().to_string();
+
+        assert!(dot.starts_with("graph ethercat {\n"));
+        assert!(dot
+            .contains("    slave_1 [label=\"#1 EK1122 \\\"junction\\\"\\n0x1001, alias 0x0010\\n"));
+        assert!(dot.contains("    master -- slave_0 [headlabel=\"0\", label=\"0 ns\"];\n"));
+        assert!(dot.contains(
+            "    slave_1 -- slave_2 [taillabel=\"3\", headlabel=\"0\", label=\"150 ns\"];\n"
+        ));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn json() {
+        let mut topology = NetworkTopology::<8>::new();
+
+        topology
+            .push(&Slave {
+                index: 0,
+                configured_address: 0x1000,
+                name: "EK1100".try_into().unwrap(),
+                ports: make_ports(true, false, false, false),
+                ..Slave::default()
+            })
+            .unwrap();
+
+        assert_eq!(
+            topology.json().to_string(),
+            concat!(
+                "{\"nodes\":[{\"index\":0,\"configured_address\":4096,\"alias\":0,\"name\":\"EK1100\",",
+                "\"vendor_id\":0,\"product_id\":0,\"revision\":0,\"serial\":0,\"entry_port\":0,",
+                "\"propagation_delay\":0}],",
+                "\"edges\":[{\"parent\":null,\"parent_port\":null,\"child\":0,\"child_port\":0,",
+                "\"propagation_delay\":0}]}"
+            )
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Sync manager (process data) and PDI watchdogs.
+
+use crate::{
+    error::Error,
+    fmt,
+    register::RegisterAddress,
+    slave::{Slave, SlaveRef},
+    Client,
+};
+use core::time::Duration;
+
+/// Watchdog divider set by the ESC on power up, giving a watchdog time base of 100 us.
+const DEFAULT_DIVIDER: u16 = 2498;
+
+/// Duration of one watchdog divider tick.
+const DIVIDER_TICK_NS: u64 = 40;
+
+/// Watchdog configuration for a slave device.
+///
+/// The sync manager watchdog resets a slave's outputs to a safe state if no process data has been
+/// written to it within the timeout, e.g. because the master has stopped. The PDI watchdog checks
+/// communication between the ESC and the slave's application controller.
+///
+/// Watchdogs left as `None` keep the slave's current configuration, which is `100 ms` for both
+/// watchdogs on most slaves. A timeout of zero disables the watchdog, which can be useful when
+/// debugging the master.
+///
+/// Both watchdogs share a time base set by the ESC's watchdog divider. The divider is kept at its
+/// default of 100 us for timeouts up to 6.5 s, and increased for longer timeouts. An increased
+/// divider also lengthens a watchdog left as `None`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Watchdog {
+    /// Sync manager (process data) watchdog timeout.
+    pub sync_manager: Option<Duration>,
+    /// Process data interface (PDI) watchdog timeout.
+    pub pdi: Option<Duration>,
+}
+
+impl Watchdog {
+    /// Set both watchdogs to the given timeout.
+    pub const fn new(timeout: Duration) -> Self {
+        Self {
+            sync_manager: Some(timeout),
+            pdi: Some(timeout),
+        }
+    }
+
+    /// Disable both watchdogs.
+    pub const fn disabled() -> Self {
+        Self::new(Duration::ZERO)
+    }
+
+    /// Set the sync manager watchdog timeout.
+    pub const fn with_sync_manager(self, timeout: Duration) -> Self {
+        Self {
+            sync_manager: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Set the PDI watchdog timeout.
+    pub const fn with_pdi(self, timeout: Duration) -> Self {
+        Self {
+            pdi: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Compute the watchdog divider and the sync manager and PDI watchdog register values for this
+    /// configuration.
+    fn registers(&self) -> Result<(u16, Option<u16>, Option<u16>), Error> {
+        let longest = self
+            .sync_manager
+            .into_iter()
+            .chain(self.pdi)
+            .max()
+            .unwrap_or_default();
+
+        let longest =
+            u64::try_from(longest.as_nanos()).map_err(|_| Error::IntegerTypeConversion)?;
+
+        // Time base is `(divider + 2) * 40 ns`. Find the smallest divider that fits the longest
+        // timeout into a `u16` register.
+        let min_divider = longest
+            .div_ceil(u64::from(u16::MAX) * DIVIDER_TICK_NS)
+            .saturating_sub(2);
+
+        let divider = u16::try_from(min_divider)
+            .map_err(|_| Error::IntegerTypeConversion)?
+            .max(DEFAULT_DIVIDER);
+
+        let base = (u64::from(divider) + 2) * DIVIDER_TICK_NS;
+
+        let ticks = |timeout: Option<Duration>| {
+            timeout
+                .map(|timeout| {
+                    let ticks = (timeout.as_nanos() as u64).div_ceil(base);
+
+                    u16::try_from(ticks).map_err(|_| Error::IntegerTypeConversion)
+                })
+                .transpose()
+        };
+
+        Ok((divider, ticks(self.sync_manager)?, ticks(self.pdi)?))
+    }
+}
+
+/// Sync manager and PDI watchdog status of a slave device.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WatchdogStatus {
+    /// Slave address.
+    pub configured_address: u16,
+    /// `false` if the sync manager watchdog has expired, so the slave's outputs have been reset.
+    /// `true` if the watchdog is running or disabled.
+    pub sync_manager_ok: bool,
+    /// The number of times the sync manager watchdog has expired, saturating at `255`.
+    pub sync_manager_expirations: u8,
+    /// The number of times the PDI watchdog has expired, saturating at `255`.
+    pub pdi_expirations: u8,
+}
+
+impl<'a, S> SlaveRef<'a, S> {
+    /// Read the sync manager and PDI watchdog status of this slave device.
+    pub async fn watchdog_status(&self) -> Result<WatchdogStatus, Error> {
+        let status = self
+            .read(RegisterAddress::SyncManagerWatchdogStatus)
+            .receive::<u16>()
+            .await?;
+
+        let [sync_manager_expirations, pdi_expirations] = self
+            .read(RegisterAddress::SyncManagerWatchdogCounter)
+            .receive::<[u8; 2]>()
+            .await?;
+
+        Ok(WatchdogStatus {
+            configured_address: self.configured_address,
+            sync_manager_ok: status & 1 == 1,
+            sync_manager_expirations,
+            pdi_expirations,
+        })
+    }
+}
+
+/// Write a slave device's watchdog configuration, falling back to its group's configuration if
+/// the slave has none.
+pub(crate) async fn configure_watchdog(
+    client: &Client<'_>,
+    slave: &Slave,
+    group: Option<Watchdog>,
+) -> Result<(), Error> {
+    let Some(watchdog) = slave.config.watchdog.or(group) else {
+        return Ok(());
+    };
+
+    let (divider, sync_manager, pdi) = watchdog.registers()?;
+
+    fmt::debug!(
+        "Slave {:#06x} watchdog divider {}, SM {:?}, PDI {:?}",
+        slave.configured_address,
+        divider,
+        sync_manager,
+        pdi
+    );
+
+    let sl = SlaveRef::new(client, slave.configured_address, ());
+
+    sl.write(RegisterAddress::WatchdogDivider)
+        .send(divider)
+        .await?;
+
+    if let Some(pdi) = pdi {
+        sl.write(RegisterAddress::PdiWatchdog).send(pdi).await?;
+    }
+
+    if let Some(sync_manager) = sync_manager {
+        sl.write(RegisterAddress::SyncManagerWatchdog)
+            .send(sync_manager)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_divider() {
+        assert_eq!(Watchdog::default().registers(), Ok((2498, None, None)));
+
+        assert_eq!(
+            Watchdog::default()
+                .with_sync_manager(Duration::from_millis(100))
+                .registers(),
+            Ok((2498, Some(1000), None))
+        );
+
+        assert_eq!(
+            Watchdog::new(Duration::from_micros(150)).registers(),
+            Ok((2498, Some(2), Some(2)))
+        );
+
+        assert_eq!(
+            Watchdog::disabled().registers(),
+            Ok((2498, Some(0), Some(0)))
+        );
+    }
+
+    #[test]
+    fn long_timeout() {
+        let (divider, sync_manager, pdi) = Watchdog::default()
+            .with_sync_manager(Duration::from_secs(10))
+            .with_pdi(Duration::from_millis(100))
+            .registers()
+            .unwrap();
+
+        // 10 s / 65535 = 152.6 us time base
+        assert_eq!(divider, 3813);
+
+        let base = Duration::from_nanos((u64::from(divider) + 2) * DIVIDER_TICK_NS);
+
+        assert!(base * u32::from(sync_manager.unwrap()) >= Duration::from_secs(10));
+        assert!(base * u32::from(pdi.unwrap()) >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn too_long() {
+        assert_eq!(
+            Watchdog::new(Duration::from_secs(200)).registers(),
+            Err(Error::IntegerTypeConversion)
+        );
+    }
+}